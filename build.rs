@@ -1,8 +1,14 @@
+use std::cell::RefCell;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use serde_json::Value;
 
+thread_local! {
+    /// The references currently being parsed, see [parse_schema].
+    static REF_STACK: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 enum RustType {
     Bool,
@@ -40,7 +46,7 @@ impl PartialOrd for Version {
 fn parse_version(s: &str) -> Version {
     let clean = s.trim_start_matches('v');
     let parts: Vec<&str> = clean.split('.').collect();
-    let major = parts.get(0).and_then(|p| p.parse().ok()).unwrap_or(0);
+    let major = parts.first().and_then(|p| p.parse().ok()).unwrap_or(0);
     let minor = parts.get(1).and_then(|p| p.parse().ok()).unwrap_or(0);
     let patch = parts.get(2).and_then(|p| p.parse().ok()).unwrap_or(0);
     Version {
@@ -53,122 +59,270 @@ fn parse_version(s: &str) -> Version {
 
 struct GeneratedComponent {
     version_mod: String,
+    root_name: String,
     type_names: Vec<String>,
 }
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=schemas/minecraftBedrock/schema/item");
+    println!("cargo:rerun-if-changed=schemas/minecraftBedrock/schema/block");
+    println!("cargo:rerun-if-changed=schemas/minecraftBedrock/schema/entity");
+    println!("cargo:rerun-if-changed=schemas/minecraftBedrock/schema/general/vanilla");
+
+    println!("cargo:rerun-if-changed=schemas/minecraftBedrock/schema/molang");
 
     generate_schemas("item".to_string());
     generate_schemas("block".to_string());
+    generate_schemas("entity".to_string());
+    generate_molang_functions();
+    generate_vanilla_lists();
 }
 
-fn generate_schemas(ty: String) {
+fn generate_vanilla_lists() {
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
-    let base_path = Path::new(&manifest_dir).join(format!("schemas/minecraftBedrock/schema/{}", ty));
+    let base_path = Path::new(&manifest_dir).join("schemas/minecraftBedrock/schema/general/vanilla");
     let out_dir = env::var("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join(format!("{}/components.rs", ty));
-
-    _ = fs::create_dir_all(&dest_path.parent().unwrap());
+    let dest_path = Path::new(&out_dir).join("core/vanilla.rs");
+
+    _ = fs::create_dir_all(dest_path.parent().unwrap());
+
+    let lists = [
+        ("ITEM_TEXTURES", "itemTexture.json", ""),
+        ("TERRAIN_TEXTURES", "terrainTexture.json", ""),
+        ("BIOME_TAGS", "biomeTagEnum.json", ""),
+        ("ITEMS", "identifiers.json", "/definitions/prefixed_item_identifiers"),
+        ("BLOCKS", "identifiers.json", "/definitions/prefixed_block_identifiers"),
+        ("ENTITIES", "identifiers.json", "/definitions/prefixed_entity_identifiers"),
+        ("BIOMES", "identifiers.json", "/definitions/prefixed_biome_identifiers"),
+        ("LOOT_TABLES", "paths.json", "/definitions/loot_table_paths"),
+        ("GEOMETRIES", "geometry.json", ""),
+        ("CLIENT_ANIMATIONS", "clientAnimation.json", ""),
+        ("CLIENT_ANIMATION_CONTROLLERS", "clientAnimationController.json", ""),
+        ("SOUND_DEFINITIONS", "soundDefinition.json", ""),
+        ("BLOCK_SOUNDS", "blockSound.json", ""),
+        ("FEATURES", "identifiers.json", "/definitions/feature_identifiers"),
+        ("FOGS", "identifiers.json", "/definitions/fog_identifiers"),
+    ];
 
-    let version_dirs = find_version_dirs(&base_path);
     let mut generated_code = String::new();
 
     generated_code.push_str("// This file is auto-generated by build.rs. Do not edit manually.\n\n");
 
-    let mut active_components: std::collections::HashMap<String, GeneratedComponent> = std::collections::HashMap::new();
+    for (name, file, pointer) in lists {
+        let content = fs::read_to_string(base_path.join(file)).unwrap();
+        let schema_val: Value = serde_json::from_str(&strip_comments(&content)).unwrap();
 
-    for (version, components_path) in version_dirs {
-        let mod_name = version.original.replace('.', "_");
-        generated_code.push_str(&format!("pub mod {} {{\n", mod_name));
+        let mut values = schema_val.pointer(pointer).and_then(|v| v.get("enum")).and_then(|v| v.as_array()).into_iter().flatten()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect::<Vec<String>>();
+        values.sort();
+        values.dedup();
 
-        let mut definitions = Vec::new();
-        let mut local_components = std::collections::HashSet::new();
+        generated_code.push_str(&format!("pub const {}: &[&str] = &{:?};\n\n", name, values));
+    }
+
+    fs::write(&dest_path, generated_code).unwrap();
+}
+
+fn generate_molang_functions() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let base_path = Path::new(&manifest_dir).join("schemas/minecraftBedrock/schema/molang");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("molang/functions.rs");
 
-        if let Ok(entries) = fs::read_dir(&components_path) {
-            let mut file_paths: Vec<PathBuf> = entries
-                .filter_map(|e| e.ok().map(|entry| entry.path()))
-                .filter(|path| {
-                    path.is_file()
-                        && path.extension().map_or(false, |ext| ext == "json")
-                        && !path.file_name().unwrap().to_string_lossy().starts_with('_')
-                })
-                .collect();
+    _ = fs::create_dir_all(dest_path.parent().unwrap());
 
-            file_paths.sort();
+    let mut queries = std::collections::BTreeSet::new();
+    let mut math = std::collections::BTreeSet::new();
 
-            for file_path in file_paths {
-                let file_stem = file_path.file_stem().unwrap().to_string_lossy().to_string();
-                let root_name = to_pascal_case(&file_stem);
+    if let Ok(entries) = fs::read_dir(&base_path) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
 
-                let content = match fs::read_to_string(&file_path) {
+            for file in fs::read_dir(&path).into_iter().flatten().filter_map(|e| e.ok()) {
+                let content = match fs::read_to_string(file.path()) {
                     Ok(c) => c,
                     Err(_) => continue,
                 };
-                let clean_content = strip_comments(&content);
-                let schema_val: Value = match serde_json::from_str(&clean_content) {
+                let schema_val: Value = match serde_json::from_str(&strip_comments(&content)) {
                     Ok(v) => v,
                     Err(e) => {
-                        println!("cargo:warning=Failed to parse JSON schema {:?}: {}", file_path, e);
+                        println!("cargo:warning=Failed to parse Molang schema {:?}: {}", file.path(), e);
                         continue;
                     }
                 };
 
-                let mut file_type_names = Vec::new();
-                let root_type = parse_schema(&file_path, &schema_val, &root_name, &mut definitions, &mut file_type_names);
-
-                if !matches!(root_type, RustType::Struct(_) | RustType::Enum(_)) {
-                    let root_type_str = to_rust_type_string(&root_type);
-                    let newtype_def = format!(
-                        "#[derive(serde::Serialize, Clone, Debug, PartialEq)]\npub struct {}(pub {});\n\n",
-                        root_name, root_type_str
-                    );
-                    definitions.push(newtype_def);
-                    file_type_names.push(root_name.clone());
+                for name in schema_val.get("enum").and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()) {
+                    if let Some(query) = name.strip_prefix("query.") {
+                        queries.insert(query.to_string());
+                    } else if let Some(function) = name.strip_prefix("math.") {
+                        math.insert(function.to_string());
+                    }
                 }
+            }
+        }
+    }
+
+    let mut generated_code = String::new();
 
-                let component_id = format!("minecraft:{}", file_stem);
-                let impl_component = format!(
-                    "impl super::Component for {} {{\n    const ID: &'static str = \"{}\";\n}}\n",
-                    root_name, component_id
-                );
-                definitions.push(impl_component);
-
-                let impl_direct_id = format!(
-                    "impl {} {{\n    pub const ID: &'static str = \"{}\";\n}}\n",
-                    root_name, component_id
-                );
-                let impl_sjson = format!(
-                    "impl eo::sjson::HasSJsonIdent for {} {{ fn sjson_ident(&self) -> String {{ \"{}\".to_string() }} }}",
-                    root_name, component_id
-                );
-                definitions.push(impl_direct_id);
-
-                definitions.push(impl_sjson);
-
-                local_components.insert(file_stem.clone());
-                active_components.insert(file_stem.clone(), GeneratedComponent {
-                    version_mod: mod_name.clone(),
-                    type_names: file_type_names,
-                });
+    generated_code.push_str("// This file is auto-generated by build.rs. Do not edit manually.\n\n");
+    generated_code.push_str(&format!("pub const KNOWN_QUERIES: &[&str] = &{:?};\n\n", queries.into_iter().collect::<Vec<String>>()));
+    generated_code.push_str(&format!("pub const KNOWN_MATH_FUNCTIONS: &[&str] = &{:?};\n", math.into_iter().collect::<Vec<String>>()));
+
+    fs::write(&dest_path, generated_code).unwrap();
+}
+
+fn generate_schemas(ty: String) {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let base_path = Path::new(&manifest_dir).join(format!("schemas/minecraftBedrock/schema/{}", ty));
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join(format!("{}/components.rs", ty));
+
+    _ = fs::create_dir_all(dest_path.parent().unwrap());
+
+    let version_dirs = find_version_dirs(&base_path);
+    let mut generated_code = String::new();
+
+    generated_code.push_str("// This file is auto-generated by build.rs. Do not edit manually.\n\n");
+
+    let mut active_components: std::collections::HashMap<String, GeneratedComponent> = std::collections::HashMap::new();
+    let mut schema_components: Vec<(String, String)> = Vec::new();
+
+    for (version, component_dirs) in version_dirs {
+        let mod_name = version.original.replace('.', "_");
+        generated_code.push_str(&format!("pub mod {} {{\n", mod_name));
+
+        let mut definitions = Vec::new();
+        definitions.push(format!(
+            "#[derive(Clone, Copy, Debug, Default, PartialEq)]\npub struct FormatVersion;\n\nimpl crate::core::component::ComponentVersion for FormatVersion {{\n    const FORMAT_VERSION: (u32, u32, u32) = ({}, {}, {});\n}}\n",
+            version.major, version.minor, version.patch
+        ));
+        definitions.push(
+            "/// Typed components of this format version.\npub fn components(components: impl crate::core::component::ComponentList<FormatVersion>) -> crate::core::component::TypedComponents<FormatVersion> {\n    components.into_components()\n}\n".to_string()
+        );
+        let mut local_components = std::collections::HashSet::new();
+        let mut module_type_names = std::collections::HashSet::new();
+
+        let mut file_paths: Vec<PathBuf> = component_dirs.iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok().map(|entry| entry.path())))
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().is_some_and(|ext| ext == "json")
+                    && !path.file_name().unwrap().to_string_lossy().starts_with('_')
+            })
+            .collect();
+
+        file_paths.sort_by_key(|path| path.file_name().unwrap().to_os_string());
+
+        // Inherited components keep their type names, local components are named around them.
+        let local_stems: std::collections::HashSet<String> = file_paths.iter()
+            .map(|path| path.file_stem().unwrap().to_string_lossy().to_string())
+            .collect();
+        for (comp_name, gen_comp) in &active_components {
+            if !local_stems.contains(comp_name) {
+                module_type_names.extend(gen_comp.type_names.iter().cloned());
             }
         }
 
+        for file_path in file_paths {
+            let file_stem = file_path.file_stem().unwrap().to_string_lossy().to_string();
+
+            let content = match fs::read_to_string(&file_path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            let clean_content = strip_comments(&content);
+            let schema_val: Value = match serde_json::from_str(&clean_content) {
+                Ok(v) => v,
+                Err(e) => {
+                    println!("cargo:warning=Failed to parse JSON schema {:?}: {}", file_path, e);
+                    continue;
+                }
+            };
+
+            // Larger schemas, like the entity one, can generate the same type name from two components,
+            // e.g. the `damage` of `attack` and `attack_damage`. The later one is suffixed, `AttackDamageComponent`.
+            let base_name = to_pascal_case(&file_stem);
+            let mut attempt = 0;
+            let (root_name, file_definitions, file_type_names) = loop {
+                let root_name = match attempt {
+                    0 => base_name.clone(),
+                    1 => format!("{}Component", base_name),
+                    n => format!("{}Component{}", base_name, n)
+                };
+                let (file_definitions, file_type_names) = generate_component_types(&file_path, &schema_val, &root_name);
+
+                if !file_type_names.iter().any(|t| module_type_names.contains(t)) {
+                    break (root_name, file_definitions, file_type_names);
+                }
+                attempt += 1;
+            };
+            module_type_names.extend(file_type_names.iter().cloned());
+            definitions.extend(file_definitions);
+
+            let component_id = format!("minecraft:{}", file_stem);
+            let impl_component = format!(
+                "impl super::Component for {} {{\n    const ID: &'static str = \"{}\";\n}}\n",
+                root_name, component_id
+            );
+            definitions.push(impl_component);
+
+            let impl_direct_id = format!(
+                "impl {} {{\n    pub const ID: &'static str = \"{}\";\n}}\n",
+                root_name, component_id
+            );
+            let impl_sjson = format!(
+                "impl eo::sjson::HasSJsonIdent for {} {{ fn sjson_ident(&self) -> String {{ \"{}\".to_string() }} }}",
+                root_name, component_id
+            );
+            definitions.push(impl_direct_id);
+
+            definitions.push(impl_sjson);
+
+            schema_components.push((version.original.clone(), component_id.clone()));
+            local_components.insert(file_stem.clone());
+            active_components.insert(file_stem.clone(), GeneratedComponent {
+                version_mod: mod_name.clone(),
+                root_name,
+                type_names: file_type_names,
+            });
+        }
+
 
         for def in &definitions {
             for line in def.lines() {
                 generated_code.push_str(&format!("    {}\n", line));
             }
-            generated_code.push_str("\n");
+            generated_code.push('\n');
         }
 
+        let mut inherited: Vec<(&String, &GeneratedComponent)> = active_components.iter()
+            .filter(|(comp_name, _)| !local_components.contains(*comp_name))
+            .collect();
+        inherited.sort_by_key(|(comp_name, _)| *comp_name);
+
+        let mut valid_components: Vec<(&String, &GeneratedComponent)> = active_components.iter()
+            .filter(|(comp_name, _)| local_components.contains(*comp_name))
+            .collect();
         let mut inherited_re_exports = Vec::new();
-        for (comp_name, gen_comp) in &active_components {
-            if !local_components.contains(comp_name) {
-                for t_name in &gen_comp.type_names {
-                    inherited_re_exports.push(format!("    pub use super::{}::{};\n", gen_comp.version_mod, t_name));
+        for (comp_name, gen_comp) in inherited {
+            // Only a local schema which failed to parse can leave an inherited component without reserved type names.
+            if local_stems.contains(comp_name) {
+                if let Some(taken) = gen_comp.type_names.iter().find(|t| module_type_names.contains(*t)) {
+                    println!("cargo:warning=Not re-exporting component {} in {}, its type {} is already generated", comp_name, mod_name, taken);
+                    continue;
                 }
+                module_type_names.extend(gen_comp.type_names.iter().cloned());
+            }
+            valid_components.push((comp_name, gen_comp));
+
+            for t_name in &gen_comp.type_names {
+                inherited_re_exports.push(format!("    pub use super::{}::{};\n", gen_comp.version_mod, t_name));
             }
         }
         inherited_re_exports.sort();
@@ -178,28 +332,64 @@ fn generate_schemas(ty: String) {
             for re_export in inherited_re_exports {
                 generated_code.push_str(&re_export);
             }
-            generated_code.push_str("\n");
+            generated_code.push('\n');
+        }
+
+        // Every component of the module, local or inherited, is valid in this format version.
+        let mut versioned_impls: Vec<String> = valid_components.iter()
+            .map(|(comp_name, gen_comp)| format!(
+                "    impl crate::core::component::VersionedComponent<FormatVersion> for {} {{ const ID: &'static str = \"minecraft:{}\"; }}\n",
+                gen_comp.root_name, comp_name
+            ))
+            .collect();
+        versioned_impls.sort();
+
+        for versioned_impl in versioned_impls {
+            generated_code.push_str(&versioned_impl);
         }
 
         generated_code.push_str("}\n\n");
     }
 
+    generated_code.push_str("/// Every component schema a type was generated for, as its version folder and component id.\n");
+    generated_code.push_str(&format!("pub const SCHEMA_COMPONENTS: &[(&str, &str)] = &{:?};\n", schema_components));
+
     fs::write(&dest_path, generated_code).unwrap();
 }
 
-fn find_version_dirs(base_path: &Path) -> Vec<(Version, PathBuf)> {
+/// The types generated for a component schema, the root one named `root_name`.
+fn generate_component_types(file_path: &Path, schema_val: &Value, root_name: &str) -> (Vec<String>, Vec<String>) {
+    let mut definitions = Vec::new();
+    let mut type_names = Vec::new();
+    let root_type = parse_schema(file_path, schema_val, root_name, &mut definitions, &mut type_names);
+
+    if !matches!(root_type, RustType::Struct(_) | RustType::Enum(_)) {
+        let root_type_str = to_rust_type_string(&root_type);
+        definitions.push(format!(
+            "#[derive(serde::Serialize, Clone, Debug, PartialEq)]\npub struct {}(pub {});\n\n",
+            root_name, root_type_str
+        ));
+        type_names.push(root_name.to_string());
+    }
+
+    (definitions, type_names)
+}
+
+/// Component schemas live in `components/`, entities also keep AI goals in `behaviors/` and `annotations/`.
+fn find_version_dirs(base_path: &Path) -> Vec<(Version, Vec<PathBuf>)> {
     let mut versions = Vec::new();
     if let Ok(entries) = fs::read_dir(base_path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    let name = path.file_name().unwrap().to_string_lossy().to_string();
-                    if name.starts_with('v') {
-                        let components_path = path.join("components");
-                        if components_path.is_dir() {
-                            versions.push((parse_version(&name), components_path));
-                        }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                if name.starts_with('v') {
+                    let component_dirs: Vec<PathBuf> = ["components", "behaviors", "annotations"].iter()
+                        .map(|dir| path.join(dir))
+                        .filter(|dir| dir.is_dir())
+                        .collect();
+                    if !component_dirs.is_empty() {
+                        versions.push((parse_version(&name), component_dirs));
                     }
                 }
             }
@@ -278,38 +468,39 @@ fn normalize_path(path: &Path) -> PathBuf {
     result
 }
 
-fn resolve_ref(current_file: &Path, ref_str: &str) -> (PathBuf, Value) {
-    let parts: Vec<&str> = ref_str.split('#').collect();
-    let file_path = if parts[0].is_empty() {
+/// Resolves a `$ref` relative to the file it's in. Both `#` and `#/` point to the root of the document.
+fn resolve_ref(current_file: &Path, ref_str: &str) -> Result<(PathBuf, Value), String> {
+    let (file, pointer) = ref_str.split_once('#').unwrap_or((ref_str, ""));
+    let file_path = if file.is_empty() {
         current_file.to_path_buf()
     } else {
-        current_file.parent().unwrap().join(parts[0])
+        current_file.parent().unwrap().join(file)
     };
     let file_path = normalize_path(&file_path);
 
     let content = fs::read_to_string(&file_path)
-        .unwrap_or_else(|e| panic!("Failed to read referenced file {:?}: {}", file_path, e));
+        .map_err(|e| format!("Failed to read referenced file {:?}: {}", file_path, e))?;
     let clean_content = strip_comments(&content);
-    let mut val: Value = serde_json::from_str(&clean_content)
-        .unwrap_or_else(|e| panic!("Failed to parse JSON from {:?}: {}", file_path, e));
+    let val: Value = serde_json::from_str(&clean_content)
+        .map_err(|e| format!("Failed to parse JSON from {:?}: {}", file_path, e))?;
 
-    if parts.len() > 1 && !parts[1].is_empty() {
-        let pointer = parts[1];
-        let normalized_pointer = if pointer.starts_with('/') {
-            pointer.to_string()
-        } else {
-            format!("/{}", pointer)
-        };
-        if let Some(sub_val) = val.pointer(&normalized_pointer) {
-            val = sub_val.clone();
-        } else {
-            panic!("Could not find pointer {} in {:?}", pointer, file_path);
-        }
+    let pointer = pointer.trim_end_matches('/');
+    if pointer.is_empty() {
+        return Ok((file_path, val));
     }
 
-    (file_path, val)
+    let normalized_pointer = if pointer.starts_with('/') {
+        pointer.to_string()
+    } else {
+        format!("/{}", pointer)
+    };
+    match val.pointer(&normalized_pointer) {
+        Some(sub_val) => Ok((file_path, sub_val.clone())),
+        None => Err(format!("Could not find pointer {} in {:?}", pointer, file_path))
+    }
 }
 
+/// Follows `$ref`s until a schema without one. Unresolvable references become an untyped value, with a warning.
 fn dereference(
     current_file: &Path,
     schema: &Value,
@@ -319,19 +510,58 @@ fn dereference(
         return (current_file.to_path_buf(), Value::Null);
     }
     if let Some(ref_str) = schema.get("$ref").and_then(|v| v.as_str()) {
-        let (file_path, referenced_schema) = resolve_ref(current_file, ref_str);
-        dereference(&file_path, &referenced_schema, depth + 1)
+        match resolve_ref(current_file, ref_str) {
+            Ok((file_path, referenced_schema)) => dereference(&file_path, &referenced_schema, depth + 1),
+            Err(e) => {
+                println!("cargo:warning={}, referenced from {:?}", e, current_file);
+                (current_file.to_path_buf(), Value::Null)
+            }
+        }
     } else {
         (current_file.to_path_buf(), schema.clone())
     }
 }
 
+fn ref_key(current_file: &Path, ref_str: &str) -> String {
+    let (file, pointer) = ref_str.split_once('#').unwrap_or((ref_str, ""));
+    let file_path = if file.is_empty() {
+        current_file.to_path_buf()
+    } else {
+        normalize_path(&current_file.parent().unwrap().join(file))
+    };
+    format!("{}#{}", file_path.display(), pointer)
+}
+
 fn parse_schema(
     current_file: &Path,
     schema: &Value,
     name_path: &str,
     definitions: &mut Vec<String>,
     type_names: &mut Vec<String>,
+) -> RustType {
+    // Recursive schemas, like entity filters, become a plain value at the first reference back into themselves.
+    let key = schema.get("$ref").and_then(|v| v.as_str()).map(|r| ref_key(current_file, r));
+    if let Some(key) = &key {
+        if REF_STACK.with(|stack| stack.borrow().contains(key)) {
+            return RustType::Value;
+        }
+        REF_STACK.with(|stack| stack.borrow_mut().push(key.clone()));
+    }
+
+    let rust_type = parse_dereferenced_schema(current_file, schema, name_path, definitions, type_names);
+
+    if key.is_some() {
+        REF_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+    rust_type
+}
+
+fn parse_dereferenced_schema(
+    current_file: &Path,
+    schema: &Value,
+    name_path: &str,
+    definitions: &mut Vec<String>,
+    type_names: &mut Vec<String>,
 ) -> RustType {
     let (resolved_file, deref_schema) = dereference(current_file, schema, 0);
 
@@ -340,7 +570,7 @@ fn parse_schema(
     }
 
     if let Some(all_of) = deref_schema.get("allOf").and_then(|v| v.as_array()) {
-        let merged_schema = merge_all_of(&resolved_file, all_of);
+        let merged_schema = merge_all_of(&resolved_file, &deref_schema, all_of);
         return parse_schema(&resolved_file, &merged_schema, name_path, definitions, type_names);
     }
 
@@ -380,8 +610,9 @@ fn parse_schema(
     }
 }
 
-fn merge_all_of(current_file: &Path, schemas: &[Value]) -> Value {
-    let mut merged_properties = serde_json::Map::new();
+/// Merges the properties of the `allOf` schemas into the properties the schema declares itself.
+fn merge_all_of(current_file: &Path, schema: &Value, schemas: &[Value]) -> Value {
+    let mut merged_properties = schema.get("properties").and_then(|v| v.as_object()).cloned().unwrap_or_default();
     for s in schemas {
         let (_, deref_s) = dereference(current_file, s, 0);
         if let Some(props) = deref_s.get("properties").and_then(|v| v.as_object()) {
@@ -406,20 +637,20 @@ fn parse_any_of(
     let mut deref_schemas = Vec::new();
     let mut object_count = 0;
     for s in schemas {
-        let (resolved_file, deref_s) = dereference(current_file, s, 0);
+        let (_, deref_s) = dereference(current_file, s, 0);
         let is_obj = deref_s.get("properties").is_some()
-            || deref_s.get("type").and_then(|t| t.as_str()).map_or(false, |t| t == "object");
+            || deref_s.get("type").and_then(|t| t.as_str()).is_some_and(|t| t == "object");
         if is_obj {
             object_count += 1;
         }
-        deref_schemas.push((resolved_file, deref_s));
+        deref_schemas.push(deref_s);
     }
 
     let mut types = Vec::new();
     let mut obj_index = 0;
-    for (i, (resolved_file, deref_s)) in deref_schemas.iter().enumerate() {
+    for (i, (s, deref_s)) in schemas.iter().zip(&deref_schemas).enumerate() {
         let is_obj = deref_s.get("properties").is_some()
-            || deref_s.get("type").and_then(|t| t.as_str()).map_or(false, |t| t == "object");
+            || deref_s.get("type").and_then(|t| t.as_str()).is_some_and(|t| t == "object");
 
         let child_name = if is_obj {
             if object_count == 1 {
@@ -435,7 +666,7 @@ fn parse_any_of(
             obj_index += 1;
         }
 
-        let t = parse_schema(resolved_file, deref_s, &child_name, definitions, type_names);
+        let t = parse_schema(current_file, s, &child_name, definitions, type_names);
         types.push(t);
     }
 
@@ -465,7 +696,11 @@ fn parse_any_of(
 
     for t in &unique_types {
         let variant_name = get_variant_name(t);
-        let rust_type_str = to_rust_type_string(t);
+        // Objects are boxed, so the enum stays as small as its scalar variants.
+        let rust_type_str = match t {
+            RustType::Struct(_) => format!("Box<{}>", to_rust_type_string(t)),
+            _ => to_rust_type_string(t)
+        };
         enum_def.push_str(&format!("    {}({}),\n", variant_name, rust_type_str));
     }
 
@@ -490,8 +725,15 @@ fn parse_object_schema(
         let mut sorted_props: Vec<(&String, &Value)> = properties.iter().collect();
         sorted_props.sort_by_key(|(k, _)| *k);
 
+        // Schemas sometimes accept both spellings of a property, e.g. `totalSupply` and `total_supply`.
+        // The snake case one keeps the plain name, the other one is suffixed with `_camel`.
+        let exact_names = sorted_props.iter().map(|(k, _)| k.as_str()).collect::<std::collections::HashSet<&str>>();
+
         for (field_name, field_schema) in sorted_props {
-            let (rust_field_name, rename_attr) = get_rust_field_name(field_name);
+            let (mut rust_field_name, rename_attr) = get_rust_field_name(field_name);
+            if rust_field_name != *field_name && exact_names.contains(rust_field_name.as_str()) {
+                rust_field_name.push_str("_camel");
+            }
             let child_name_path = format!("{}{}", struct_name, to_pascal_case(field_name));
             let field_type = parse_schema(current_file, field_schema, &child_name_path, definitions, type_names);
             let field_type_str = to_rust_type_string(&field_type);
@@ -528,7 +770,7 @@ fn get_rust_field_name(name: &str) -> (String, String) {
         "use", "where", "while", "async", "await", "dyn", "try",
     ];
 
-    let cleaned = name.replace('*', "all").replace(':', "_").replace('$', "_");
+    let cleaned = to_snake_case(&name.replace('*', "all").replace([':', '$'], "_"));
 
     if keywords.contains(&cleaned.as_str()) {
         let rename_attr = format!("#[serde(rename = \"{}\")]", name);
@@ -541,6 +783,30 @@ fn get_rust_field_name(name: &str) -> (String, String) {
     }
 }
 
+/// `minXP` becomes `min_xp`, names which already are snake case are kept.
+fn to_snake_case(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<char>>();
+    let mut result = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = if i > 0 { chars.get(i - 1) } else { None };
+            let next = chars.get(i + 1);
+            let starts_word = match prev {
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_ascii_uppercase() => next.is_some_and(|n| n.is_ascii_lowercase()),
+                _ => false
+            };
+            if starts_word {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(*c);
+        }
+    }
+    result
+}
+
 fn to_pascal_case(s: &str) -> String {
     let mut result = String::new();
     let mut next_upper = true;
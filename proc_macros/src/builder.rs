@@ -1,7 +1,6 @@
-use proc_macro::{Punct, TokenStream};
 use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{braced, bracketed, custom_keyword, parenthesized, parse::{Parse, ParseStream}, punctuated::Punctuated, Expr, Path, Token};
+use syn::{braced, parenthesized, parse::{Parse, ParseStream}, Expr, Path, Token};
 use syn::token::Paren;
 
 mod keywords {
@@ -1,13 +1,9 @@
 mod builder;
 
-use proc_macro::{Ident, Span};
 use std::collections::HashMap;
 use std::fs;
-use std::hash::Hash;
-use std::iter::Peekable;
 use std::path::PathBuf;
 use proc_macro2::{Group, TokenStream, TokenTree};
-use proc_macro2::token_stream::IntoIter;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::__private::TokenStream2;
 use syn::parse::{Parse, ParseStream, Parser};
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::animation::{Animate, AnimationAction};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::BedrockSerializeVec;
+use crate::core::validation::{check_molang, Diagnostics};
+use crate::hold_builders;
+use crate::molang::Molang;
+
+hold_builders!(AnimationController, AnimationState);
+
+/// An animation controller, a state machine playing animations. Registered for the BP, it's written to `BP/animation_controllers/`,
+/// registered for the RP to `RP/animation_controllers/`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct AnimationController {
+    /// Has to start with `controller.animation.`, e.g. `controller.animation.woah.cow.walk`.
+    pub id: String,
+    /// Falls back to the `default` state when not set.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub initial_state: Option<String>,
+    #[builder(default = "Vec::new()")]
+    pub states: Vec<AnimationState>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct AnimationState {
+    pub name: String,
+    #[builder(setter(into = false), default = "Vec::new()")]
+    pub animations: Vec<Animate>,
+    /// Target states mapped to the condition switching to them, checked in order.
+    #[builder(setter(custom), default = "Vec::new()")]
+    pub transitions: Vec<(String, Molang)>,
+    #[builder(default = "Vec::new()")]
+    pub on_entry: Vec<AnimationAction>,
+    #[builder(default = "Vec::new()")]
+    pub on_exit: Vec<AnimationAction>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub blend_transition: Option<f64>
+}
+
+impl AnimationStateBuilder {
+    /// Adds a transition to another state, e.g. `transition = ("walking", Molang::new("q.modified_move_speed > 0.1"));`.
+    pub fn transition<S: Into<String>, M: Into<Molang>>(&mut self, (state, condition): (S, M)) -> &mut Self {
+        self.transitions.get_or_insert_with(Vec::new).push((state.into(), condition.into()));
+        self
+    }
+}
+
+impl AnimationController {
+    /// The name of the controller file, `controller.animation.woah.cow` is written to `animation_controllers/woah.cow.animation_controllers.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.animation_controllers.json", self.id.strip_prefix("controller.animation.").unwrap_or(&self.id))
+    }
+
+    pub fn initial_state(&self) -> &str {
+        self.initial_state.as_deref().unwrap_or("default")
+    }
+
+    /// Every Molang statement of the controller, including the transition conditions.
+    pub fn statements(&self) -> Vec<&Molang> {
+        self.states.iter().flat_map(|state| {
+            state.animations.iter().filter_map(|a| a.condition.as_ref())
+                .chain(state.transitions.iter().map(|(_, condition)| condition))
+                .chain(state.on_entry.iter().chain(state.on_exit.iter()).filter_map(|a| a.molang()))
+        }).collect()
+    }
+
+    /// Controllers start in their initial state and may only transition to states they declare.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics) {
+        let mut states = HashSet::new();
+
+        for state in &self.states {
+            if !states.insert(&state.name) {
+                diagnostics.error(&self.id, format!("The state `{}` is declared more than once", state.name));
+            }
+        }
+
+        if !states.contains(&self.initial_state().to_string()) {
+            diagnostics.error(&self.id, format!("The initial state `{}` isn't declared", self.initial_state()));
+        }
+
+        for state in &self.states {
+            for (target, _) in &state.transitions {
+                if !states.contains(target) {
+                    diagnostics.error(&self.id, format!("The state `{}` transitions to an undeclared state `{}`", state.name, target));
+                }
+            }
+        }
+
+        for statement in self.statements() {
+            check_molang(diagnostics, &self.id, statement);
+        }
+    }
+}
+
+impl BedrockSerializable for AnimationState {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut state = Map::new();
+
+        if !self.animations.is_empty() {
+            state.insert("animations".to_string(), Value::Array(self.animations.serialize_vec()?));
+        }
+        if !self.transitions.is_empty() {
+            state.insert("transitions".to_string(), Value::Array(
+                self.transitions.iter().map(|(target, condition)| json!({ target: condition })).collect()
+            ));
+        }
+
+        for (key, actions) in [("on_entry", &self.on_entry), ("on_exit", &self.on_exit)] {
+            if !actions.is_empty() {
+                state.insert(key.to_string(), Value::from(actions.iter().map(|a| a.render()).collect::<Vec<String>>()));
+            }
+        }
+
+        if let Some(blend) = self.blend_transition {
+            state.insert("blend_transition".to_string(), json!(blend));
+        }
+
+        Ok(json!({ &self.name: state }))
+    }
+}
+
+impl BedrockSerializable for AnimationController {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut controller = Map::new();
+
+        if let Some(initial_state) = &self.initial_state {
+            controller.insert("initial_state".to_string(), Value::from(initial_state.clone()));
+        }
+        controller.insert("states".to_string(), self.states.serialize_object()?);
+
+        Ok(json!({ &self.id: controller }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn client_controller() {
+        let memory = testing::build(|events| {
+            events.client_animation_registration.subscribe(|reg| {
+                reg.register_animation_controller(woah! {
+                    @AnimationController {
+                        id = "controller.animation.cool.entity.move";
+                        states = vec![
+                            @AnimationState {
+                                name = "default";
+                                transition = ("walking", Molang::new("q.modified_move_speed > 0.1"));
+                            },
+                            @AnimationState {
+                                name = "walking";
+                                animations = map vec!["walk"];
+                                transition = ("default", Molang::new("q.modified_move_speed < 0.1"));
+                                on_entry = vec![AnimationAction::Molang(Molang::new("v.walking = 1"))];
+                            }
+                        ];
+                    }
+                });
+            });
+        });
+
+        let controller = memory.read_json("WoahTest_RP/animation_controllers/cool.entity.move.animation_controllers.json").unwrap();
+        assert_eq!(controller["animation_controllers"]["controller.animation.cool.entity.move"]["states"]["walking"], json!({
+            "animations": ["walk"],
+            "transitions": [{ "default": "q.modified_move_speed < 0.1" }],
+            "on_entry": ["v.walking = 1;"]
+        }));
+    }
+}
@@ -0,0 +1,410 @@
+pub mod controller;
+pub mod registry;
+
+use std::collections::BTreeMap;
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::animation::controller::AnimationController;
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::Identifier;
+use crate::core::validation::{check_molang, check_unique_ids, Diagnostics};
+use crate::hold_builders;
+use crate::molang::ast::MolangExpr;
+use crate::molang::Molang;
+
+hold_builders!(Animation, ClientAnimation, BoneAnimation);
+
+/// A behavior pack animation, written to `BP/animations/`. It runs commands and events on a timeline.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Animation {
+    /// Has to start with `animation.`, e.g. `animation.woah.cow.moo`.
+    pub id: String,
+    #[builder(default = "AnimationLoop::Once")]
+    pub loop_mode: AnimationLoop,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub animation_length: Option<f64>,
+    /// Keyframe times in seconds mapped to the actions run at that time.
+    #[builder(setter(custom), default = "Vec::new()")]
+    pub timeline: Vec<(f64, Vec<AnimationAction>)>
+}
+
+/// A resource pack animation, written to `RP/animations/`. It moves the bones of a geometry.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ClientAnimation {
+    /// Has to start with `animation.`, e.g. `animation.woah.cow.walk`.
+    pub id: String,
+    #[builder(default = "AnimationLoop::Once")]
+    pub loop_mode: AnimationLoop,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub animation_length: Option<f64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub anim_time_update: Option<Molang>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub bones: BTreeMap<String, BoneAnimation>
+}
+
+/// The channels of a single bone in a [ClientAnimation].
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct BoneAnimation {
+    #[builder(setter(into, strip_option), default = "None")]
+    pub rotation: Option<AnimationChannel>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub position: Option<AnimationChannel>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub scale: Option<AnimationChannel>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationLoop {
+    Once,
+    Loop,
+    HoldOnLastFrame
+}
+
+/// The X, Y and Z values of a bone channel, either Molang-driven for the whole animation or keyframed.
+#[derive(Clone, Debug)]
+pub enum AnimationChannel {
+    Static([Molang; 3]),
+    /// Keyframe times in seconds mapped to the values at that time.
+    Keyframes(Vec<(f64, [Molang; 3])>)
+}
+
+/// Something run by a timeline or an animation controller state.
+#[derive(Clone, Debug)]
+pub enum AnimationAction {
+    /// A slash command, e.g. `say hi`. The leading `/` is added when rendering.
+    Command(String),
+    /// An entity event triggered on the entity itself, e.g. `woah:grow_up`.
+    Event(String),
+    Molang(Molang)
+}
+
+/// An entry of `scripts.animate` or of a controller state's animations, played while the condition holds.
+#[derive(Clone, Debug)]
+pub struct Animate {
+    pub name: String,
+    pub condition: Option<Molang>
+}
+
+impl AnimationBuilder {
+    /// Adds actions at a time of the timeline, e.g. `timeline = (0.5, vec![AnimationAction::Event("woah:moo".into())]);`.
+    pub fn timeline(&mut self, (time, actions): (f64, Vec<AnimationAction>)) -> &mut Self {
+        self.timeline.get_or_insert_with(Vec::new).push((time, actions));
+        self
+    }
+}
+
+impl ClientAnimationBuilder {
+    /// Animates a bone of the geometry, e.g. `bone = ("head", @BoneAnimation { ... });`.
+    pub fn bone<N: Into<String>>(&mut self, (name, animation): (N, BoneAnimation)) -> &mut Self {
+        self.bones.get_or_insert_with(BTreeMap::new).insert(name.into(), animation);
+        self
+    }
+}
+
+impl Animation {
+    /// The name of the animation file, `animation.woah.cow.moo` is written to `animations/woah.cow.moo.animation.json`.
+    pub fn file_name(&self) -> String {
+        animation_file_name(&self.id)
+    }
+
+    /// Every Molang statement of the timeline.
+    pub fn statements(&self) -> Vec<&Molang> {
+        self.timeline.iter().flat_map(|(_, actions)| actions).filter_map(|a| a.molang()).collect()
+    }
+}
+
+impl ClientAnimation {
+    /// The name of the animation file, `animation.woah.cow.walk` is written to `animations/woah.cow.walk.animation.json`.
+    pub fn file_name(&self) -> String {
+        animation_file_name(&self.id)
+    }
+
+    /// Every Molang statement of the animation, including the channel values.
+    pub fn statements(&self) -> Vec<&Molang> {
+        self.anim_time_update.iter()
+            .chain(self.bones.values().flat_map(|bone| bone.channels().into_iter().flat_map(|c| c.values())))
+            .collect()
+    }
+}
+
+/// Animation and controller identifiers need their prefix, and their Molang has to be valid.
+pub(crate) fn validate_animations(
+    diagnostics: &mut Diagnostics,
+    animations: &[Animation],
+    controllers: &[AnimationController],
+    client_animations: &[ClientAnimation],
+    client_controllers: &[AnimationController]
+) {
+    check_unique_ids(diagnostics, "animation", "animation.", animations.iter().map(|a| &a.id));
+    check_unique_ids(diagnostics, "client animation", "animation.", client_animations.iter().map(|a| &a.id));
+    check_unique_ids(diagnostics, "animation controller", "controller.animation.", controllers.iter().map(|c| &c.id));
+    check_unique_ids(diagnostics, "client animation controller", "controller.animation.", client_controllers.iter().map(|c| &c.id));
+
+    for (id, statement) in animations.iter().flat_map(|a| a.statements().into_iter().map(move |s| (&a.id, s)))
+        .chain(client_animations.iter().flat_map(|a| a.statements().into_iter().map(move |s| (&a.id, s)))) {
+        check_molang(diagnostics, id, statement);
+    }
+
+    for controller in controllers.iter().chain(client_controllers) {
+        controller.validate(diagnostics);
+    }
+}
+
+/// `scripts.animate` plays animations by the short names declared in the description.
+pub(crate) fn validate_animate(diagnostics: &mut Diagnostics, subject: &Identifier, animate: &[Animate], animations: &BTreeMap<String, String>) {
+    for entry in animate {
+        if !animations.contains_key(&entry.name) {
+            diagnostics.error(subject, format!("`scripts.animate` references an undeclared animation `{}`", entry.name));
+        }
+
+        if let Some(condition) = &entry.condition {
+            check_molang(diagnostics, subject, condition);
+        }
+    }
+}
+
+fn animation_file_name(id: &str) -> String {
+    format!("{}.animation.json", id.strip_prefix("animation.").unwrap_or(id))
+}
+
+impl BoneAnimation {
+    pub fn channels(&self) -> Vec<&AnimationChannel> {
+        [&self.rotation, &self.position, &self.scale].into_iter().flatten().collect()
+    }
+}
+
+impl AnimationChannel {
+    pub fn values(&self) -> Vec<&Molang> {
+        match &self {
+            AnimationChannel::Static(values) => values.iter().collect(),
+            AnimationChannel::Keyframes(frames) => frames.iter().flat_map(|(_, values)| values.iter()).collect()
+        }
+    }
+}
+
+impl AnimationAction {
+    pub fn molang(&self) -> Option<&Molang> {
+        match &self {
+            AnimationAction::Molang(molang) => Some(molang),
+            _ => None
+        }
+    }
+
+    pub fn render(&self) -> String {
+        match &self {
+            AnimationAction::Command(command) => format!("/{}", command.trim_start_matches('/')),
+            AnimationAction::Event(event) => format!("@s {}", event),
+            AnimationAction::Molang(molang) => format!("{};", molang.render().trim_end_matches(';'))
+        }
+    }
+}
+
+impl From<Molang> for AnimationAction {
+    fn from(value: Molang) -> Self {
+        AnimationAction::Molang(value)
+    }
+}
+
+impl Animate {
+    pub fn always(name: impl Into<String>) -> Self {
+        Self { name: name.into(), condition: None }
+    }
+
+    pub fn when(name: impl Into<String>, condition: impl Into<Molang>) -> Self {
+        Self { name: name.into(), condition: Some(condition.into()) }
+    }
+}
+
+impl From<&str> for Animate {
+    fn from(value: &str) -> Self {
+        Self::always(value)
+    }
+}
+
+impl From<String> for Animate {
+    fn from(value: String) -> Self {
+        Self::always(value)
+    }
+}
+
+impl From<[Molang; 3]> for AnimationChannel {
+    fn from(value: [Molang; 3]) -> Self {
+        AnimationChannel::Static(value)
+    }
+}
+
+impl From<[f64; 3]> for AnimationChannel {
+    fn from(value: [f64; 3]) -> Self {
+        AnimationChannel::Static(value.map(|v| Molang::from_expr(MolangExpr::Number(v))))
+    }
+}
+
+/// Time keys are rendered like Blockbench does, e.g. `0.0` and `1.25`.
+fn time_key(time: f64) -> String {
+    format!("{:?}", time)
+}
+
+/// Constant channel values are written as numbers, everything else as Molang strings.
+fn channel_value(molang: &Molang) -> Value {
+    match molang.expr() {
+        Ok(MolangExpr::Number(number)) => json!(number),
+        _ => Value::String(molang.render())
+    }
+}
+
+fn channel_values(values: &[Molang; 3]) -> Value {
+    Value::Array(values.iter().map(channel_value).collect())
+}
+
+impl BedrockSerializable for AnimationLoop {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            AnimationLoop::Once => Value::Bool(false),
+            AnimationLoop::Loop => Value::Bool(true),
+            AnimationLoop::HoldOnLastFrame => Value::from("hold_on_last_frame")
+        })
+    }
+}
+
+impl BedrockSerializable for AnimationChannel {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            AnimationChannel::Static(values) => channel_values(values),
+            AnimationChannel::Keyframes(frames) => Value::Object(
+                frames.iter().map(|(time, values)| (time_key(*time), channel_values(values))).collect()
+            )
+        })
+    }
+}
+
+impl BedrockSerializable for BoneAnimation {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut bone = Map::new();
+
+        for (key, channel) in [("rotation", &self.rotation), ("position", &self.position), ("scale", &self.scale)] {
+            if let Some(channel) = channel {
+                bone.insert(key.to_string(), channel.bedrock_json()?);
+            }
+        }
+
+        Ok(Value::Object(bone))
+    }
+}
+
+impl BedrockSerializable for Animate {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self.condition {
+            Some(condition) => json!({ &self.name: condition }),
+            None => Value::from(self.name.clone())
+        })
+    }
+}
+
+impl BedrockSerializable for Animation {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut animation = Map::new();
+        animation.insert("loop".to_string(), self.loop_mode.bedrock_json()?);
+
+        if let Some(length) = self.animation_length {
+            animation.insert("animation_length".to_string(), json!(length));
+        }
+
+        animation.insert("timeline".to_string(), Value::Object(
+            self.timeline.iter().map(|(time, actions)| {
+                (time_key(*time), Value::from(actions.iter().map(|a| a.render()).collect::<Vec<String>>()))
+            }).collect()
+        ));
+
+        Ok(json!({ &self.id: animation }))
+    }
+}
+
+impl BedrockSerializable for ClientAnimation {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut animation = Map::new();
+        animation.insert("loop".to_string(), self.loop_mode.bedrock_json()?);
+
+        if let Some(length) = self.animation_length {
+            animation.insert("animation_length".to_string(), json!(length));
+        }
+        if let Some(update) = &self.anim_time_update {
+            animation.insert("anim_time_update".to_string(), json!(update));
+        }
+
+        let mut bones = Map::new();
+        for (name, bone) in &self.bones {
+            bones.insert(name.clone(), bone.bedrock_json()?);
+        }
+        animation.insert("bones".to_string(), Value::Object(bones));
+
+        Ok(json!({ &self.id: animation }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn behavior_animation() {
+        let memory = testing::build(|events| {
+            events.animation_registration.subscribe(|reg| {
+                reg.register_animation(woah! {
+                    @Animation {
+                        id = "animation.cool.entity.moo";
+                        animation_length = 1.0;
+                        timeline = (0.0, vec![AnimationAction::Command("say moo".into())]);
+                        timeline = (0.5, vec![AnimationAction::Event("woah:cool_event".into())]);
+                    }
+                });
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_BP/animations/cool.entity.moo.animation.json").unwrap(), json!({
+            "format_version": "1.8.0",
+            "animations": {
+                "animation.cool.entity.moo": {
+                    "loop": false,
+                    "animation_length": 1.0,
+                    "timeline": { "0.0": ["/say moo"], "0.5": ["@s woah:cool_event"] }
+                }
+            }
+        }));
+    }
+
+    #[test]
+    fn client_animation() {
+        let memory = testing::build(|events| {
+            events.client_animation_registration.subscribe(|reg| {
+                reg.register_client_animation(woah! {
+                    @ClientAnimation {
+                        id = "animation.cool.entity.walk";
+                        loop_mode = AnimationLoop::Loop;
+                        bone = ("head", @BoneAnimation {
+                            rotation = [Molang::new("math.sin(q.anim_time * 90) * 10"), Molang::new("0"), Molang::new("0")];
+                            scale = [1.0, 1.5, 1.0];
+                        });
+                    }
+                });
+            });
+        });
+
+        let animation = memory.read_json("WoahTest_RP/animations/cool.entity.walk.animation.json").unwrap();
+        assert_eq!(animation["animations"]["animation.cool.entity.walk"], json!({
+            "loop": true,
+            "bones": {
+                "head": {
+                    "rotation": ["math.sin(q.anim_time * 90) * 10", 0.0, 0.0],
+                    "scale": [1.0, 1.5, 1.0]
+                }
+            }
+        }));
+    }
+}
@@ -0,0 +1,43 @@
+use log::info;
+use std::sync::Arc;
+use crate::animation::{Animation, ClientAnimation};
+use crate::animation::controller::AnimationController;
+use crate::core::core_registry::CoreRegistry;
+
+/// The animation registry. Used for registering behavior pack Animations and animation controllers.
+pub struct AnimationRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl AnimationRegistry {
+    pub fn register_animation(&self, animation: Animation) {
+        info!("@animation Registering animation {}", &animation.id);
+
+        self.registry.register_animation(animation);
+    }
+
+    pub fn register_animation_controller(&self, controller: AnimationController) {
+        info!("@animation Registering animation controller {}", &controller.id);
+
+        self.registry.register_animation_controller(controller);
+    }
+}
+
+/// The client animation registry. Used for registering resource pack Animations and animation controllers.
+pub struct ClientAnimationRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl ClientAnimationRegistry {
+    pub fn register_client_animation(&self, animation: ClientAnimation) {
+        info!("@client:animation Registering client animation {}", &animation.id);
+
+        self.registry.register_client_animation(animation);
+    }
+
+    pub fn register_animation_controller(&self, controller: AnimationController) {
+        info!("@client:animation Registering client animation controller {}", &controller.id);
+
+        self.registry.register_client_animation_controller(controller);
+    }
+}
@@ -1,118 +1,419 @@
-use std::fs;
-use std::path::PathBuf;
 use std::sync::Arc;
-use log::info;
+use serde_json::{json, Map, Value};
 use crate::block::Block;
 use crate::block::client::BlockTexture;
 use crate::code_gen::generator::PackGenerator;
-use crate::code_gen::TEMPLATES;
 use crate::core::metadata::PackMetadata;
 use crate::bedrock::BedrockSerializable;
 use crate::bedrock::metadata::{AddonBp, AddonRp, BedrockPath, BedrockPathResolver};
 use crate::core::build_target::BuildTarget;
+use crate::core::error::{WoahResult, WoahResultExt};
 use crate::core::utilities::{BedrockSerializeVec, JsonFormat};
 use crate::entity::Entity;
+use crate::entity::client::ClientEntity;
 use crate::item::client::ItemTexture;
 use crate::item::Item;
+use crate::lang::Translations;
+use crate::recipe::Recipe;
+use crate::loot::LootTable;
+use crate::geometry::Geometry;
+use crate::animation::{Animation, ClientAnimation};
+use crate::animation::controller::AnimationController;
+use crate::sound::Sounds;
+use crate::worldgen::Feature;
+use crate::worldgen::rule::FeatureRule;
+use crate::biome::Biome;
+use crate::biome::client::ClientBiome;
 
 /// The default Bedrock generator of Woah, it's integrated with the framework itself.
 #[derive(Clone)]
 pub struct WoahBedrockGenerator;
 
 impl PackGenerator for WoahBedrockGenerator {
-    fn build_prepare(&self, target: Arc<dyn BuildTarget>, metadata: &PackMetadata) {
-        fs::create_dir_all(&metadata.bedrock_path(BedrockPath::BPRoot, &target)).unwrap();
-        fs::create_dir_all(&metadata.bedrock_path(BedrockPath::RPRoot, &target)).unwrap();
+    fn build_prepare(&self, target: Arc<dyn BuildTarget>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::BPRoot, &target)?)?;
+        target.create_dir(&metadata.bedrock_path(BedrockPath::RPRoot, &target)?)
     }
 
-    fn build_manifest(&self, target: Arc<dyn BuildTarget>, metadata: &PackMetadata) {
-        fs::write(metadata.bedrock_path(
-            BedrockPath::bp("manifest.json"), &target
-        ), &AddonBp.bedrock_serialize().json_format()).unwrap();
-        fs::write(metadata.bedrock_path(
-            BedrockPath::rp("manifest.json"), 
-            &target
-        ), &AddonRp.bedrock_serialize().json_format()).unwrap();
+    fn build_manifest(&self, target: Arc<dyn BuildTarget>, metadata: &PackMetadata) -> WoahResult<()> {
+        let bp = metadata.bedrock_path(BedrockPath::bp("manifest.json"), &target)?;
+        let rp = metadata.bedrock_path(BedrockPath::rp("manifest.json"), &target)?;
+
+        target.write(&bp, AddonBp(metadata).bedrock_json()?.json_format().at_path(&bp)?.as_bytes())?;
+        target.write(&rp, AddonRp(metadata).bedrock_json()?.json_format().at_path(&rp)?.as_bytes())
     }
 
-    fn build_items(&self, target: Arc<dyn BuildTarget>, items: Vec<Item>, metadata: &PackMetadata) {
-        fs::create_dir_all(&metadata.bedrock_path(BedrockPath::bp("items"), &target)).unwrap();
+    fn build_items(&self, target: Arc<dyn BuildTarget>, items: Vec<Item>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::bp("items"), &target)?)?;
 
         for item in items {
-            let path = metadata.bedrock_path(BedrockPath::bp(format!("items/{}.json", &item.id.render_underscore())), &target);
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("items/{}.json", &item.id.render_underscore())), &target)?;
 
-            fs::write(path, item.bedrock_serialize().json_format()).unwrap()
+            let content = item.bedrock_json().and_then(|v| v.json_format()).for_identifier(&item.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&item.id)?;
         }
+
+        Ok(())
     }
 
-    fn build_client_items(&self, target: Arc<dyn BuildTarget>, items: Vec<ItemTexture>, metadata: &PackMetadata) {
-        let item_textures_path = metadata.bedrock_path(BedrockPath::rp(format!("textures/items/{}", metadata.name)), &target);
+    fn build_client_items(&self, target: Arc<dyn BuildTarget>, mut items: Vec<ItemTexture>, metadata: &PackMetadata) -> WoahResult<()> {
+        items.sort_by_key(|item| item.id.render());
 
-        fs::create_dir_all(&item_textures_path).unwrap();
+        let item_textures_path = metadata.bedrock_path(BedrockPath::rp(format!("textures/items/{}", metadata.name)), &target)?;
+
+        target.create_dir(&item_textures_path)?;
 
         for item in &items {
             let file_path = item_textures_path.join(
                 format!("{}.png", &item.id.render_underscore())
             );
 
-            item.sprite.build(file_path)
+            let png = item.sprite.encode().for_identifier(&item.id).at_path(&file_path)?;
+            target.write(&file_path, &png).for_identifier(&item.id)?;
         }
 
-        let item_texture_json_path = metadata.bedrock_path(BedrockPath::rp("textures/item_texture.json"), &target);
-
-        let mut c = tera::Context::new();
+        let item_texture_json_path = metadata.bedrock_path(BedrockPath::rp("textures/item_texture.json"), &target)?;
 
-        c.insert("name", &metadata.name);
-        c.insert("contents", &items.into_iter().map(|x| x.bedrock_serialize()).collect::<Vec<String>>().join(","));
+        let contents = json!({
+            "resource_pack_name": metadata.name,
+            "texture_name": "atlas.items",
+            "texture_data": items.iter().map(|item| item.atlas_entry(&metadata.name)).collect::<Map<String, Value>>()
+        });
 
-        let temp = TEMPLATES.render("items/item_texture.json", &c).unwrap();
-
-        fs::write(item_texture_json_path, temp.json_format()).unwrap();
+        target.write(&item_texture_json_path, contents.json_format().at_path(&item_texture_json_path)?.as_bytes())
     }
 
-    fn build_blocks(&self, target: Arc<dyn BuildTarget>, blocks: Vec<Block>, metadata: &PackMetadata) {
-        fs::create_dir_all(metadata.bedrock_path(BedrockPath::bp("blocks"), &target)).unwrap();
+    fn build_blocks(&self, target: Arc<dyn BuildTarget>, blocks: Vec<Block>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::bp("blocks"), &target)?)?;
 
         for block in blocks {
-            fs::write(metadata.bedrock_path(
-                BedrockPath::bp(format!("blocks/{}.json", &block.id.render_underscore())), &target
-            ), block.bedrock_serialize().json_format()).unwrap()
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("blocks/{}.json", &block.id.render_underscore())), &target)?;
+
+            let content = block.bedrock_json().and_then(|v| v.json_format()).for_identifier(&block.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&block.id)?;
         }
+
+        Ok(())
     }
 
-    fn build_client_blocks(&self, target: Arc<dyn BuildTarget>, blocks: Vec<BlockTexture>, metadata: &PackMetadata) {
-        fs::create_dir_all(metadata.bedrock_path(BedrockPath::rp(format!("textures/block/{}", metadata.name)), &target)).unwrap();
+    fn build_client_blocks(&self, target: Arc<dyn BuildTarget>, mut blocks: Vec<BlockTexture>, metadata: &PackMetadata) -> WoahResult<()> {
+        blocks.sort_by_key(|texture| texture.id.render());
+
+        target.create_dir(&metadata.bedrock_path(BedrockPath::rp(format!("textures/block/{}", metadata.name)), &target)?)?;
 
         for texture in &blocks {
-            let path = &metadata.bedrock_path(BedrockPath::rp(format!("textures/block/{}/{}.png", metadata.name, &texture.id.render_underscore())), &target);
+            let path = metadata.bedrock_path(BedrockPath::rp(format!("textures/block/{}/{}.png", metadata.name, &texture.id.render_underscore())), &target)?;
 
-            texture.sprite.build(path);
+            let png = texture.sprite.encode().for_identifier(&texture.id).at_path(&path)?;
+            target.write(&path, &png).for_identifier(&texture.id)?;
         }
 
-        let block_texture_json_path = metadata.bedrock_path(BedrockPath::rp("textures/terrain_texture.json"), &target);
+        let block_texture_json_path = metadata.bedrock_path(BedrockPath::rp("textures/terrain_texture.json"), &target)?;
 
-        let mut c = tera::Context::new();
+        let contents = json!({
+            "num_mip_levels": 4,
+            "padding": 8,
+            "resource_pack_name": metadata.name,
+            "texture_name": "atlas.terrain",
+            "texture_data": blocks.iter().map(|texture| texture.atlas_entry(&metadata.name)).collect::<Map<String, Value>>()
+        });
 
-        c.insert("name", &metadata.name);
-        c.insert("content", &blocks.serialize_vec().join(","));
-
-        let contents = TEMPLATES.render("block/terrain_texture.json", &c).unwrap();
-
-        fs::write(block_texture_json_path, contents.json_format()).unwrap()
+        target.write(&block_texture_json_path, contents.json_format().at_path(&block_texture_json_path)?.as_bytes())
     }
 
-    fn build_entities(&self, target: Arc<dyn BuildTarget>, entities: Vec<Entity>, metadata: &PackMetadata) {
-        fs::create_dir_all(metadata.bedrock_path(BedrockPath::bp(format!("entities/{}", metadata.name)), &target)).unwrap();
+    fn build_entities(&self, target: Arc<dyn BuildTarget>, entities: Vec<Entity>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::bp(format!("entities/{}", metadata.name)), &target)?)?;
 
-        // dbg!(&entities);
         for entity in entities {
             let path = metadata.bedrock_path(
                 BedrockPath::bp(format!("entities/{}/{}.json", metadata.name, entity.id.render_underscore())), &target
-            );
+            )?;
+
+            let content = entity.bedrock_json().and_then(|v| v.json_format()).for_identifier(&entity.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&entity.id)?;
+
+            if let Some(spawn_rule) = &entity.spawn_rule {
+                target.create_dir(&metadata.bedrock_path(BedrockPath::bp("spawn_rules"), &target)?)?;
+
+                let path = metadata.bedrock_path(BedrockPath::bp(format!("spawn_rules/{}.json", entity.id.render_underscore())), &target)?;
+
+                let content = spawn_rule.bedrock_json(&entity.id).and_then(|v| v.json_format()).for_identifier(&entity.id).at_path(&path)?;
+                target.write(&path, content.as_bytes()).for_identifier(&entity.id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build_client_entities(&self, target: Arc<dyn BuildTarget>, entities: Vec<ClientEntity>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::rp("entity"), &target)?)?;
+        target.create_dir(&metadata.bedrock_path(BedrockPath::rp(format!("textures/entity/{}", metadata.name)), &target)?)?;
+
+        for entity in entities {
+            for (name, sprite) in &entity.textures {
+                let path = metadata.bedrock_path(BedrockPath::rp(format!("{}.png", entity.texture_path(&metadata.name, name))), &target)?;
+
+                let png = sprite.encode().for_identifier(&entity.id).at_path(&path)?;
+                target.write(&path, &png).for_identifier(&entity.id)?;
+            }
+
+            let path = metadata.bedrock_path(BedrockPath::rp(format!("entity/{}.entity.json", entity.id.render_underscore())), &target)?;
+
+            let content = entity.bedrock_json(&metadata.name).and_then(|v| v.json_format()).for_identifier(&entity.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&entity.id)?;
+
+            if !entity.render_controllers.is_empty() {
+                target.create_dir(&metadata.bedrock_path(BedrockPath::rp("render_controllers"), &target)?)?;
+
+                let path = metadata.bedrock_path(
+                    BedrockPath::rp(format!("render_controllers/{}.render_controllers.json", entity.id.render_underscore())), &target
+                )?;
+
+                let contents = json!({
+                    "format_version": "1.8.0",
+                    "render_controllers": entity.render_controllers.serialize_object().for_identifier(&entity.id)?
+                });
+                target.write(&path, contents.json_format().at_path(&path)?.as_bytes()).for_identifier(&entity.id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build_recipes(&self, target: Arc<dyn BuildTarget>, recipes: Vec<Recipe>, metadata: &PackMetadata) -> WoahResult<()> {
+        target.create_dir(&metadata.bedrock_path(BedrockPath::bp("recipes"), &target)?)?;
+
+        for recipe in recipes {
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("recipes/{}.json", &recipe.id.render_underscore())), &target)?;
+
+            let content = recipe.bedrock_json().and_then(|v| v.json_format()).for_identifier(&recipe.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&recipe.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_loot_tables(&self, target: Arc<dyn BuildTarget>, loot_tables: Vec<LootTable>, metadata: &PackMetadata) -> WoahResult<()> {
+        for table in loot_tables {
+            let path = metadata.bedrock_path(BedrockPath::bp(table.handle().path()), &target)?;
+
+            if let Some(parent) = path.parent() {
+                target.create_dir(parent)?;
+            }
+
+            let content = table.bedrock_json().and_then(|v| v.json_format()).for_identifier(&table.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&table.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_geometries(&self, target: Arc<dyn BuildTarget>, geometries: Vec<Geometry>, metadata: &PackMetadata) -> WoahResult<()> {
+        if geometries.is_empty() {
+            return Ok(());
+        }
+
+        target.create_dir(&metadata.bedrock_path(BedrockPath::rp("models"), &target)?)?;
+
+        for geometry in geometries {
+            let path = metadata.bedrock_path(BedrockPath::rp(format!("models/{}", geometry.file_name())), &target)?;
+
+            let content = geometry.bedrock_json().and_then(|v| v.json_format()).for_identifier(&geometry.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&geometry.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_animations(&self, target: Arc<dyn BuildTarget>, animations: Vec<Animation>, controllers: Vec<AnimationController>, metadata: &PackMetadata) -> WoahResult<()> {
+        let animations = animations.iter().map(|a| (a.id.clone(), a.file_name(), a.bedrock_json())).collect();
+
+        write_animations(&target, metadata, BedrockPath::BP, animations, controllers)
+    }
+
+    fn build_client_animations(&self, target: Arc<dyn BuildTarget>, animations: Vec<ClientAnimation>, controllers: Vec<AnimationController>, metadata: &PackMetadata) -> WoahResult<()> {
+        let animations = animations.iter().map(|a| (a.id.clone(), a.file_name(), a.bedrock_json())).collect();
+
+        write_animations(&target, metadata, BedrockPath::RP, animations, controllers)
+    }
+
+    fn build_sounds(&self, target: Arc<dyn BuildTarget>, sounds: Sounds, metadata: &PackMetadata) -> WoahResult<()> {
+        if !sounds.definitions.is_empty() {
+            target.create_dir(&metadata.bedrock_path(BedrockPath::rp(format!("sounds/{}", metadata.name)), &target)?)?;
 
-            let ser = entity.bedrock_serialize();
+            let mut definitions = Map::new();
 
-            fs::write(path, ser.json_format()).unwrap()
+            for definition in &sounds.definitions {
+                for (index, audio) in definition.audio() {
+                    let path = metadata.bedrock_path(
+                        BedrockPath::rp(format!("{}.{}", definition.sound_path(&metadata.name, index), audio.format().extension())), &target
+                    )?;
+                    target.write(&path, audio.bytes()).for_identifier(&definition.id)?;
+                }
+
+                if let Value::Object(entry) = definition.bedrock_json(&metadata.name).for_identifier(&definition.id)? {
+                    definitions.extend(entry);
+                }
+            }
+
+            let path = metadata.bedrock_path(BedrockPath::rp("sounds/sound_definitions.json"), &target)?;
+            let contents = json!({
+                "format_version": "1.20.20",
+                "sound_definitions": definitions
+            });
+            target.write(&path, contents.json_format().at_path(&path)?.as_bytes())?;
+        }
+
+        if !sounds.block_sounds.is_empty() || !sounds.entity_sounds.is_empty() {
+            let path = metadata.bedrock_path(BedrockPath::rp("sounds.json"), &target)?;
+            let contents = json!({
+                "block_sounds": sounds.block_sounds.serialize_object()?,
+                "entity_sounds": {
+                    "entities": sounds.entity_sounds.serialize_object()?
+                }
+            });
+            target.write(&path, contents.json_format().at_path(&path)?.as_bytes())?;
         }
+
+        if !sounds.block_bindings.is_empty() {
+            let mut blocks = Map::new();
+            blocks.insert("format_version".to_string(), json!([1, 1, 0]));
+            for (block, sound) in &sounds.block_bindings {
+                blocks.insert(block.clone(), json!({ "sound": sound }));
+            }
+
+            let path = metadata.bedrock_path(BedrockPath::rp("blocks.json"), &target)?;
+            target.write(&path, Value::Object(blocks).json_format().at_path(&path)?.as_bytes())?;
+        }
+
+        Ok(())
     }
-}
\ No newline at end of file
+
+    fn build_features(&self, target: Arc<dyn BuildTarget>, features: Vec<Feature>, rules: Vec<FeatureRule>, metadata: &PackMetadata) -> WoahResult<()> {
+        if !features.is_empty() {
+            target.create_dir(&metadata.bedrock_path(BedrockPath::bp("features"), &target)?)?;
+        }
+
+        for feature in features {
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("features/{}", feature.file_name())), &target)?;
+
+            let content = feature.bedrock_json().and_then(|v| v.json_format()).for_identifier(&feature.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&feature.id)?;
+        }
+
+        if !rules.is_empty() {
+            target.create_dir(&metadata.bedrock_path(BedrockPath::bp("feature_rules"), &target)?)?;
+        }
+
+        for rule in rules {
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("feature_rules/{}", rule.file_name())), &target)?;
+
+            let content = rule.bedrock_json().and_then(|v| v.json_format()).for_identifier(&rule.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&rule.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_biomes(&self, target: Arc<dyn BuildTarget>, biomes: Vec<Biome>, metadata: &PackMetadata) -> WoahResult<()> {
+        if biomes.is_empty() {
+            return Ok(());
+        }
+
+        target.create_dir(&metadata.bedrock_path(BedrockPath::bp("biomes"), &target)?)?;
+
+        for biome in biomes {
+            let path = metadata.bedrock_path(BedrockPath::bp(format!("biomes/{}", biome.file_name())), &target)?;
+
+            let content = biome.bedrock_json().and_then(|v| v.json_format()).for_identifier(&biome.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&biome.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_client_biomes(&self, target: Arc<dyn BuildTarget>, biomes: Vec<ClientBiome>, metadata: &PackMetadata) -> WoahResult<()> {
+        if biomes.is_empty() {
+            return Ok(());
+        }
+
+        target.create_dir(&metadata.bedrock_path(BedrockPath::rp("biomes"), &target)?)?;
+
+        let mut entries = Map::new();
+        for biome in biomes {
+            let path = metadata.bedrock_path(BedrockPath::rp(format!("biomes/{}", biome.file_name())), &target)?;
+
+            let content = biome.bedrock_json().and_then(|v| v.json_format()).for_identifier(&biome.id).at_path(&path)?;
+            target.write(&path, content.as_bytes()).for_identifier(&biome.id)?;
+
+            if let Value::Object(entry) = biome.biomes_client_entry() {
+                entries.extend(entry);
+            }
+        }
+
+        let path = metadata.bedrock_path(BedrockPath::rp("biomes_client.json"), &target)?;
+        let content = json!({ "biomes": entries }).json_format().at_path(&path)?;
+        target.write(&path, content.as_bytes())
+    }
+
+    fn build_lang(&self, target: Arc<dyn BuildTarget>, translations: Translations, metadata: &PackMetadata) -> WoahResult<()> {
+        if translations.is_empty() {
+            return Ok(());
+        }
+
+        let languages = translations.locales().cloned().collect::<Vec<String>>();
+
+        for pack in [BedrockPath::BP, BedrockPath::RP] {
+            target.create_dir(&metadata.bedrock_path(pack("texts".to_string()), &target)?)?;
+
+            let languages_path = metadata.bedrock_path(pack("texts/languages.json".to_string()), &target)?;
+            target.write(&languages_path, Value::from(languages.clone()).json_format().at_path(&languages_path)?.as_bytes())?;
+
+            for locale in &languages {
+                let path = metadata.bedrock_path(pack(format!("texts/{}.lang", locale)), &target)?;
+                target.write(&path, translations.render(locale).as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Animations of both packs share their file layout, only the pack they're written to differs.
+fn write_animations(
+    target: &Arc<dyn BuildTarget>,
+    metadata: &PackMetadata,
+    pack: fn(String) -> BedrockPath,
+    animations: Vec<(String, String, WoahResult<Value>)>,
+    controllers: Vec<AnimationController>
+) -> WoahResult<()> {
+    if !animations.is_empty() {
+        target.create_dir(&metadata.bedrock_path(pack("animations".to_string()), target)?)?;
+    }
+
+    for (id, file_name, animation) in animations {
+        let path = metadata.bedrock_path(pack(format!("animations/{}", file_name)), target)?;
+
+        let contents = json!({
+            "format_version": "1.8.0",
+            "animations": animation.for_identifier(&id)?
+        });
+        target.write(&path, contents.json_format().at_path(&path)?.as_bytes()).for_identifier(&id)?;
+    }
+
+    if !controllers.is_empty() {
+        target.create_dir(&metadata.bedrock_path(pack("animation_controllers".to_string()), target)?)?;
+    }
+
+    for controller in controllers {
+        let path = metadata.bedrock_path(pack(format!("animation_controllers/{}", controller.file_name())), target)?;
+
+        let contents = json!({
+            "format_version": "1.10.0",
+            "animation_controllers": controller.bedrock_json().for_identifier(&controller.id)?
+        });
+        target.write(&path, contents.json_format().at_path(&path)?.as_bytes()).for_identifier(&controller.id)?;
+    }
+
+    Ok(())
+}
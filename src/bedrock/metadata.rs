@@ -1,13 +1,12 @@
-use std::fmt::format;
 use std::path::PathBuf;
 use std::sync::Arc;
 use derive_builder::Builder;
+use serde_json::{json, Value};
 use crate::bedrock::BedrockSerializable;
-use crate::code_gen::{WoahConfig, TEMPLATES};
 use crate::core::build_target::BuildTarget;
-use crate::core::core_registry::REGISTRY;
+use crate::core::error::{WoahError, WoahResult, WoahResultExt};
 use crate::core::metadata::PackMetadata;
-use crate::core::utilities::{ScriptModuleVer, SemVer};
+use crate::core::utilities::{BedrockSerializeVec, ScriptModuleVer, SemVer};
 use crate::hold_builders;
 
 hold_builders!(BedrockSpecificMetadata, ScriptModule);
@@ -71,80 +70,103 @@ impl ScriptModule {
         }
     }
 
-    pub fn render(&self) -> String {
-        let mut context = tera::Context::new();
-
-        context.insert("name", &self.name.render());
-        context.insert("version", &self.version.render());
-
-        TEMPLATES.render("manifest/script_module.json", &context).unwrap()
+    pub fn render(&self) -> Value {
+        json!({
+            "module_name": self.name.render(),
+            "version": self.version.render()
+        })
     }
 }
 
 impl BedrockSerializable for ScriptModule {
-    fn bedrock_serialize(&self) -> String {
-        self.render()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(self.render())
     }
 }
 
-pub(crate) struct AddonBp;
-
-pub(crate) struct AddonRp;
-
-impl BedrockSerializable for AddonBp {
-    fn bedrock_serialize(&self) -> String {
-        let md = &REGISTRY.pack_metadata.read().unwrap();
-
-        let mut c = tera::Context::new();
-
-        let conf = WoahConfig::read();
-
-        if let Some(add) = &md.additional {
-            if let Some(bedrock) = &add.bedrock_specific {
-                let serialized_script_modules = bedrock.script_modules.clone().into_iter().map(
-                    |x| x.bedrock_serialize()
-                ).collect::<Vec<String>>().join(",");
-
-                c.insert("script_modules", &serialized_script_modules);
-                c.insert("min_engine_version", &bedrock.min_engine_version.render_commas());
-                c.insert("use_scripts", &(bedrock.script_modules.len() > 0));
+/// The behavior pack manifest of a pack.
+pub(crate) struct AddonBp<'a>(pub &'a PackMetadata);
+
+/// The resource pack manifest of a pack.
+pub(crate) struct AddonRp<'a>(pub &'a PackMetadata);
+
+impl BedrockSerializable for AddonBp<'_> {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let md = self.0;
+
+        let conf = md.uuids.as_ref().ok_or_else(|| WoahError::other("The pack UUIDs are only known during a build"))?;
+
+        let mut modules = vec![json!({
+            "type": "data",
+            "uuid": conf.uuid2b,
+            "version": [1, 0, 0]
+        })];
+
+        let mut manifest = json!({
+            "format_version": 2,
+            "metadata": {
+                "authors": [md.author]
+            },
+            "header": {
+                "name": md.name,
+                "description": md.description,
+                "uuid": conf.uuid1b,
+                "version": md.version.render_array()
+            }
+        });
+
+        if let Some(bedrock) = md.additional.as_ref().and_then(|a| a.bedrock_specific.as_ref()) {
+            manifest["header"]["min_engine_version"] = bedrock.min_engine_version.render_array();
+
+            if !bedrock.script_modules.is_empty() {
+                modules.push(json!({
+                    "type": "script",
+                    "language": "javascript",
+                    "uuid": conf.uuid3b,
+                    "entry": "scripts/main.js",
+                    "version": [1, 0, 0]
+                }));
+                manifest["dependencies"] = Value::Array(bedrock.script_modules.serialize_vec()?);
             }
         }
 
-        c.insert("name", &md.name);
-        c.insert("version", &md.version.render_commas());
-        c.insert("author", &md.author);
-        c.insert("description", &md.description);
-        c.insert("uuid_1", &conf.uuid1b);
-        c.insert("uuid_2", &conf.uuid2b);
-        c.insert("uuid_3", &conf.uuid3b);
-
+        manifest["modules"] = Value::Array(modules);
 
-        TEMPLATES.render("manifest/behavior_pack.json", &c).unwrap()
+        Ok(manifest)
     }
 }
 
-impl BedrockSerializable for AddonRp {
-    fn bedrock_serialize(&self) -> String {
-        let md = &REGISTRY.pack_metadata.read().unwrap();
-
-        let mut c = tera::Context::new();
-
-        let conf = WoahConfig::read();
-
-        c.insert("name", &md.name);
-        c.insert("version", &md.version.render_commas());
-        c.insert("author", &md.author);
-        c.insert("description", &md.description);
-        if let Some(add) = &md.additional {
-            if let Some(bedrock) = &add.bedrock_specific {
-                c.insert("min_engine_version", &bedrock.min_engine_version.render_commas());
-            }
+impl BedrockSerializable for AddonRp<'_> {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let md = self.0;
+
+        let conf = md.uuids.as_ref().ok_or_else(|| WoahError::other("The pack UUIDs are only known during a build"))?;
+
+        let mut manifest = json!({
+            "format_version": 2,
+            "metadata": {
+                "authors": [md.author]
+            },
+            "header": {
+                "name": md.name,
+                "description": md.description,
+                "uuid": conf.uuid1r,
+                "version": md.version.render_array()
+            },
+            "modules": [
+                {
+                    "type": "resources",
+                    "uuid": conf.uuid2r,
+                    "version": [1, 0, 0]
+                }
+            ]
+        });
+
+        if let Some(bedrock) = md.additional.as_ref().and_then(|a| a.bedrock_specific.as_ref()) {
+            manifest["header"]["min_engine_version"] = bedrock.min_engine_version.render_array();
         }
-        c.insert("uuid_1", &conf.uuid1r);
-        c.insert("uuid_2", &conf.uuid2r);
 
-        TEMPLATES.render("manifest/resource_pack.json", &c).unwrap()
+        Ok(manifest)
     }
 }
 
@@ -174,26 +196,66 @@ impl BedrockPath {
 pub trait BedrockPathResolver {
     fn pack_name(&self) -> &String;
 
-    fn bedrock_path(&self, path: BedrockPath, target: &Arc<dyn BuildTarget>) -> PathBuf {
-        let p = match path {
-            BedrockPath::BP(v) => target.path_keyed("bp").unwrap().join(
-                format!("{}_BP", self.pack_name())
-            ).join(
-                v
-            ),
-            BedrockPath::RP(v) => target.path_keyed("rp").unwrap().join(
-                format!("{}_RP", self.pack_name())
-            ).join(
-                v
-            ),
-            BedrockPath::BPRoot => target.path_keyed("bp").unwrap().join(
-                format!("{}_BP", self.pack_name())
-            ),
-            BedrockPath::RPRoot => target.path_keyed("rp").unwrap().join(
-                format!("{}_RP", self.pack_name())
-            )
+    fn bedrock_path(&self, path: BedrockPath, target: &Arc<dyn BuildTarget>) -> WoahResult<PathBuf> {
+        let (key, suffix, sub) = match path {
+            BedrockPath::BP(v) => ("bp", "BP", Some(v)),
+            BedrockPath::RP(v) => ("rp", "RP", Some(v)),
+            BedrockPath::BPRoot => ("bp", "BP", None),
+            BedrockPath::RPRoot => ("rp", "RP", None)
+        };
+
+        let root = target.path_keyed(key).ok_or_else(|| {
+            WoahError::other(format!("The build target has no `{}` path", key))
+        }).at_path(target.path())?;
+
+        let p = root.join(format!("{}_{}", self.pack_name(), suffix));
+
+        Ok(match sub {
+            Some(v) => p.join(v),
+            None => p
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::metadata::AdditionalMetadata;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn manifests() {
+        let metadata = woah! {
+            @PackMetadata {
+                name = "WoahTest";
+                version = (1, 0, 0);
+                author = "NaKeR";
+                description = "Smth";
+                additional = @AdditionalMetadata {
+                    bedrock_specific = @BedrockSpecificMetadata {
+                        min_engine_version = (1, 26, 40);
+                        script_modules = vec![
+                            @ScriptModule {
+                                name = ScriptModuleName::Server;
+                                version = (2, 8, 0);
+                            },
+                            @ScriptModule {
+                                name = ScriptModuleName::Ui;
+                                version = (2, 1, 0);
+                            }
+                        ];
+                    };
+                };
+            }
         };
+        let memory = testing::build_with(metadata, |_| {});
+
+        let manifest = memory.read_json("WoahTest_BP/manifest.json").unwrap();
+        assert_eq!(manifest["header"]["name"], "WoahTest");
+        assert_eq!(manifest["header"]["min_engine_version"], json!([1, 26, 40]));
+        assert_eq!(manifest["dependencies"][0]["module_name"], "@minecraft/server");
 
-        p
+        let manifest = memory.read_json("WoahTest_RP/manifest.json").unwrap();
+        assert_eq!(manifest["header"]["min_engine_version"], json!([1, 26, 40]));
     }
-}
\ No newline at end of file
+}
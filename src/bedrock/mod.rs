@@ -1,13 +1,24 @@
+use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::sync::Arc;
-use crate::code_gen::generator::GeneratorInstance;
+use serde_json::Value;
 use crate::core::build_target::BuildTarget;
+use crate::core::error::{WoahResult, WoahResultExt};
+use crate::core::metadata::PackMetadata;
 
 pub mod bedrock_generator;
 pub mod metadata;
+mod package;
+pub mod schema;
 
+/// Serialization into Bedrock JSON. Implementors build a [Value], so the output is always valid JSON.<br>
+/// Objects which are keyed in their parent (like Block states or Entity component groups) return a single-entry object, see [BedrockSerializeVec::serialize_object](crate::core::utilities::BedrockSerializeVec::serialize_object).
 pub trait BedrockSerializable {
-    fn bedrock_serialize(&self) -> String;
+    fn bedrock_json(&self) -> WoahResult<Value>;
+
+    fn bedrock_serialize(&self) -> WoahResult<String> {
+        Ok(serde_json::to_string(&self.bedrock_json()?)?)
+    }
 }
 
 #[derive(Clone)]
@@ -17,6 +28,10 @@ pub enum BedrockTarget {
     },
     Local {
         path: PathBuf
+    },
+    /// Writes the packs as `<name>_BP-<version>.mcpack` and `<name>_RP-<version>.mcpack`, and bundles both into `<name>-<version>.mcaddon`.
+    Package {
+        path: PathBuf
     }
 }
 
@@ -32,28 +47,80 @@ impl BedrockTarget {
             path: path.into()
         }
     }
+
+    pub fn package(path: impl Into<PathBuf>) -> Self {
+        Self::Package {
+            path: path.into()
+        }
+    }
+
+    /// The folder the packs are generated into before being archived.
+    fn staging_path(&self) -> PathBuf {
+        self.path().join(".staging")
+    }
 }
 
 impl BuildTarget for BedrockTarget {
     fn path(&self) -> PathBuf {
         match &self {
             BedrockTarget::Develop { path } => path.clone(),
-            BedrockTarget::Local { path } => path.clone()
+            BedrockTarget::Local { path } => path.clone(),
+            BedrockTarget::Package { path } => path.clone()
         }
     }
 
     fn path_keyed(&self, key: &str) -> Option<PathBuf> {
         match &self {
-            BedrockTarget::Develop {
-                path
-            } => if key == "bp" {
+            BedrockTarget::Develop { .. } => if key == "bp" {
                 Some(self.path().join("development_behavior_packs"))
             } else if key == "rp" {
                 Some(self.path().join("development_resource_packs"))
             } else { None }
+            BedrockTarget::Package { .. } => Some(self.staging_path()),
             _ => Some(self.path())
         }
     }
+
+    /// Packages always start from an empty staging folder, so files of earlier builds don't end up in the archives.
+    fn prepare(&self) -> WoahResult<()> {
+        let BedrockTarget::Package { .. } = &self else {
+            return Ok(());
+        };
+
+        let staging = self.staging_path();
+        match std::fs::remove_dir_all(&staging) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err).at_path(staging),
+            _ => Ok(())
+        }
+    }
+
+    fn finalize(&self, metadata: &PackMetadata) -> WoahResult<()> {
+        let BedrockTarget::Package { path } = &self else {
+            return Ok(());
+        };
+
+        let staging = self.staging_path();
+        let version = metadata.version.render_dotted();
+        let bp = staging.join(format!("{}_BP", metadata.name));
+        let rp = staging.join(format!("{}_RP", metadata.name));
+
+        package::write_mcpack(&bp, &path.join(format!("{}_BP-{}.mcpack", metadata.name, version)))?;
+        package::write_mcpack(&rp, &path.join(format!("{}_RP-{}.mcpack", metadata.name, version)))?;
+        package::write_mcaddon(&[&bp, &rp], &path.join(format!("{}-{}.mcaddon", metadata.name, version)))?;
+
+        std::fs::remove_dir_all(&staging).at_path(staging)
+    }
+
+    fn abort(&self) {
+        let BedrockTarget::Package { .. } = &self else {
+            return;
+        };
+
+        let staging = self.staging_path();
+        if let Some(err) = std::fs::remove_dir_all(&staging).err().filter(|e| e.kind() != ErrorKind::NotFound) {
+            log::warn!("Could not remove the staging folder {}: {}", staging.display(), err);
+        }
+    }
 }
 
 pub trait TargetInstance {
@@ -66,4 +133,28 @@ pub trait TargetInstance {
 impl<T> TargetInstance for T
 where
     T : BuildTarget + Clone {
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+
+    #[test]
+    fn package_staging() {
+        let path = testing::temp_dir("package_staging");
+        let target = BedrockTarget::package(&path);
+        let stale = path.join(".staging/Stale_BP");
+
+        std::fs::create_dir_all(&stale).unwrap();
+        target.prepare().unwrap();
+        assert!(!stale.exists());
+
+        std::fs::create_dir_all(&stale).unwrap();
+        target.abort();
+        assert!(!path.join(".staging").exists());
+
+        // Nothing to clear is fine too.
+        target.prepare().unwrap();
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
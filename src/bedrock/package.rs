@@ -0,0 +1,61 @@
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+use zip::write::SimpleFileOptions;
+use crate::core::error::{WoahResult, WoahResultExt};
+
+/// Entries are written in name order with a fixed timestamp, so archives of the same files are byte-for-byte identical.
+fn options() -> SimpleFileOptions {
+    SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+}
+
+/// Writes the contents of `dir` into the archive under `prefix`. An empty prefix puts the contents at the archive root.
+fn add_dir(zip: &mut ZipWriter<File>, dir: &Path, prefix: &str) -> WoahResult<()> {
+    let mut entries = fs::read_dir(dir).at_path(dir)?.collect::<Result<Vec<_>, _>>().at_path(dir)?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let path = entry.path();
+
+        if path.is_dir() {
+            zip.add_directory(format!("{}/", name), options()).at_path(&path)?;
+            add_dir(zip, &path, &format!("{}/", name))?;
+        } else {
+            zip.start_file(name, options()).at_path(&path)?;
+            zip.write_all(&fs::read(&path).at_path(&path)?).at_path(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Zips a pack folder into an `.mcpack`, with the manifest at the archive root.
+pub(crate) fn write_mcpack(pack: &Path, output: &Path) -> WoahResult<()> {
+    let mut zip = ZipWriter::new(File::create(output).at_path(output)?);
+
+    add_dir(&mut zip, pack, "").at_path(output)?;
+    zip.finish().at_path(output)?;
+
+    Ok(())
+}
+
+/// Bundles pack folders into an `.mcaddon`, each under its own root folder named after the pack folder.
+pub(crate) fn write_mcaddon(packs: &[&Path], output: &Path) -> WoahResult<()> {
+    let mut zip = ZipWriter::new(File::create(output).at_path(output)?);
+
+    for pack in packs {
+        let root = pack.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        zip.add_directory(format!("{}/", root), options()).at_path(output)?;
+        add_dir(&mut zip, pack, &format!("{}/", root)).at_path(output)?;
+    }
+
+    zip.finish().at_path(output)?;
+
+    Ok(())
+}
@@ -0,0 +1,570 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use serde_json::Value;
+use crate::bedrock::metadata::{BedrockPath, BedrockPathResolver};
+use crate::core::build_target::BuildTarget;
+use crate::core::error::WoahResult;
+use crate::core::metadata::PackMetadata;
+use crate::core::validation::{Diagnostics, Severity};
+use crate::core::PackPart;
+
+/// The prefix of the schema URIs in `fileDefinition/*.json`, which is the root of the bundled schemas.
+const SCHEMA_URI: &str = "file:///data/packages/minecraftBedrock/";
+
+/// Nested references deeper than this are assumed to be valid, instead of following a recursive schema forever.
+const MAX_DEPTH: usize = 256;
+
+/// Keywords which aren't checked, with the type of value they apply to. Schemas using them on a value are reported as warnings.
+const SKIPPED_KEYWORDS: &[(&str, &str)] = &[
+    ("pattern", "string"),
+    ("format", "string"),
+    ("patternProperties", "object"),
+    ("dependencies", "object"),
+    ("minProperties", "object"),
+    ("maxProperties", "object"),
+    ("uniqueItems", "array"),
+    ("multipleOf", "number")
+];
+
+/// A value of a file that doesn't match its schema, or, as a warning, a keyword of its schema which wasn't checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaViolation {
+    /// The JSON pointer of the value, empty for the root of the file.
+    pub pointer: String,
+    pub message: String,
+    pub severity: Severity
+}
+
+/// Which schema a pack file is validated with, read from `fileDefinition/*.json`.
+struct FileDefinition {
+    pack_types: Vec<String>,
+    matchers: Vec<String>,
+    extensions: Vec<String>,
+    schema: PathBuf
+}
+
+/// Validates generated files offline, against the JSON schemas bundled in `schemas/minecraftBedrock`.<br>
+/// Supports the parts of draft-07 the bundled schemas use. Keywords like `pattern` and `patternProperties` aren't checked, but reported once per file as warnings.
+/// The empty enums of the `dynamic` schemas, which editors fill with project content, accept any value.
+pub struct SchemaValidator {
+    root: PathBuf,
+    definitions: Vec<FileDefinition>,
+    schemas: RwLock<HashMap<PathBuf, Option<Arc<Value>>>>
+}
+
+impl SchemaValidator {
+    /// A validator using the schemas shipped with Woah.
+    pub fn bundled() -> Self {
+        Self::new(concat!(env!("CARGO_MANIFEST_DIR"), "/schemas/minecraftBedrock"))
+    }
+
+    /// A validator using the schemas in `root`, which has the layout of `schemas/minecraftBedrock`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let mut definitions = Vec::new();
+
+        let mut paths = fs::read_dir(root.join("fileDefinition"))
+            .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect::<Vec<PathBuf>>())
+            .unwrap_or_default();
+        paths.sort();
+
+        for path in paths {
+            let Some(definition) = fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str::<Value>(&strip_comments(&c)).ok()) else {
+                log::warn!("Skipping the unreadable file definition {}", path.display());
+                continue;
+            };
+
+            if let Some(schema) = definition["schema"].as_str().and_then(|s| s.strip_prefix(SCHEMA_URI)) {
+                let detect = &definition["detect"];
+
+                definitions.push(FileDefinition {
+                    pack_types: strings(&detect["packType"]),
+                    matchers: strings(&detect["matcher"]),
+                    extensions: strings(&detect["fileExtensions"]),
+                    schema: root.join(schema)
+                });
+            }
+        }
+
+        Self {
+            root,
+            definitions,
+            schemas: RwLock::new(HashMap::new())
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The schema of a file, by its path relative to the pack, e.g. `items/ruby.json`.
+    pub fn schema_for(&self, part: &PackPart, path: &str) -> Option<&Path> {
+        let pack_type = match part {
+            PackPart::BehaviourPack => "behaviorPack",
+            PackPart::ResourcePack => "resourcePack",
+            PackPart::Custom(custom) => custom.as_str()
+        };
+
+        self.definitions.iter()
+            .find(|d| {
+                d.pack_types.iter().any(|t| t == pack_type)
+                    && (d.extensions.is_empty() || d.extensions.iter().any(|e| path.ends_with(e.as_str())))
+                    && d.matchers.iter().any(|m| glob_matches(m, path))
+            })
+            .map(|d| d.schema.as_path())
+    }
+
+    /// Validates a pack file, `None` when no schema is mapped to it.
+    pub fn validate_file(&self, part: &PackPart, path: &str, value: &Value) -> Option<Vec<SchemaViolation>> {
+        self.schema_for(part, path).map(|schema| self.validate(schema, value))
+    }
+
+    /// Validates a value against a schema file. Every skipped keyword is only reported for the first value it applies to.
+    pub fn validate(&self, schema: &Path, value: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.check_ref(schema, "", value, "", 0, &mut violations);
+
+        let mut skipped = HashSet::new();
+        violations.retain(|v| v.severity == Severity::Error || skipped.insert(v.message.clone()));
+        violations
+    }
+
+    fn load(&self, path: &Path) -> Option<Arc<Value>> {
+        if let Some(schema) = self.schemas.read().unwrap().get(path) {
+            return schema.clone();
+        }
+
+        let schema = fs::read_to_string(path).ok()
+            .and_then(|content| serde_json::from_str::<Value>(&strip_comments(&content)).ok())
+            .map(Arc::new);
+        if schema.is_none() {
+            log::warn!("Skipping the unreadable schema {}", path.display());
+        }
+
+        self.schemas.write().unwrap().insert(path.to_path_buf(), schema.clone());
+        schema
+    }
+
+    fn check_ref(&self, file: &Path, reference: &str, value: &Value, pointer: &str, depth: usize, out: &mut Vec<SchemaViolation>) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        let (path, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        let file = if path.is_empty() {
+            file.to_path_buf()
+        } else {
+            normalize(&file.parent().unwrap_or(Path::new("")).join(path))
+        };
+
+        let Some(document) = self.load(&file) else { return };
+        let fragment = if fragment.is_empty() || fragment.starts_with('/') { fragment.to_string() } else { format!("/{}", fragment) };
+
+        match document.pointer(&fragment) {
+            Some(schema) => self.check(&file, schema, value, pointer, depth + 1, out),
+            None => log::warn!("Skipping the unresolvable schema reference {}#{}", file.display(), fragment)
+        }
+    }
+
+    fn passes(&self, file: &Path, schema: &Value, value: &Value, pointer: &str, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.check(file, schema, value, pointer, depth, &mut violations);
+        error_count(&violations) == 0
+    }
+
+    fn check(&self, file: &Path, schema: &Value, value: &Value, pointer: &str, depth: usize, out: &mut Vec<SchemaViolation>) {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(false) => return out.push(violation(pointer, "No value is allowed here")),
+            _ => return
+        };
+
+        // Keywords next to a reference are ignored in draft-07.
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.check_ref(file, reference, value, pointer, depth, out);
+        }
+
+        let types = schema.get("type").map(strings).unwrap_or_default();
+        if !types.is_empty() && !types.iter().any(|t| is_type(value, t)) {
+            return out.push(violation(pointer, format!("Expected {}, found {}", types.join(" or "), type_name(value))));
+        }
+
+        for (keyword, ty) in SKIPPED_KEYWORDS {
+            if schema.contains_key(*keyword) && is_type(value, ty) {
+                out.push(SchemaViolation {
+                    pointer: pointer.to_string(),
+                    message: format!("The `{}` keyword isn't supported, so values aren't checked against it", keyword),
+                    severity: Severity::Warning
+                });
+            }
+        }
+
+        if let Some(Value::Array(allowed)) = schema.get("enum") && !allowed.is_empty() && !allowed.iter().any(|a| json_eq(a, value)) {
+            out.push(violation(pointer, format!("{} is not one of the allowed values", value)));
+        }
+        if let Some(constant) = schema.get("const") && !json_eq(constant, value) {
+            out.push(violation(pointer, format!("Expected {}, found {}", constant, value)));
+        }
+
+        if let Some(number) = value.as_f64() {
+            for (keyword, valid) in [
+                ("minimum", schema.get("minimum").and_then(Value::as_f64).is_none_or(|m| number >= m)),
+                ("maximum", schema.get("maximum").and_then(Value::as_f64).is_none_or(|m| number <= m)),
+                ("exclusiveMinimum", schema.get("exclusiveMinimum").and_then(Value::as_f64).is_none_or(|m| number > m)),
+                ("exclusiveMaximum", schema.get("exclusiveMaximum").and_then(Value::as_f64).is_none_or(|m| number < m))
+            ] {
+                if !valid {
+                    out.push(violation(pointer, format!("{} violates the {} of {}", number, keyword, schema[keyword])));
+                }
+            }
+        }
+
+        if let Some(string) = value.as_str() {
+            let length = string.chars().count() as u64;
+            if schema.get("minLength").and_then(Value::as_u64).is_some_and(|m| length < m) {
+                out.push(violation(pointer, format!("The string is shorter than {} characters", schema["minLength"])));
+            }
+            if schema.get("maxLength").and_then(Value::as_u64).is_some_and(|m| length > m) {
+                out.push(violation(pointer, format!("The string is longer than {} characters", schema["maxLength"])));
+            }
+        }
+
+        if let Value::Array(values) = value {
+            match schema.get("items") {
+                Some(Value::Array(items)) => {
+                    for (index, (item, schema)) in values.iter().zip(items).enumerate() {
+                        self.check(file, schema, item, &format!("{}/{}", pointer, index), depth, out);
+                    }
+                }
+                Some(items) => {
+                    for (index, item) in values.iter().enumerate() {
+                        self.check(file, items, item, &format!("{}/{}", pointer, index), depth, out);
+                    }
+                }
+                None => {}
+            }
+
+            if schema.get("minItems").and_then(Value::as_u64).is_some_and(|m| (values.len() as u64) < m) {
+                out.push(violation(pointer, format!("Expected at least {} items, found {}", schema["minItems"], values.len())));
+            }
+            if schema.get("maxItems").and_then(Value::as_u64).is_some_and(|m| (values.len() as u64) > m) {
+                out.push(violation(pointer, format!("Expected at most {} items, found {}", schema["maxItems"], values.len())));
+            }
+        }
+
+        if let Value::Object(object) = value {
+            for required in schema.get("required").map(strings).unwrap_or_default() {
+                if !object.contains_key(&required) {
+                    out.push(violation(pointer, format!("Missing the required property `{}`", required)));
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, property) in object {
+                let property_pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+
+                if let Some(property_schema) = properties.and_then(|p| p.get(key)) {
+                    self.check(file, property_schema, property, &property_pointer, depth, out);
+                } else if !schema.contains_key("patternProperties") {
+                    match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => out.push(violation(&property_pointer, format!("Unknown property `{}`", key))),
+                        Some(additional) => self.check(file, additional, property, &property_pointer, depth, out),
+                        None => {}
+                    }
+                }
+
+                if let Some(names) = schema.get("propertyNames") {
+                    self.check(file, names, &Value::String(key.clone()), &property_pointer, depth, out);
+                }
+            }
+        }
+
+        if let Some(Value::Array(all_of)) = schema.get("allOf") {
+            for schema in all_of {
+                self.check(file, schema, value, pointer, depth, out);
+            }
+        }
+        if let Some(Value::Array(alternatives)) = schema.get("anyOf") {
+            self.check_alternatives(file, alternatives, value, pointer, depth, out);
+        }
+        if let Some(Value::Array(alternatives)) = schema.get("oneOf") && self.check_alternatives(file, alternatives, value, pointer, depth, out) > 1 {
+            out.push(violation(pointer, "Matches more than one of the `oneOf` schemas"));
+        }
+        if let Some(not) = schema.get("not") && self.passes(file, not, value, pointer, depth) {
+            out.push(violation(pointer, "Matches a schema it must not match"));
+        }
+
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.passes(file, condition, value, pointer, depth) { schema.get("then") } else { schema.get("else") };
+
+            if let Some(branch) = branch {
+                self.check(file, branch, value, pointer, depth, out);
+            }
+        }
+    }
+
+    /// Returns how many alternatives match, counting up to two, which tells `anyOf` and `oneOf` apart, and keeps the warnings of the first match.<br>
+    /// When none matches, reports the violations of the closest alternative, or a single violation when every alternative already fails at the value itself.
+    fn check_alternatives(&self, file: &Path, alternatives: &[Value], value: &Value, pointer: &str, depth: usize, out: &mut Vec<SchemaViolation>) -> usize {
+        let mut closest: Option<Vec<SchemaViolation>> = None;
+        let mut matches = 0;
+
+        for alternative in alternatives {
+            let mut violations = Vec::new();
+            self.check(file, alternative, value, pointer, depth, &mut violations);
+
+            if error_count(&violations) == 0 {
+                if matches == 0 {
+                    out.extend(violations);
+                }
+                matches += 1;
+
+                if matches == 2 {
+                    break;
+                }
+            } else if matches == 0 && closest.as_ref().is_none_or(|c| error_count(&violations) < error_count(c)) {
+                closest = Some(violations);
+            }
+        }
+
+        if matches == 0 {
+            match closest {
+                Some(violations) if violations.iter().any(|v| v.severity == Severity::Error && v.pointer != pointer) => out.extend(violations),
+                Some(_) => out.push(violation(pointer, "Doesn't match any of the allowed schemas")),
+                None => {}
+            }
+        }
+
+        matches
+    }
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "at the root: {}", self.message)
+        } else {
+            write!(f, "at `{}`: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Validates the JSON files written to a target. Violations are reported for the identifier in the `description` of the file,
+/// or for its path when it has none.
+pub(crate) fn validate_pack_files(validator: &SchemaValidator, metadata: &PackMetadata, target: &Arc<dyn BuildTarget>, files: &[(PathBuf, Vec<u8>)]) -> WoahResult<Diagnostics> {
+    let mut diagnostics = Diagnostics::default();
+    let bp = metadata.bedrock_path(BedrockPath::BPRoot, target)?;
+    let rp = metadata.bedrock_path(BedrockPath::RPRoot, target)?;
+
+    for (path, contents) in files {
+        let (part, relative) = if let Ok(relative) = path.strip_prefix(&bp) {
+            (PackPart::BehaviourPack, relative)
+        } else if let Ok(relative) = path.strip_prefix(&rp) {
+            (PackPart::ResourcePack, relative)
+        } else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        let Ok(value) = serde_json::from_slice::<Value>(contents) else { continue };
+        let Some(violations) = validator.validate_file(&part, &relative, &value) else { continue };
+
+        let subject = source_identifier(&value).unwrap_or_else(|| path.display().to_string());
+        for violation in violations {
+            match violation.severity {
+                Severity::Error => diagnostics.error(&subject, format!("`{}` does not match its schema {}", relative, violation)),
+                Severity::Warning => diagnostics.warning(&subject, format!("`{}` isn't fully checked against its schema {}", relative, violation))
+            }
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// The identifier in the `description` of a file, like `minecraft:item.description.identifier`.
+fn source_identifier(value: &Value) -> Option<String> {
+    value.as_object()?.values()
+        .find_map(|v| v.get("description").and_then(|d| d.get("identifier")).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn violation(pointer: &str, message: impl Into<String>) -> SchemaViolation {
+    SchemaViolation { pointer: pointer.to_string(), message: message.into(), severity: Severity::Error }
+}
+
+fn error_count(violations: &[SchemaViolation]) -> usize {
+    violations.iter().filter(|v| v.severity == Severity::Error).count()
+}
+
+/// A string or an array of strings.
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(values) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => Vec::new()
+    }
+}
+
+fn is_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => true
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object"
+    }
+}
+
+/// Equality as JSON schema defines it, so `1` equals `1.0`.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b)),
+        (Value::Object(a), Value::Object(b)) => a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| json_eq(v, w))),
+        _ => a == b
+    }
+}
+
+/// Matches a path against the globs of file definitions, where `**` matches any number of folders and `*` any part of a name.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    fn segments(glob: &[&str], path: &[&str]) -> bool {
+        match (glob.first(), path.first()) {
+            (None, None) => true,
+            (Some(&"**"), _) => segments(&glob[1..], path) || (!path.is_empty() && segments(glob, &path[1..])),
+            (Some(g), Some(p)) => segment(g, p) && segments(&glob[1..], &path[1..]),
+            _ => false
+        }
+    }
+
+    fn segment(glob: &str, name: &str) -> bool {
+        match glob.split_once('*') {
+            None => glob == name,
+            Some((prefix, rest)) => name.strip_prefix(prefix)
+                .is_some_and(|name| (0..=name.len()).filter(|i| name.is_char_boundary(*i)).any(|i| segment(rest, &name[i..])))
+        }
+    }
+
+    segments(&glob.split('/').collect::<Vec<&str>>(), &path.split('/').collect::<Vec<&str>>())
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::ParentDir => { normalized.pop(); }
+            Component::CurDir => {}
+            other => normalized.push(other)
+        }
+    }
+
+    normalized
+}
+
+/// The bundled schemas are JSON with `//` and `/* */` comments.
+fn strip_comments(json: &str) -> String {
+    let mut result = String::with_capacity(json.len());
+    let mut chars = json.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => in_string = false,
+                _ => escaped = false
+            }
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            while chars.peek().is_some_and(|c| *c != '\n') {
+                chars.next();
+            }
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    break;
+                }
+            }
+        } else {
+            if c == '"' {
+                in_string = true;
+            }
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::core::testing;
+
+    #[test]
+    fn schema_validation() {
+        let validator = SchemaValidator::bundled();
+        let item = |icon: serde_json::Value| json!({
+            "format_version": "1.20.0",
+            "minecraft:item": {
+                "description": { "identifier": "woah:ruby" },
+                "components": { "minecraft:icon": icon }
+            }
+        });
+
+        assert!(validator.schema_for(&PackPart::BehaviourPack, "items/ruby.json").unwrap().ends_with("schema/item/main.json"));
+        assert!(validator.schema_for(&PackPart::ResourcePack, "items/ruby.json").unwrap().ends_with("schema/clientItem/main.json"));
+        assert_eq!(validator.validate_file(&PackPart::BehaviourPack, "items/ruby.json", &item(json!({ "texture": "woah:ruby" }))), Some(vec![]));
+
+        let violations = validator.validate_file(&PackPart::BehaviourPack, "items/ruby.json", &item(json!("woah:ruby"))).unwrap();
+        assert!(violations.iter().any(|v| v.pointer == "/minecraft:item/components/minecraft:icon"), "{:?}", violations);
+    }
+
+    #[test]
+    fn one_of() {
+        let validator = SchemaValidator::bundled();
+        let folder = testing::temp_dir("schema_one_of");
+        let schema = folder.join("one_of.json");
+
+        // `oneOf` allows exactly one match, and keywords which aren't checked are reported as warnings.
+        std::fs::write(&schema, json!({
+            "oneOf": [{ "type": "integer" }, { "type": "number", "maximum": 10 }],
+            "pattern": "^[a-z]+$"
+        }).to_string()).unwrap();
+
+        let messages = |value: serde_json::Value| validator.validate(&schema, &value).into_iter()
+            .map(|v| (v.severity, v.message))
+            .collect::<Vec<(Severity, String)>>();
+
+        assert_eq!(messages(json!(20)), vec![]);
+        assert_eq!(messages(json!(2.5)), vec![]);
+        assert_eq!(messages(json!(5)), vec![(Severity::Error, "Matches more than one of the `oneOf` schemas".to_string())]);
+        assert_eq!(messages(json!("ruby")), vec![
+            (Severity::Warning, "The `pattern` keyword isn't supported, so values aren't checked against it".to_string()),
+            (Severity::Error, "Doesn't match any of the allowed schemas".to_string())
+        ]);
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::core::validation::{Diagnostics, Registered};
+use crate::core::vanilla;
+use crate::hold_builders;
+
+hold_builders!(ClientBiome);
+
+/// The client side of a [Biome](crate::biome::Biome). Sky and water surface colors and the fog are written to `RP/biomes/`,
+/// every color and the water fog to the `RP/biomes_client.json` entry of the biome.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ClientBiome {
+    #[builder(default = "SemVer::new(1, 21, 40)")]
+    pub format_version: SemVer,
+    pub id: Identifier,
+    /// `0xRRGGBB`, like every other color.
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub sky_color: Option<u32>,
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub fog_color: Option<u32>,
+    /// A fog definition, e.g. `minecraft:fog_plains`.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub fog_identifier: Option<Identifier>,
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub water_surface_color: Option<u32>,
+    /// From 0 to 1.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub water_surface_transparency: Option<f64>,
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub water_fog_color: Option<u32>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub water_fog_distance: Option<f64>
+}
+
+fn hex_color(color: u32) -> Value {
+    Value::from(format!("#{:06X}", color))
+}
+
+impl ClientBiome {
+    /// The name of the client biome file, `woah:crystal_caves` is written to `biomes/crystal_caves.client_biome.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.client_biome.json", self.id.path())
+    }
+
+    /// The `RP/biomes_client.json` entry of the biome, keyed by its identifier.
+    pub fn biomes_client_entry(&self) -> Value {
+        let mut biome = Map::new();
+
+        for (key, color) in [
+            ("fog_color", self.fog_color),
+            ("water_surface_color", self.water_surface_color),
+            ("water_fog_color", self.water_fog_color)
+        ] {
+            if let Some(color) = color {
+                biome.insert(key.to_string(), hex_color(color));
+            }
+        }
+
+        if let Some(fog) = &self.fog_identifier {
+            biome.insert("fog_identifier".to_string(), Value::from(fog.render()));
+        }
+        if let Some(transparency) = self.water_surface_transparency {
+            biome.insert("water_surface_transparency".to_string(), json!(transparency));
+        }
+        if let Some(distance) = self.water_fog_distance {
+            biome.insert("water_fog_distance".to_string(), json!(distance));
+        }
+
+        json!({ self.id.render(): biome })
+    }
+
+    /// The client biome may override a vanilla biome, otherwise its biome has to be registered.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        if !registered.is_known_biome(&self.id) {
+            diagnostics.error(&self.id, "The client biome has no matching registered biome");
+        }
+
+        if let Some(fog) = &self.fog_identifier && !vanilla::FOGS.contains(&fog.render().as_str()) {
+            diagnostics.warning(&self.id, format!("The fog `{}` isn't a vanilla fog, so it has to be shipped separately", fog));
+        }
+    }
+}
+
+impl BedrockSerializable for ClientBiome {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut components = Map::new();
+
+        if let Some(color) = self.sky_color {
+            components.insert("minecraft:sky_color".to_string(), json!({ "sky_color": hex_color(color) }));
+        }
+        if let Some(color) = self.water_surface_color {
+            components.insert("minecraft:water_appearance".to_string(), json!({ "surface_color": hex_color(color) }));
+        }
+        if let Some(fog) = &self.fog_identifier {
+            components.insert("minecraft:fog_appearance".to_string(), json!({ "fog_identifier": fog.render() }));
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:client_biome": {
+                "description": { "identifier": self.id.render() },
+                "components": components
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biome::Biome;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn client_biome() {
+        let memory = testing::build(|events| {
+            events.biome_registration.subscribe(|reg| {
+                reg.register_biome(woah! {
+                    @Biome {
+                        id = "woah:crystal_caves";
+                    }
+                });
+            });
+            events.client_biome_registration.subscribe(|reg| {
+                reg.register_client_biome(woah! {
+                    @ClientBiome {
+                        id = "woah:crystal_caves";
+                        sky_color = 0x7BA4FFu32;
+                        fog_color = 0xC0D8FFu32;
+                        water_surface_color = 0x44AFF5u32;
+                    }
+                });
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_RP/biomes/crystal_caves.client_biome.json").unwrap()["minecraft:client_biome"]["components"], json!({
+            "minecraft:sky_color": { "sky_color": "#7BA4FF" },
+            "minecraft:water_appearance": { "surface_color": "#44AFF5" }
+        }));
+        assert_eq!(memory.read_json("WoahTest_RP/biomes_client.json").unwrap(), json!({
+            "biomes": {
+                "woah:crystal_caves": { "fog_color": "#C0D8FF", "water_surface_color": "#44AFF5" }
+            }
+        }));
+    }
+}
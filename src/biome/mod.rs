@@ -0,0 +1,274 @@
+pub mod client;
+pub mod registry;
+
+use std::fmt::{Display, Formatter};
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::core::validation::{Diagnostics, Registered};
+use crate::entity::filter::{EntityFilter, EntityFilterTest, FilterValue};
+use crate::hold_builders;
+use crate::worldgen::BlockReference;
+
+hold_builders!(Biome, Climate, SurfaceParameters);
+
+/// A custom biome, written to `BP/biomes/`. Its client side is a [ClientBiome](client::ClientBiome).
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Biome {
+    #[builder(default = "SemVer::new(1, 21, 110)")]
+    pub format_version: SemVer,
+    /// The file is named after the path of the identifier, so it has to be unique across namespaces.
+    pub id: Identifier,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub climate: Option<Climate>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub surface: Option<SurfaceParameters>,
+    /// Both vanilla tags, like `overworld` or `caves`, and custom ones, which filters can test for afterwards.
+    #[builder(setter(into = false), default = "Vec::new()")]
+    pub tags: Vec<BiomeTag>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Climate {
+    pub temperature: f64,
+    /// The amount of rain or snow, from 0 to 1.
+    pub downfall: f64,
+    /// The minimum and maximum snow layers during snowfall.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub snow_accumulation: Option<(f64, f64)>
+}
+
+/// The blocks the terrain of the biome is made of, written as a `minecraft:surface_builder`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SurfaceParameters {
+    #[builder(default = "SurfaceType::Overworld")]
+    pub surface_type: SurfaceType,
+    #[builder(default = "BlockReference::new(\"minecraft:grass_block\")")]
+    pub top_material: BlockReference,
+    #[builder(default = "BlockReference::new(\"minecraft:dirt\")")]
+    pub mid_material: BlockReference,
+    #[builder(default = "BlockReference::new(\"minecraft:stone\")")]
+    pub foundation_material: BlockReference,
+    #[builder(default = "BlockReference::new(\"minecraft:gravel\")")]
+    pub sea_floor_material: BlockReference,
+    #[builder(default = "BlockReference::new(\"minecraft:water\")")]
+    pub sea_material: BlockReference,
+    #[builder(setter(into = false), default = "7")]
+    pub sea_floor_depth: u32
+}
+
+/// The surface builders placing the materials of [SurfaceParameters].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceType {
+    Overworld,
+    FrozenOcean,
+    Swamp
+}
+
+/// A biome tag, either vanilla or declared by a registered [Biome]. Converts into a `has_biome_tag` [EntityFilter],
+/// so it can be used directly as the biome filter of spawn and feature rules.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BiomeTag(String);
+
+impl Biome {
+    /// The name of the biome file, `woah:crystal_caves` is written to `biomes/crystal_caves.biome.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.biome.json", self.id.path())
+    }
+
+    /// Every block the surface is made of.
+    pub fn blocks(&self) -> Vec<&BlockReference> {
+        match &self.surface {
+            Some(surface) => vec![
+                &surface.top_material, &surface.mid_material, &surface.foundation_material,
+                &surface.sea_floor_material, &surface.sea_material
+            ],
+            None => Vec::new()
+        }
+    }
+
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        for block in self.blocks() {
+            if !registered.is_known_block(&block.name) {
+                diagnostics.error(&self.id, format!("The surface references an unknown block `{}`", block.name));
+            }
+        }
+    }
+}
+
+impl SurfaceType {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            SurfaceType::Overworld => "minecraft:overworld",
+            SurfaceType::FrozenOcean => "minecraft:frozen_ocean",
+            SurfaceType::Swamp => "minecraft:swamp"
+        }
+    }
+}
+
+impl BiomeTag {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// A `has_biome_tag` filter test for this tag.
+    pub fn filter(&self) -> EntityFilter {
+        EntityFilter::Test(EntityFilterTest::with_value("has_biome_tag", self.clone()))
+    }
+}
+
+impl Display for BiomeTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for BiomeTag {
+    fn from(value: &str) -> Self {
+        BiomeTag::new(value)
+    }
+}
+
+impl From<String> for BiomeTag {
+    fn from(value: String) -> Self {
+        BiomeTag::new(value)
+    }
+}
+
+impl From<BiomeTag> for FilterValue {
+    fn from(value: BiomeTag) -> Self {
+        FilterValue::String(value.0)
+    }
+}
+
+impl From<BiomeTag> for EntityFilter {
+    fn from(value: BiomeTag) -> Self {
+        value.filter()
+    }
+}
+
+impl From<&BiomeTag> for EntityFilter {
+    fn from(value: &BiomeTag) -> Self {
+        value.filter()
+    }
+}
+
+impl BedrockSerializable for Climate {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut climate = Map::new();
+        climate.insert("temperature".to_string(), json!(self.temperature));
+        climate.insert("downfall".to_string(), json!(self.downfall));
+
+        if let Some((min, max)) = self.snow_accumulation {
+            climate.insert("snow_accumulation".to_string(), json!([min, max]));
+        }
+
+        Ok(Value::Object(climate))
+    }
+}
+
+impl BedrockSerializable for SurfaceParameters {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "builder": {
+                "type": self.surface_type.render(),
+                "top_material": self.top_material.bedrock_json()?,
+                "mid_material": self.mid_material.bedrock_json()?,
+                "foundation_material": self.foundation_material.bedrock_json()?,
+                "sea_floor_material": self.sea_floor_material.bedrock_json()?,
+                "sea_material": self.sea_material.bedrock_json()?,
+                "sea_floor_depth": self.sea_floor_depth
+            }
+        }))
+    }
+}
+
+impl BedrockSerializable for Biome {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut components = Map::new();
+
+        if let Some(climate) = &self.climate {
+            components.insert("minecraft:climate".to_string(), climate.bedrock_json()?);
+        }
+        if let Some(surface) = &self.surface {
+            components.insert("minecraft:surface_builder".to_string(), surface.bedrock_json()?);
+        }
+        if !self.tags.is_empty() {
+            components.insert("minecraft:tags".to_string(), json!({
+                "tags": self.tags.iter().map(|t| t.name()).collect::<Vec<&str>>()
+            }));
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:biome": {
+                "description": { "identifier": self.id.render() },
+                "components": components
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::block::Block;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn biome() {
+        let memory = testing::build(|events| {
+            events.block_registration.subscribe(|reg| {
+                reg.register_block(woah! {
+                    @Block {
+                        id = "woah:block";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.biome_registration.subscribe(|reg| {
+                reg.register_biome(woah! {
+                    @Biome {
+                        id = "woah:crystal_caves";
+                        climate = @Climate {
+                            temperature = 0.4;
+                            downfall = 0.2;
+                        };
+                        surface = @SurfaceParameters {
+                            top_material = "woah:block";
+                        };
+                        tags = map vec!["overworld", "woah_crystal"];
+                    }
+                });
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_BP/biomes/crystal_caves.biome.json").unwrap()["minecraft:biome"]["components"], json!({
+            "minecraft:climate": { "temperature": 0.4, "downfall": 0.2 },
+            "minecraft:surface_builder": {
+                "builder": {
+                    "type": "minecraft:overworld",
+                    "top_material": "woah:block",
+                    "mid_material": "minecraft:dirt",
+                    "foundation_material": "minecraft:stone",
+                    "sea_floor_material": "minecraft:gravel",
+                    "sea_material": "minecraft:water",
+                    "sea_floor_depth": 7
+                }
+            },
+            "minecraft:tags": { "tags": ["overworld", "woah_crystal"] }
+        }));
+    }
+}
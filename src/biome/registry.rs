@@ -0,0 +1,31 @@
+use log::info;
+use std::sync::Arc;
+use crate::biome::Biome;
+use crate::biome::client::ClientBiome;
+use crate::core::core_registry::CoreRegistry;
+
+/// The biome registry. Used for registering Biomes.
+pub struct BiomeRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl BiomeRegistry {
+    pub fn register_biome(&self, biome: Biome) {
+        info!("@biome Registering biome {}", &biome.id);
+
+        self.registry.register_biome(biome);
+    }
+}
+
+/// The client biome registry. Used for registering the colors and fog of Biomes.
+pub struct ClientBiomeRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl ClientBiomeRegistry {
+    pub fn register_client_biome(&self, biome: ClientBiome) {
+        info!("@biome Registering client biome {}", &biome.id);
+
+        self.registry.register_client_biome(biome);
+    }
+}
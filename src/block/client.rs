@@ -1,8 +1,6 @@
-use crate::code_gen::TEMPLATES;
-use crate::core::core_registry::REGISTRY;
-use crate::bedrock::BedrockSerializable;
 use crate::core::sprite::Sprite;
 use crate::core::utilities::Identifier;
+use serde_json::{json, Value};
 
 /// A struct for describing Block textures.
 #[derive(Clone, Debug)]
@@ -15,17 +13,11 @@ impl BlockTexture {
     pub fn new(id: Identifier, sprite: Sprite) -> Self {
         Self { id, sprite }
     }
-}
-
-impl BedrockSerializable for BlockTexture {
-    fn bedrock_serialize(&self) -> String {
-        let mut c = tera::Context::new();
 
-        let texture_path = format!("textures/block/{}/{}.png", &REGISTRY.pack_metadata.read().unwrap().name, &self.id.render_underscore());
-
-        c.insert("texture_path", &texture_path);
-        c.insert("id", &self.id.render());
-        
-        TEMPLATES.render("generic/atlas.json", &c).unwrap()
+    /// The `texture_data` entry of this texture in the atlas of the pack named `pack_name`.
+    pub fn atlas_entry(&self, pack_name: &str) -> (String, Value) {
+        (self.id.render(), json!({
+            "textures": format!("textures/block/{}/{}.png", pack_name, &self.id.render_underscore())
+        }))
     }
-}
\ No newline at end of file
+}
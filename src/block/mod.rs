@@ -5,16 +5,23 @@ pub mod registry;
 pub mod client;
 pub mod components;
 
-use std::collections::HashMap;
+use std::collections::HashSet;
 use derive_builder::Builder;
-use eo::sjson::{SJsonElement, SJsonValue, TransformHashMap};
+use eo::sjson::{SJsonElement, TransformHashMap};
 use crate::block::permutation::BlockPermutation;
 use crate::block::state::BlockState;
 use crate::block::traits::BlockTrait;
-use crate::code_gen::TEMPLATES;
 use crate::bedrock::BedrockSerializable;
-use crate::core::utilities::{Identifier, SemVer, BedrockSerializeVec};
+use crate::core::component::Components;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, ComponentMap, Identifier, SemVer};
+use crate::core::validation::{check_components_version, check_group_version, check_molang, Diagnostics, Registered};
+use crate::core::vanilla;
 use crate::hold_builders;
+use serde_json::{json, Value};
+use crate::molang::ast::{MolangExpr, MolangNamespace};
+use crate::molang::eval::{MolangContext, MolangEvalError};
+use crate::molang::Molang;
 
 hold_builders!(Block);
 
@@ -23,7 +30,8 @@ hold_builders!(Block);
 #[builder(setter(into))]
 pub struct Block {
     pub id: Identifier,
-    pub components: HashMap<String, SJsonValue>,
+    #[builder(setter(custom))]
+    pub components: ComponentMap,
     #[builder(default = "SemVer::latest()")]
     pub format_version: SemVer,
     #[builder(default = "Vec::new()")]
@@ -34,11 +42,25 @@ pub struct Block {
     pub traits: Vec<BlockTrait>
 }
 
+impl BlockBuilder {
+    /// Typed components, e.g. `components = v1_26_20::components((...));`, also set the format version of their module,
+    /// unless it's set explicitly. The validation reports an explicit format version other than the one of the components.
+    pub fn components(&mut self, components: impl Into<Components>) -> &mut Self {
+        let components = components.into();
+
+        if self.format_version.is_none() {
+            self.format_version = components.format_version;
+        }
+        self.components = Some(components.components);
+        self
+    }
+}
+
 impl Block {
     pub fn new(id: Identifier, components: Vec<SJsonElement>) -> Self {
         Self {
             id,
-            components: components.transform_hashmap(),
+            components: components.transform_hashmap().into(),
             format_version: SemVer::latest(),
             permutations: Vec::new(),
             states: Vec::new(),
@@ -73,26 +95,203 @@ impl Block {
             ..self.clone()
         }
     }
+
+    /// Every combination of the values of [Block::states], as contexts for evaluating permutation conditions.
+    pub fn state_combinations(&self) -> Vec<MolangContext> {
+        let mut combinations = vec![MolangContext::new()];
+
+        for state in &self.states {
+            combinations = combinations.into_iter().flat_map(|context| {
+                state.values().into_iter().map(move |value| context.clone().with_block_state(state.id().render(), value))
+            }).collect();
+        }
+
+        combinations
+    }
+
+    /// Returns the indices of the permutations whose conditions hold in the given context.
+    pub fn active_permutations(&self, context: &MolangContext) -> Result<Vec<usize>, MolangEvalError> {
+        let mut active = Vec::new();
+
+        for (i, permutation) in self.permutations.iter().enumerate() {
+            if permutation.condition().evaluate(&mut context.clone())?.as_bool()? {
+                active.push(i);
+            }
+        }
+
+        Ok(active)
+    }
+
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        check_components_version(diagnostics, &self.id, "block", &self.components, &self.format_version);
+        for permutation in &self.permutations {
+            check_group_version(diagnostics, &self.id, &format!("permutation `{}`", permutation.condition()), permutation.components(), &self.format_version);
+        }
+
+        let component_sets = std::iter::once(&self.components).chain(self.permutations.iter().map(|p| p.components()));
+
+        for components in component_sets {
+            for texture in material_textures(components.get("minecraft:material_instances")) {
+                if !registered.block_textures.contains(&texture) && !vanilla::TERRAIN_TEXTURES.contains(&texture.as_str()) {
+                    diagnostics.error(&self.id, format!("`minecraft:material_instances` references an unregistered block texture `{}`", texture));
+                }
+            }
+
+            if let Some(geometry) = block_geometry(components.get("minecraft:geometry")) && !registered.is_known_geometry(&geometry) {
+                diagnostics.error(&self.id, format!("`minecraft:geometry` references an unregistered geometry `{}`", geometry));
+            }
+
+            if let Some(path) = components.get("minecraft:loot").and_then(|l| l.as_str()) && !registered.is_known_loot_table(path) {
+                diagnostics.error(&self.id, format!("`minecraft:loot` references an unregistered loot table `{}`", path));
+            }
+        }
+
+        let states = self.states.iter().map(|s| s.id().render()).collect::<HashSet<String>>();
+
+        for permutation in &self.permutations {
+            check_molang(diagnostics, &self.id, permutation.condition());
+
+            for state in referenced_block_states(permutation.condition()) {
+                if !states.contains(&state) && !state.starts_with("minecraft:") {
+                    diagnostics.error(&self.id, format!("Permutation condition `{}` references an undeclared block state `{}`", permutation.condition(), state));
+                }
+            }
+        }
+    }
 }
 
 impl BedrockSerializable for Block {
-    fn bedrock_serialize(&self) -> String {
-        let id = &self.id.render();
-        let components = serde_json::to_string(&self.components).unwrap();
-        let format_version = &self.format_version.render_dotted();
-        let states = &self.states.serialize_vec().join(",");
-        let permutations = &self.permutations.serialize_vec().join(",");
-        let traits = &self.traits.serialize_vec().join(",");
-
-        let mut c = tera::Context::new();
-
-        c.insert("id", &id);
-        c.insert("components", &components);
-        c.insert("format_version", &format_version);
-        c.insert("states", &states);
-        c.insert("traits", &traits);
-        c.insert("permutations", &permutations);
-
-        TEMPLATES.render("block/block.json", &c).unwrap()
-    }
-}
\ No newline at end of file
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:block": {
+                "description": {
+                    "identifier": self.id.render(),
+                    "states": self.states.serialize_object()?,
+                    "traits": self.traits.serialize_object()?
+                },
+                "components": self.components.to_value()?,
+                "permutations": self.permutations.serialize_vec()?
+            }
+        }))
+    }
+}
+
+/// `minecraft:geometry` is either the geometry identifier, or `{ "identifier": ... }`.
+fn block_geometry(geometry: Option<&Value>) -> Option<String> {
+    match geometry {
+        Some(Value::String(id)) => Some(id.clone()),
+        Some(Value::Object(geometry)) => geometry.get("identifier").and_then(|id| id.as_str()).map(|id| id.to_string()),
+        _ => None
+    }
+}
+
+/// Instances either define a `texture`, or point at another instance by name, which isn't a texture reference.
+fn material_textures(instances: Option<&Value>) -> Vec<String> {
+    match instances {
+        Some(Value::Object(instances)) => instances.values()
+            .filter_map(|instance| instance.get("texture").and_then(|t| t.as_str()).map(|t| t.to_string()))
+            .collect(),
+        _ => Vec::new()
+    }
+}
+
+fn referenced_block_states(molang: &Molang) -> Vec<String> {
+    let mut states = Vec::new();
+
+    if let Ok(expr) = molang.expr() {
+        expr.walk(&mut |e| {
+            if let MolangExpr::Call(access, args) = e {
+                let name = access.name();
+                if access.namespace == MolangNamespace::Query && (name == "block_state" || name == "has_block_state") && let Some(MolangExpr::String(state)) = args.first() {
+                    states.push(state.clone());
+                }
+            }
+        });
+    }
+
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    
+    use crate::block::components::v1_26_20::LightEmission;
+    use crate::block::state::BlockStateType;
+    use crate::block::traits::PlacementDirectionState;
+    use crate::woah;
+
+    fn block() -> Block {
+        woah! {
+            @Block {
+                id = "woah:block";
+                components = sjson! {};
+                states = vec![
+                    BlockState::new("woah:val".into(), BlockStateType::Range(0..=5)),
+                    BlockState::new("woah:toggle".into(), BlockStateType::Boolean)
+                ];
+                traits = vec![
+                    BlockTrait::PlacementDirection {
+                        enabled_states: vec![PlacementDirectionState::CardinalDirection],
+                        y_rotation_offset: 0
+                    }
+                ];
+                permutations = vec![
+                    BlockPermutation::new(Molang::new("q.block_state('woah:val') > 2"), sjson! {}),
+                    BlockPermutation::new(Molang::new("q.block_state('woah:toggle') && q.block_state('woah:val') == 0"), sjson! {})
+                ];
+            }
+        }
+    }
+
+    #[test]
+    fn description() {
+        assert_eq!(block().bedrock_json().unwrap()["minecraft:block"]["description"], json!({
+            "identifier": "woah:block",
+            "states": {
+                "woah:val": { "values": { "min": 0, "max": 5 } },
+                "woah:toggle": [true, false]
+            },
+            "traits": {
+                "minecraft:placement_direction": {
+                    "enabled_states": ["minecraft:cardinal_direction"],
+                    "y_rotation_offset": 0
+                }
+            }
+        }));
+    }
+
+    #[test]
+    fn typed_components() {
+        let block = woah! {
+            @Block {
+                id = "woah:typed_block";
+                components = components::v1_26_20::components((
+                    LightEmission(10),
+                ));
+            }
+        };
+        assert_eq!(block.bedrock_json().unwrap()["format_version"], "1.26.20");
+        assert_eq!(block.bedrock_json().unwrap()["minecraft:block"]["components"], json!({ "minecraft:light_emission": 10 }));
+    }
+
+    #[test]
+    fn permutations() {
+        let block = block();
+        let combinations = block.state_combinations();
+        assert_eq!(combinations.len(), 12);
+
+        for context in &combinations {
+            let val = Molang::new("q.block_state('woah:val')").evaluate(&mut context.clone()).unwrap().as_number().unwrap();
+            let toggle = Molang::new("q.block_state('woah:toggle')").evaluate(&mut context.clone()).unwrap().as_bool().unwrap();
+
+            let mut expected = Vec::new();
+            if val > 2.0 { expected.push(0) }
+            if toggle && val == 0.0 { expected.push(1) }
+
+            assert_eq!(block.active_permutations(context).unwrap(), expected);
+        }
+    }
+}
@@ -1,14 +1,15 @@
-use std::collections::HashMap;
-use eo::sjson::{SJsonElement, SJsonMacro, SJsonValue, TransformHashMap};
-use crate::code_gen::TEMPLATES;
+use eo::sjson::SJsonMacro;
 use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::ComponentMap;
 use crate::molang::Molang;
+use serde_json::{json, Value};
 
 /// A struct for describing Block permutations.
 #[derive(Clone, Debug)]
 pub struct BlockPermutation {
     condition: Molang,
-    components: HashMap<String, SJsonValue>
+    components: ComponentMap
 }
 
 impl BlockPermutation {
@@ -18,19 +19,21 @@ impl BlockPermutation {
             components: components.into()
         }
     }
+
+    pub fn condition(&self) -> &Molang {
+        &self.condition
+    }
+
+    pub fn components(&self) -> &ComponentMap {
+        &self.components
+    }
 }
 
 impl BedrockSerializable for BlockPermutation {
-    fn bedrock_serialize(&self) -> String {
-        let components = serde_json::to_string(&self.components).unwrap();
-        
-        let condition = &self.condition.bedrock_serialize();
-        
-        let mut c = tera::Context::new();
-        
-        c.insert("condition", &condition);
-        c.insert("components", &components);
-        
-        TEMPLATES.render("block/block_permutation.json", &c).unwrap()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "condition": self.condition.bedrock_json()?,
+            "components": self.components.to_value()?
+        }))
     }
 }
\ No newline at end of file
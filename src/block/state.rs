@@ -1,7 +1,9 @@
 use std::ops::{RangeInclusive};
-use crate::code_gen::TEMPLATES;
 use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
 use crate::core::utilities::Identifier;
+use crate::molang::eval::MolangValue;
+use serde_json::{json, Value};
 
 /// A struct describing a Block state.
 #[derive(Clone, Debug)]
@@ -20,56 +22,17 @@ pub enum BlockStateType {
 }
 
 impl BedrockSerializable for BlockState {
-    fn bedrock_serialize(&self) -> String {
-        match &self.state_type {
-            BlockStateType::String(v) => {
-                let ser = v.into_iter().map(|x| format!("\"{x}\"")).collect::<Vec<String>>().join(",");
-                
-                let id = &self.id.render();
-                
-                let mut c = tera::Context::new();
-                
-                c.insert("id", &id);
-                c.insert("values", &ser);
-                
-                TEMPLATES.render("block/block_state_arr.json", &c).unwrap()
-            },
-            BlockStateType::Boolean => {
-                let ser = vec![true, false].into_iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",");
-
-                let id = &self.id.render();
-
-                let mut c = tera::Context::new();
-
-                c.insert("id", &id);
-                c.insert("values", &ser);
-
-                TEMPLATES.render("block/block_state_arr.json", &c).unwrap()
-            },
-            BlockStateType::Integer(v) => {
-                let ser = v.into_iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",");
-                
-                let id = &self.id.render();
-
-                let mut c = tera::Context::new();
-
-                c.insert("id", &id);
-                c.insert("values", &ser);
-
-                TEMPLATES.render("block/block_state_arr.json", &c).unwrap()
-            },
-            BlockStateType::Range(r) => {
-                let id = &self.id.render();
-                
-                let mut c = tera::Context::new();
-                
-                c.insert("id", &id);
-                c.insert("min", &r.clone().min().unwrap_or(0));
-                c.insert("max", &r.clone().max().unwrap_or(15));
-                
-                TEMPLATES.render("block/block_state_range.json", &c).unwrap()
-            }
-        }
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let values = match &self.state_type {
+            BlockStateType::String(v) => json!(v),
+            BlockStateType::Boolean => json!([true, false]),
+            BlockStateType::Integer(v) => json!(v),
+            BlockStateType::Range(r) => json!({
+                "values": { "min": r.clone().min().unwrap_or(0), "max": r.clone().max().unwrap_or(15) }
+            })
+        };
+
+        Ok(json!({ self.id.render(): values }))
     }
 }
 
@@ -79,4 +42,18 @@ impl BlockState {
             id, state_type
         }
     }
+
+    pub fn id(&self) -> &Identifier {
+        &self.id
+    }
+
+    /// All values this state can take, as returned by `q.block_state`.
+    pub fn values(&self) -> Vec<MolangValue> {
+        match &self.state_type {
+            BlockStateType::String(v) => v.iter().map(|x| MolangValue::from(x.as_str())).collect(),
+            BlockStateType::Boolean => vec![false.into(), true.into()],
+            BlockStateType::Integer(v) => v.iter().map(|x| MolangValue::from(*x)).collect(),
+            BlockStateType::Range(r) => r.clone().map(MolangValue::from).collect()
+        }
+    }
 }
\ No newline at end of file
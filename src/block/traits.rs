@@ -1,5 +1,6 @@
-use crate::code_gen::TEMPLATES;
 use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use serde_json::{json, Value};
 
 /// An enum describing Block traits.
 #[derive(Clone, Debug)]
@@ -35,7 +36,7 @@ pub enum PlacementPositionState {
 impl PlacementDirectionState {
     pub fn render(&self) -> String {
         match &self {
-            PlacementDirectionState::CardinalDirection => "minecraft_cardinal_direction",
+            PlacementDirectionState::CardinalDirection => "minecraft:cardinal_direction",
             PlacementDirectionState::FacingDirection => "minecraft:facing_direction"
         }.to_string()
     }
@@ -51,43 +52,31 @@ impl PlacementPositionState {
 }
 
 impl BedrockSerializable for PlacementDirectionState {
-    fn bedrock_serialize(&self) -> String {
-        self.render()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(Value::String(self.render()))
     }
 }
 
 impl BedrockSerializable for PlacementPositionState {
-    fn bedrock_serialize(&self) -> String {
-        self.render()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(Value::String(self.render()))
     }
 }
 
 impl BedrockSerializable for BlockTrait {
-    fn bedrock_serialize(&self) -> String {
-        match &self {
-            BlockTrait::PlacementDirection { enabled_states, y_rotation_offset } => {
-                let ser_states = enabled_states.into_iter().map(|x| format!("\"{}\"", x.bedrock_serialize())).collect::<Vec<String>>().join(",");
-                
-                let add = format!(",\"y_rotation_offset\": {}", y_rotation_offset);
-                
-                let mut c = tera::Context::new();
-                
-                c.insert("id", "minecraft:placement_direction");
-                c.insert("enabled_states", &ser_states);
-                c.insert("additional", &add);
-                
-                TEMPLATES.render("block/block_trait.json", &c).unwrap()
-            }
-            BlockTrait::PlacementPosition { enabled_states } => {
-                let ser_states = enabled_states.into_iter().map(|x| x.bedrock_serialize()).collect::<Vec<String>>().join(",");
-                let mut c = tera::Context::new();
-
-                c.insert("id", "minecraft:placement_position");
-                c.insert("enabled_states", &ser_states);
-                c.insert("additional", "");
-
-                TEMPLATES.render("block/block_trait.json", &c).unwrap()
-            }
-        }
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            BlockTrait::PlacementDirection { enabled_states, y_rotation_offset } => json!({
+                "minecraft:placement_direction": {
+                    "enabled_states": enabled_states.iter().map(|x| x.render()).collect::<Vec<String>>(),
+                    "y_rotation_offset": y_rotation_offset
+                }
+            }),
+            BlockTrait::PlacementPosition { enabled_states } => json!({
+                "minecraft:placement_position": {
+                    "enabled_states": enabled_states.iter().map(|x| x.render()).collect::<Vec<String>>()
+                }
+            })
+        })
     }
-}
\ No newline at end of file
+}
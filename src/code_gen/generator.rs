@@ -1,13 +1,24 @@
-use std::path::PathBuf;
 use std::sync::Arc;
 use crate::block::Block;
 use crate::block::client::BlockTexture;
-use crate::code_gen::Targets;
 use crate::core::build_target::BuildTarget;
+use crate::core::error::WoahResult;
 use crate::core::metadata::PackMetadata;
 use crate::entity::Entity;
+use crate::entity::client::ClientEntity;
 use crate::item::client::ItemTexture;
 use crate::item::Item;
+use crate::lang::Translations;
+use crate::recipe::Recipe;
+use crate::loot::LootTable;
+use crate::geometry::Geometry;
+use crate::animation::{Animation, ClientAnimation};
+use crate::animation::controller::AnimationController;
+use crate::sound::Sounds;
+use crate::worldgen::Feature;
+use crate::worldgen::rule::FeatureRule;
+use crate::biome::Biome;
+use crate::biome::client::ClientBiome;
 
 pub trait GeneratorInstance {
     /// A function that returns an Arc with the generator in it.
@@ -25,16 +36,39 @@ type Target = Arc<dyn BuildTarget>;
 
 pub trait PackGenerator : Send + Sync {
     /// Meant to generate necessary folders etc.
-    fn build_prepare(&self, target: Target, metadata: &PackMetadata);
+    fn build_prepare(&self, target: Target, metadata: &PackMetadata) -> WoahResult<()>;
     /// Meant to generate the main parts of the pack, like the manifest in Bedrock add-ons.
-    fn build_manifest(&self, target: Target, metadata: &PackMetadata);
+    fn build_manifest(&self, target: Target, metadata: &PackMetadata) -> WoahResult<()>;
     /// Meant to generate stuff for items, not client-sided.
-    fn build_items(&self, target: Target, items: Vec<Item>, metadata: &PackMetadata);
+    fn build_items(&self, target: Target, items: Vec<Item>, metadata: &PackMetadata) -> WoahResult<()>;
     /// Meant to generate client-sided stuff for items.
-    fn build_client_items(&self, target: Target, items: Vec<ItemTexture>, metadata: &PackMetadata);
+    fn build_client_items(&self, target: Target, items: Vec<ItemTexture>, metadata: &PackMetadata) -> WoahResult<()>;
     /// Meant to generate stuff for blocks, not client-sided.
-    fn build_blocks(&self, target: Target, blocks: Vec<Block>, metadata: &PackMetadata);
+    fn build_blocks(&self, target: Target, blocks: Vec<Block>, metadata: &PackMetadata) -> WoahResult<()>;
     /// Meant to generate client-sided stuff for blocks.
-    fn build_client_blocks(&self, target: Target, blocks: Vec<BlockTexture>, metadata: &PackMetadata);
-    fn build_entities(&self, target: Target, entities: Vec<Entity>, metadata: &PackMetadata);
+    fn build_client_blocks(&self, target: Target, blocks: Vec<BlockTexture>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate stuff for entities, not client-sided.
+    fn build_entities(&self, target: Target, entities: Vec<Entity>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate client-sided stuff for entities.
+    fn build_client_entities(&self, target: Target, entities: Vec<ClientEntity>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate recipes.
+    fn build_recipes(&self, target: Target, recipes: Vec<Recipe>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate loot tables.
+    fn build_loot_tables(&self, target: Target, loot_tables: Vec<LootTable>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate geometry models.
+    fn build_geometries(&self, target: Target, geometries: Vec<Geometry>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate animations and animation controllers, not client-sided.
+    fn build_animations(&self, target: Target, animations: Vec<Animation>, controllers: Vec<AnimationController>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate client-sided animations and animation controllers.
+    fn build_client_animations(&self, target: Target, animations: Vec<ClientAnimation>, controllers: Vec<AnimationController>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate sound files, definitions and their bindings.
+    fn build_sounds(&self, target: Target, sounds: Sounds, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate worldgen features and the feature rules placing them.
+    fn build_features(&self, target: Target, features: Vec<Feature>, rules: Vec<FeatureRule>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate biomes, not client-sided.
+    fn build_biomes(&self, target: Target, biomes: Vec<Biome>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate client-sided stuff for biomes.
+    fn build_client_biomes(&self, target: Target, biomes: Vec<ClientBiome>, metadata: &PackMetadata) -> WoahResult<()>;
+    /// Meant to generate the translations of every locale.
+    fn build_lang(&self, target: Target, translations: Translations, metadata: &PackMetadata) -> WoahResult<()>;
 }
\ No newline at end of file
@@ -1,31 +1,33 @@
 pub mod generator;
 
-use std::collections::VecDeque;
-use crate::core::core_registry::REGISTRY;
+use crate::core::core_registry::CoreRegistry;
 use crate::core::metadata::PackMetadata;
-use crate::core::utilities::{BedrockSerializeVec, JsonFormat};
-use crate::bedrock::BedrockSerializable;
-use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use include_dir::{include_dir, Dir};
-use tera::Tera;
 use uuid::Uuid;
-use proc_macros::template_encoder;
 use crate::code_gen::generator::{GeneratorInstance, PackGenerator};
 use crate::bedrock::bedrock_generator::WoahBedrockGenerator;
-use crate::core::build_target::BuildTarget;
+use crate::bedrock::schema::{validate_pack_files, SchemaValidator};
+use crate::core::build_target::{BuildTarget, RecordingTarget};
+use crate::core::error::{BuildPhase, WoahError, WoahResult, WoahResultExt};
+use crate::core::validation::validate_registry;
 
 type Generators = Vec<Arc<dyn PackGenerator>>;
 type Targets = Vec<Arc<dyn BuildTarget>>;
 
+/// The code generation state of a single build.
 pub struct CodeGen {
+    registry: Arc<CoreRegistry>,
     targets: RwLock<Targets>,
-    generators: RwLock<Generators>
+    generators: RwLock<Generators>,
+    schema_validation: RwLock<bool>
 }
 
+const UUID_CACHE: &str = "cache.woah";
+
+/// The manifest UUIDs of a pack. They're kept between builds, so the game keeps recognizing the pack as the same one.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WoahConfig {
     pub uuid1b: String,
@@ -36,57 +38,60 @@ pub struct WoahConfig {
 }
 
 impl WoahConfig {
-    pub fn read() -> WoahConfig {
-        serde_json::from_str(
-            &fs::read_to_string("./cache.woah").unwrap()
-        ).unwrap()
+    pub fn generate() -> WoahConfig {
+        WoahConfig {
+            uuid1b: Uuid::new_v4().to_string(),
+            uuid2b: Uuid::new_v4().to_string(),
+            uuid3b: Uuid::new_v4().to_string(),
+            uuid1r: Uuid::new_v4().to_string(),
+            uuid2r: Uuid::new_v4().to_string()
+        }
     }
-}
-
-static TEMPLATES_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/templates");
-
-lazy_static! {
-    pub static ref TEMPLATES: Tera = {
-        let mut tera = Tera::default();
-
-        let mut templates = TEMPLATES_DIR
-            .entries().into_iter().collect::<VecDeque<_>>();
 
-        let mut t: Vec<(String, String)> = vec![];
-
-        while !templates.is_empty() {
-            let entry = templates.pop_front().unwrap();
-            if let Some(file) = entry.as_file() {
-                let path = file.path().to_str().unwrap().to_string();
-                let raw_content = file.contents_utf8().unwrap();
-
-                let sanitized = raw_content.replace('\r', "");
-
-                t.push((path, sanitized));
-            };
-            if let Some(dir) = entry.as_dir() {
-                templates.extend(
-                    dir.entries()
-                )
-            }
+    /// The UUIDs of a pack, read from the `cache.woah` cache of the target or generated and added to it on the first build.<br>
+    /// The cache is keyed by pack name. A cache from before that, holding the UUIDs of a single pack, is taken over by the first pack reading it.
+    pub fn for_pack(metadata: &PackMetadata, target: &Arc<dyn BuildTarget>) -> WoahResult<WoahConfig> {
+        let mut changed = false;
+        let mut packs: BTreeMap<String, WoahConfig> = match target.read_cache(UUID_CACHE) {
+            Some(content) => match serde_json::from_slice::<WoahConfig>(&content) {
+                Ok(single) => {
+                    changed = true;
+                    BTreeMap::from([(metadata.name.clone(), single)])
+                }
+                Err(_) => serde_json::from_slice(&content).at_path(UUID_CACHE)?
+            },
+            None => BTreeMap::new()
+        };
+
+        let config = packs.entry(metadata.name.clone())
+            .or_insert_with(|| {
+                changed = true;
+                WoahConfig::generate()
+            })
+            .clone();
+
+        if changed {
+            target.write_cache(UUID_CACHE, serde_json::to_string_pretty(&packs)?.as_bytes())?;
         }
 
-        tera.add_raw_templates(t).unwrap();
-        
-        tera
-    };
-
-    pub static ref CODE_GEN: CodeGen = CodeGen {
-        targets: RwLock::new(vec![]),
-        generators: RwLock::new(
-            vec![
-                WoahBedrockGenerator.generator()
-            ]
-        )
-    };
+        Ok(config)
+    }
 }
 
 impl CodeGen {
+    pub(crate) fn new(registry: Arc<CoreRegistry>) -> Self {
+        Self {
+            registry,
+            targets: RwLock::new(vec![]),
+            generators: RwLock::new(
+                vec![
+                    WoahBedrockGenerator.generator()
+                ]
+            ),
+            schema_validation: RwLock::new(false)
+        }
+    }
+
     pub fn set_targets(&self, targets: Targets) {
         *self.targets.write().unwrap() = targets;
     }
@@ -95,119 +100,388 @@ impl CodeGen {
     //     (&*self.output_path.read().unwrap()).clone()
     // }
 
-
-    pub fn cwd(&self) -> PathBuf {
-        PathBuf::from("./")
-    }
-
     pub fn set_generators(&self, generators: Generators) {
         *self.generators.write().unwrap() = generators;
     }
 
     pub fn generators(&self) -> Generators {
-        (&*self.generators.read().unwrap()).clone()
+        self.generators.read().unwrap().clone()
+    }
+
+    /// Enables validating the generated files against the bundled JSON schemas, see [CodeGen::validate_schemas].
+    pub fn set_schema_validation(&self, enabled: bool) {
+        *self.schema_validation.write().unwrap() = enabled;
     }
 
     pub fn targets(&self) -> Targets {
-        (&*self.targets.read().unwrap()).clone()
+        self.targets.read().unwrap().clone()
     }
 
     pub fn metadata(&self) -> PackMetadata {
-        (&*REGISTRY.pack_metadata.read().unwrap()).clone()
+        self.registry.pack_metadata.read().unwrap().clone()
     }
 
-    pub fn build(&self) -> anyhow::Result<()> {
+    /// Runs the validation phase, logging warnings and failing on errors.
+    pub fn validate(&self) -> WoahResult<()> {
+        let diagnostics = validate_registry(&self.registry);
+
+        for warning in diagnostics.warnings() {
+            log::warn!("{}", warning);
+        }
+
+        if diagnostics.has_errors() {
+            return Err(WoahError::from(diagnostics)).in_phase(BuildPhase::Validation);
+        }
+
+        Ok(())
+    }
+
+    /// Runs the schema validation phase on the files generated for a target, logging warnings and failing on errors.
+    pub fn validate_schemas(&self, validator: &SchemaValidator, metadata: &PackMetadata, target: &Arc<dyn BuildTarget>, files: &[(PathBuf, Vec<u8>)]) -> WoahResult<()> {
+        let diagnostics = validate_pack_files(validator, metadata, target, files).in_phase(BuildPhase::SchemaValidation)?;
+
+        for warning in diagnostics.warnings() {
+            log::warn!("{}", warning);
+        }
+
+        if diagnostics.has_errors() {
+            return Err(WoahError::from(diagnostics)).in_phase(BuildPhase::SchemaValidation);
+        }
+
+        Ok(())
+    }
+
+    /// Builds the pack for every target. The first error aborts the build, tagged with the phase it happened in.
+    pub fn build(&self) -> WoahResult<()> {
+        self.validate()?;
+
         let generators = self.generators();
+        let validator = (*self.schema_validation.read().unwrap()).then(SchemaValidator::bundled);
 
         for target in self.targets() {
-            fs::create_dir_all(target.path())?;
+            if let Err(err) = self.build_target(target.clone(), &generators, validator.as_ref()) {
+                target.abort();
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
 
-            let metadata = self.metadata();
+    /// Builds the pack into a single target. The target is aborted by [CodeGen::build] when this fails.
+    fn build_target(&self, target: Arc<dyn BuildTarget>, generators: &Generators, validator: Option<&SchemaValidator>) -> WoahResult<()> {
+        let recording = validator.map(|_| Arc::new(RecordingTarget::new(target.clone())));
+        let target: Arc<dyn BuildTarget> = match &recording {
+            Some(recording) => recording.clone(),
+            None => target
+        };
 
-            generators.iter().for_each(|generator| {
-                generator.build_prepare(
-                    target.clone(),
-                    &metadata
-                )
-            });
+        target.prepare().in_phase(BuildPhase::Prepare)?;
+        target.create_dir(&target.path()).in_phase(BuildPhase::Prepare)?;
+
+        let mut metadata = self.metadata();
+        metadata.uuids = Some(WoahConfig::for_pack(&metadata, &target).in_phase(BuildPhase::Prepare)?);
+
+        for generator in generators {
+            generator.build_prepare(
+                target.clone(),
+                &metadata
+            ).in_phase(BuildPhase::Prepare)?;
+        }
+
+        self.build_manifest(generators, &metadata, target.clone()).in_phase(BuildPhase::Manifest)?;
+
+        self.build_items(generators, &metadata, target.clone())?;
+
+        self.build_blocks(generators, &metadata, target.clone())?;
+
+        self.build_entities(generators, &metadata, target.clone())?;
 
-            self.try_generate_uuid();
+        self.build_recipes(generators, &metadata, target.clone()).in_phase(BuildPhase::Recipes)?;
 
-            self.build_manifest(&generators, &metadata, target.clone());
+        self.build_loot_tables(generators, &metadata, target.clone()).in_phase(BuildPhase::LootTables)?;
 
-            self.build_items(&generators, &metadata, target.clone());
+        self.build_geometries(generators, &metadata, target.clone()).in_phase(BuildPhase::Geometries)?;
 
-            self.build_blocks(&generators, &metadata, target.clone());
+        self.build_animations(generators, &metadata, target.clone())?;
 
-            self.build_entities(&generators, &metadata, target.clone());
+        self.build_sounds(generators, &metadata, target.clone()).in_phase(BuildPhase::Sounds)?;
+
+        self.build_features(generators, &metadata, target.clone()).in_phase(BuildPhase::Features)?;
+
+        self.build_biomes(generators, &metadata, target.clone())?;
+
+        self.build_lang(generators, &metadata, target.clone()).in_phase(BuildPhase::Lang)?;
+
+        if let (Some(validator), Some(recording)) = (validator, &recording) {
+            self.validate_schemas(validator, &metadata, &target, &recording.files())?;
         }
 
+        target.finalize(&metadata).in_phase(BuildPhase::Packaging)?;
+
         Ok(())
     }
 
-    pub fn build_manifest(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
+    pub fn build_manifest(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
         for generator in generators {
             generator.build_manifest(
                 target.clone(),
                 metadata,
-            )
+            )?;
         }
-    }
 
-    pub fn try_generate_uuid(&self) {
-        let config_path = self.cwd().join("cache.woah");
-        if let Err(_) = fs::read_to_string(&config_path) {
-            fs::write(&config_path, serde_json::to_string_pretty(&WoahConfig {
-                uuid1b: Uuid::new_v4().to_string(),
-                uuid2b: Uuid::new_v4().to_string(),
-                uuid3b: Uuid::new_v4().to_string(),
-                uuid1r: Uuid::new_v4().to_string(),
-                uuid2r: Uuid::new_v4().to_string()
-            }).unwrap()).unwrap();
-        }
+        Ok(())
     }
 
-    pub fn build_items(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
-        let items = REGISTRY.items.read().unwrap().clone();
+    pub fn build_items(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let items = self.registry.items.read().unwrap().clone();
 
         for generator in generators {
-            generator.build_items(target.clone(), items.clone(), metadata)
+            generator.build_items(target.clone(), items.clone(), metadata).in_phase(BuildPhase::Items)?;
         }
 
-        self.build_client_items(generators, metadata, target);
+        self.build_client_items(generators, metadata, target)
     }
     
-    pub fn build_client_items(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
-        let items = REGISTRY.item_textures.read().unwrap().clone();
+    pub fn build_client_items(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let items = self.registry.item_textures.read().unwrap().clone();
 
         for generator in generators {
-            generator.build_client_items(target.clone(), items.clone(), metadata);
+            generator.build_client_items(target.clone(), items.clone(), metadata).in_phase(BuildPhase::ClientItems)?;
         }
+
+        Ok(())
     }
     
-    pub fn build_blocks(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
-        let blocks = REGISTRY.blocks.read().unwrap().clone();
+    pub fn build_blocks(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let blocks = self.registry.blocks.read().unwrap().clone();
         
         for generator in generators {
-            generator.build_blocks(target.clone(), blocks.clone(), metadata)
+            generator.build_blocks(target.clone(), blocks.clone(), metadata).in_phase(BuildPhase::Blocks)?;
+        }
+
+        self.build_block_textures(generators, metadata, target)
+    }
+
+    pub fn build_block_textures(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let blocks = self.registry.block_textures.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_client_blocks(target.clone(), blocks.clone(), metadata).in_phase(BuildPhase::ClientBlocks)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_entities(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let entities = self.registry.entities.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_entities(target.clone(), entities.clone(), metadata).in_phase(BuildPhase::Entities)?;
+        }
+
+        self.build_client_entities(generators, metadata, target)
+    }
+
+    pub fn build_client_entities(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let entities = self.registry.client_entities.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_client_entities(target.clone(), entities.clone(), metadata).in_phase(BuildPhase::ClientEntities)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_recipes(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let recipes = self.registry.recipes.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_recipes(target.clone(), recipes.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_loot_tables(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let loot_tables = self.registry.loot_tables.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_loot_tables(target.clone(), loot_tables.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_geometries(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let geometries = self.registry.geometries.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_geometries(target.clone(), geometries.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_animations(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let animations = self.registry.animations.read().unwrap().clone();
+        let controllers = self.registry.animation_controllers.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_animations(target.clone(), animations.clone(), controllers.clone(), metadata).in_phase(BuildPhase::Animations)?;
+        }
+
+        self.build_client_animations(generators, metadata, target)
+    }
+
+    pub fn build_client_animations(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let animations = self.registry.client_animations.read().unwrap().clone();
+        let controllers = self.registry.client_animation_controllers.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_client_animations(target.clone(), animations.clone(), controllers.clone(), metadata).in_phase(BuildPhase::ClientAnimations)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_sounds(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let sounds = self.registry.sounds.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_sounds(target.clone(), sounds.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build_features(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let features = self.registry.features.read().unwrap().clone();
+        let rules = self.registry.feature_rules.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_features(target.clone(), features.clone(), rules.clone(), metadata)?;
         }
 
-        self.build_block_textures(generators, metadata, target);
+        Ok(())
     }
 
-    pub fn build_block_textures(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
-        let blocks = REGISTRY.block_textures.read().unwrap().clone();
+    pub fn build_biomes(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let biomes = self.registry.biomes.read().unwrap().clone();
 
         for generator in generators {
-            generator.build_client_blocks(target.clone(), blocks.clone(), metadata);
+            generator.build_biomes(target.clone(), biomes.clone(), metadata).in_phase(BuildPhase::Biomes)?;
         }
+
+        self.build_client_biomes(generators, metadata, target)
     }
 
-    pub fn build_entities(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) {
-        let entities = REGISTRY.entities.read().unwrap().clone();
+    pub fn build_client_biomes(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let biomes = self.registry.client_biomes.read().unwrap().clone();
 
         for generator in generators {
-            generator.build_entities(target.clone(), entities.clone(), metadata);
+            generator.build_client_biomes(target.clone(), biomes.clone(), metadata).in_phase(BuildPhase::ClientBiomes)?;
         }
+
+        Ok(())
     }
-}
\ No newline at end of file
+
+    pub fn build_lang(&self, generators: &Generators, metadata: &PackMetadata, target: Arc<dyn BuildTarget>) -> WoahResult<()> {
+        let translations = self.registry.translations.read().unwrap().clone();
+
+        for generator in generators {
+            generator.build_lang(target.clone(), translations.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::bedrock::TargetInstance;
+    use crate::core::build_target::MemoryTarget;
+    use crate::core::PackProcessingEvents;
+    use crate::core::testing;
+    use crate::core::utilities::HoldBuilder;
+    use crate::item::Item;
+    use crate::woah;
+
+    #[test]
+    fn pack_uuids() {
+        let memory = MemoryTarget::new();
+        let target: Arc<dyn BuildTarget> = memory.target();
+        let pack = |name: &str| PackMetadata { name: name.to_string(), ..PackMetadata::default() };
+
+        let first = WoahConfig::for_pack(&pack("First"), &target).unwrap();
+        let second = WoahConfig::for_pack(&pack("Second"), &target).unwrap();
+        assert_ne!(first.uuid1b, second.uuid1b);
+        assert_eq!(WoahConfig::for_pack(&pack("First"), &target).unwrap().uuid1b, first.uuid1b);
+
+        // The cache of a memory target stays in memory, and isn't one of the generated files.
+        assert!(memory.paths().is_empty());
+        assert_ne!(WoahConfig::for_pack(&pack("First"), &(MemoryTarget::new().target() as Arc<dyn BuildTarget>)).unwrap().uuid1b, first.uuid1b);
+    }
+
+    #[test]
+    fn repeated_builds() {
+        fn initialize(events: &PackProcessingEvents) {
+            events.item_registration.subscribe(|reg| {
+                reg.register_item(woah! {
+                    @Item {
+                        id = "woah:ruby";
+                        components = sjson! {};
+                    }
+                })
+            });
+        }
+
+        // Registrations don't leak between packs, so building again doesn't report duplicates.
+        let first = testing::build(initialize);
+        let second = testing::build(initialize);
+        assert!(first.read("WoahTest_BP/items/woah_ruby.json").is_some());
+        assert_eq!(first.paths(), second.paths());
+    }
+
+    #[test]
+    fn custom_target() {
+        // A target without the `bp` and `rp` folders of the bedrock generator.
+        struct KeylessTarget;
+
+        impl BuildTarget for KeylessTarget {
+            fn path(&self) -> PathBuf {
+                PathBuf::from("keyless")
+            }
+
+            fn path_keyed(&self, _key: &str) -> Option<PathBuf> {
+                None
+            }
+
+            fn create_dir(&self, _path: &Path) -> WoahResult<()> {
+                Ok(())
+            }
+
+            fn read_cache(&self, _name: &str) -> Option<Vec<u8>> {
+                None
+            }
+
+            fn write_cache(&self, _name: &str, _contents: &[u8]) -> WoahResult<()> {
+                Ok(())
+            }
+        }
+
+        let registry = Arc::new(CoreRegistry::default());
+        registry.set_pack_metadata(PackMetadata { name: "Keyless".to_string(), ..PackMetadata::default() });
+
+        let code_gen = CodeGen::new(registry);
+        code_gen.set_targets(vec![Arc::new(KeylessTarget)]);
+
+        let err = code_gen.build().unwrap_err();
+        assert_eq!(err.phase, Some(BuildPhase::Prepare));
+        assert_eq!(err.path, Some(PathBuf::from("keyless")));
+        assert_eq!(err.kind.to_string(), "The build target has no `bp` path");
+    }
+}
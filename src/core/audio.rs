@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::core::error::{WoahError, WoahResult, WoahResultExt};
+
+/// The audio formats Bedrock plays. Files are shipped as they are, so they have to be in one of these already.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioFormat {
+    Ogg,
+    Wav,
+    Fsb
+}
+
+/// A struct for describing sound files.
+#[derive(Clone, Debug)]
+pub struct Audio {
+    bytes: Vec<u8>,
+    format: AudioFormat
+}
+
+impl Audio {
+    pub fn read(path: impl Into<PathBuf>) -> WoahResult<Self> {
+        let path = path.into();
+        let bytes = fs::read(&path).at_path(&path)?;
+
+        Self::from_bytes(bytes).at_path(path)
+    }
+
+    /// The format is detected from the file header, not from the extension.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> WoahResult<Self> {
+        let bytes = bytes.into();
+
+        let format = if bytes.starts_with(b"OggS") {
+            AudioFormat::Ogg
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WAVE") {
+            AudioFormat::Wav
+        } else if bytes.starts_with(b"FSB5") {
+            AudioFormat::Fsb
+        } else {
+            return Err(WoahError::other("Unsupported audio format, expected an Ogg Vorbis, WAV or FSB file"));
+        };
+
+        Ok(Self { bytes, format })
+    }
+
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AudioFormat {
+    pub fn extension(&self) -> &'static str {
+        match &self {
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Fsb => "fsb"
+        }
+    }
+}
@@ -1,7 +1,186 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use serde_json::Value;
+use crate::core::error::{WoahResult, WoahResultExt};
+use crate::core::metadata::PackMetadata;
 
+/// Where a pack is generated. Generators write through [BuildTarget::write] and [BuildTarget::create_dir], which go to disk by default.
 pub trait BuildTarget : Send + Sync {
     fn path(&self) -> PathBuf;
 
     fn path_keyed(&self, key: &str) -> Option<PathBuf>;
-}
\ No newline at end of file
+
+    fn create_dir(&self, path: &Path) -> WoahResult<()> {
+        fs::create_dir_all(path).at_path(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> WoahResult<()> {
+        fs::write(path, contents).at_path(path)
+    }
+
+    /// Runs before any generator writes to this target, e.g. to clear the leftovers of a previous build.
+    fn prepare(&self) -> WoahResult<()> {
+        Ok(())
+    }
+
+    /// Runs after every generator has finished with this target, e.g. to package the generated folders.
+    fn finalize(&self, _metadata: &PackMetadata) -> WoahResult<()> {
+        Ok(())
+    }
+
+    /// Runs when building into this target failed, e.g. to remove the partially written files.
+    fn abort(&self) {}
+
+    /// Reads a file kept between builds, like the manifest UUIDs in `cache.woah`. Goes to the working directory by default.
+    fn read_cache(&self, name: &str) -> Option<Vec<u8>> {
+        fs::read(name).ok()
+    }
+
+    fn write_cache(&self, name: &str, contents: &[u8]) -> WoahResult<()> {
+        fs::write(name, contents).at_path(name)
+    }
+}
+
+/// A target which keeps every generated file in memory instead of writing it to disk, for inspecting the output in tests.<br>
+/// Clones share the same files, so keep one around and pass [.target](crate::bedrock::TargetInstance::target) to the pack.
+/// The build cache is kept in memory too, so the manifest UUIDs only stay the same for builds into the same target.
+/// ```rust
+/// use woah::core::build_target::MemoryTarget;
+///
+/// let memory = MemoryTarget::new();
+/// // Return `memory.target()` from `PackImplementation::targets`, then after `Woah::pack`:
+/// let manifest = memory.read_json("woah_BP/manifest.json");
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryTarget {
+    files: Arc<RwLock<BTreeMap<PathBuf, Vec<u8>>>>,
+    cache: Arc<RwLock<BTreeMap<String, Vec<u8>>>>
+}
+
+impl MemoryTarget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every generated file, keyed by its path relative to the target.
+    pub fn files(&self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.files.read().unwrap().clone()
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.read().unwrap().keys().cloned().collect()
+    }
+
+    pub fn read(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.files.read().unwrap().get(path.as_ref()).cloned()
+    }
+
+    pub fn read_string(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.read(path).and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+
+    pub fn read_json(&self, path: impl AsRef<Path>) -> Option<Value> {
+        self.read(path).and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+
+    pub fn clear(&self) {
+        self.files.write().unwrap().clear();
+    }
+}
+
+impl BuildTarget for MemoryTarget {
+    fn path(&self) -> PathBuf {
+        PathBuf::new()
+    }
+
+    fn path_keyed(&self, _key: &str) -> Option<PathBuf> {
+        Some(PathBuf::new())
+    }
+
+    fn create_dir(&self, _path: &Path) -> WoahResult<()> {
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> WoahResult<()> {
+        self.files.write().unwrap().insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn read_cache(&self, name: &str) -> Option<Vec<u8>> {
+        self.cache.read().unwrap().get(name).cloned()
+    }
+
+    fn write_cache(&self, name: &str, contents: &[u8]) -> WoahResult<()> {
+        self.cache.write().unwrap().insert(name.to_string(), contents.to_vec());
+        Ok(())
+    }
+}
+
+/// Forwards everything to another target, keeping a copy of every JSON file written through it.
+/// Used by the schema validation stage, see [PackImplementation::schema_validation](crate::core::PackImplementation::schema_validation).
+pub struct RecordingTarget {
+    target: Arc<dyn BuildTarget>,
+    files: RwLock<Vec<(PathBuf, Vec<u8>)>>
+}
+
+impl RecordingTarget {
+    pub fn new(target: Arc<dyn BuildTarget>) -> Self {
+        Self {
+            target,
+            files: RwLock::new(Vec::new())
+        }
+    }
+
+    /// The recorded JSON files, by their full path, in the order they were written.
+    pub fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.files.read().unwrap().clone()
+    }
+}
+
+impl BuildTarget for RecordingTarget {
+    fn path(&self) -> PathBuf {
+        self.target.path()
+    }
+
+    fn path_keyed(&self, key: &str) -> Option<PathBuf> {
+        self.target.path_keyed(key)
+    }
+
+    fn create_dir(&self, path: &Path) -> WoahResult<()> {
+        self.target.create_dir(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> WoahResult<()> {
+        self.target.write(path, contents)?;
+
+        if path.extension().is_some_and(|e| e == "json") {
+            let mut files = self.files.write().unwrap();
+            files.retain(|(p, _)| p != path);
+            files.push((path.to_path_buf(), contents.to_vec()));
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self) -> WoahResult<()> {
+        self.target.prepare()
+    }
+
+    fn finalize(&self, metadata: &PackMetadata) -> WoahResult<()> {
+        self.target.finalize(metadata)
+    }
+
+    fn abort(&self) {
+        self.target.abort()
+    }
+
+    fn read_cache(&self, name: &str) -> Option<Vec<u8>> {
+        self.target.read_cache(name)
+    }
+
+    fn write_cache(&self, name: &str, contents: &[u8]) -> WoahResult<()> {
+        self.target.write_cache(name, contents)
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use eo::sjson::{SJsonMacro, SJsonValue};
+use serde::Serialize;
+use serde_json::Value;
+use crate::core::utilities::{ComponentMap, SemVer};
+
+/// The format version a module of generated components belongs to, e.g. `item::components::v1_26_10::FormatVersion`.
+pub trait ComponentVersion {
+    const FORMAT_VERSION: (u32, u32, u32);
+
+    fn format_version() -> SemVer {
+        let (major, minor, patch) = Self::FORMAT_VERSION;
+        SemVer::new(major, minor, patch)
+    }
+}
+
+/// A generated component that is valid in the format version `V`.
+/// Components unchanged since an older version are valid in every newer one they're re-exported to.
+pub trait VersionedComponent<V: ComponentVersion>: Serialize {
+    const ID: &'static str;
+}
+
+/// A single typed component, serialized through serde. Any [VersionedComponent] of `V` converts into it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedComponent<V: ComponentVersion> {
+    id: &'static str,
+    value: Value,
+    error: Option<String>,
+    version: PhantomData<V>
+}
+
+impl<V: ComponentVersion> TypedComponent<V> {
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Why the component couldn't be serialized, reported when its [TypedComponents] are serialized.
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
+
+impl<V: ComponentVersion, C: VersionedComponent<V>> From<C> for TypedComponent<V> {
+    fn from(value: C) -> Self {
+        let (value, error) = match serde_json::to_value(&value) {
+            Ok(value) => (value, None),
+            Err(err) => (Value::Null, Some(err.to_string()))
+        };
+
+        Self {
+            id: C::ID,
+            value,
+            error,
+            version: PhantomData
+        }
+    }
+}
+
+/// A list of typed components of one format version. Every generated version module has a `components` function creating one,
+/// which only accepts components of that version:
+/// ```rust,ignore
+/// use woah::item::components::v1_26_10::*;
+///
+/// components((
+///     Icon::String("woah:ruby".to_string()),
+///     MaxStackSize::Integer(16)
+/// ))
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedComponents<V: ComponentVersion> {
+    components: ComponentMap,
+    version: PhantomData<V>
+}
+
+impl<V: ComponentVersion> TypedComponents<V> {
+    pub fn new() -> Self {
+        let mut components = ComponentMap::new();
+        components.set_format_version(V::format_version());
+
+        Self {
+            components,
+            version: PhantomData
+        }
+    }
+
+    pub fn with(mut self, component: impl Into<TypedComponent<V>>) -> Self {
+        let component = component.into();
+
+        match component.error {
+            Some(error) => self.components.insert_error(format!("`{}`: {}", component.id, error)),
+            None => self.components.insert(component.id, component.value)
+        }
+        self
+    }
+
+    pub fn format_version(&self) -> SemVer {
+        V::format_version()
+    }
+
+    pub fn components(&self) -> &ComponentMap {
+        &self.components
+    }
+}
+
+impl<V: ComponentVersion> Default for TypedComponents<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: ComponentVersion> FromIterator<TypedComponent<V>> for TypedComponents<V> {
+    fn from_iter<T: IntoIterator<Item = TypedComponent<V>>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::new(), |components, component| components.with(component))
+    }
+}
+
+/// Anything the generated `components` functions accept: a tuple of [VersionedComponent]s of `V`, or a list of [TypedComponent]s.
+pub trait ComponentList<V: ComponentVersion> {
+    fn into_components(self) -> TypedComponents<V>;
+}
+
+impl<V: ComponentVersion> ComponentList<V> for Vec<TypedComponent<V>> {
+    fn into_components(self) -> TypedComponents<V> {
+        self.into_iter().collect()
+    }
+}
+
+macro_rules! component_tuples {
+    ($($component:ident),+) => {
+        impl<V: ComponentVersion, $($component: VersionedComponent<V>),+> ComponentList<V> for ($($component,)+) {
+            #[allow(non_snake_case)]
+            fn into_components(self) -> TypedComponents<V> {
+                let ($($component,)+) = self;
+                TypedComponents::new()$(.with($component))+
+            }
+        }
+    };
+}
+
+component_tuples!(A);
+component_tuples!(A, B);
+component_tuples!(A, B, C);
+component_tuples!(A, B, C, D);
+component_tuples!(A, B, C, D, E);
+component_tuples!(A, B, C, D, E, F);
+component_tuples!(A, B, C, D, E, F, G);
+component_tuples!(A, B, C, D, E, F, G, H);
+component_tuples!(A, B, C, D, E, F, G, H, I);
+component_tuples!(A, B, C, D, E, F, G, H, I, J);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K, L);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+component_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+/// The components of an Item or a Block, either typed or written with [eo::sjson!].
+/// Typed components also carry the format version of their module, which the Item or Block takes over.
+#[derive(Clone, Debug)]
+pub struct Components {
+    pub components: ComponentMap,
+    pub format_version: Option<SemVer>
+}
+
+impl<V: ComponentVersion> From<TypedComponents<V>> for Components {
+    fn from(value: TypedComponents<V>) -> Self {
+        Self {
+            format_version: Some(value.format_version()),
+            components: value.components
+        }
+    }
+}
+
+/// For component groups and permutations, which use the format version of their entity or block.
+/// The map keeps the version of its components, so the validation can report groups of another version.
+impl<V: ComponentVersion> From<TypedComponents<V>> for ComponentMap {
+    fn from(value: TypedComponents<V>) -> Self {
+        value.components
+    }
+}
+
+impl From<SJsonMacro> for Components {
+    fn from(value: SJsonMacro) -> Self {
+        ComponentMap::from(value).into()
+    }
+}
+
+impl From<HashMap<String, SJsonValue>> for Components {
+    fn from(value: HashMap<String, SJsonValue>) -> Self {
+        ComponentMap::from(value).into()
+    }
+}
+
+impl From<ComponentMap> for Components {
+    fn from(value: ComponentMap) -> Self {
+        Self {
+            components: value,
+            format_version: None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::{block, entity, item};
+    use crate::item::components::v1_26_10::{components, FormatVersion};
+
+    #[test]
+    fn serialization_errors() {
+        #[derive(serde::Serialize)]
+        struct Unserializable(HashMap<(u8, u8), u8>);
+
+        impl VersionedComponent<FormatVersion> for Unserializable {
+            const ID: &'static str = "woah:unserializable";
+        }
+
+        let typed = components((Unserializable(HashMap::from([((0, 0), 0)])),));
+        assert!(typed.components().to_value().unwrap_err().to_string().contains("`woah:unserializable`"));
+    }
+
+    #[test]
+    fn generated_components() {
+        for (ty, generated) in [
+            ("item", item::components::SCHEMA_COMPONENTS),
+            ("block", block::components::SCHEMA_COMPONENTS),
+            ("entity", entity::components::SCHEMA_COMPONENTS)
+        ] {
+            let root = PathBuf::from(format!("./schemas/minecraftBedrock/schema/{}", ty));
+
+            for version in std::fs::read_dir(&root).unwrap().filter_map(|e| e.ok()) {
+                let version = version.file_name().to_string_lossy().to_string();
+                if !version.starts_with('v') {
+                    continue;
+                }
+
+                for folder in ["components", "behaviors", "annotations"] {
+                    for file in std::fs::read_dir(root.join(&version).join(folder)).into_iter().flatten().filter_map(|e| e.ok()) {
+                        let name = file.file_name().to_string_lossy().to_string();
+                        let Some(stem) = name.strip_suffix(".json").filter(|s| !s.starts_with('_')) else { continue };
+
+                        let id = format!("minecraft:{}", stem);
+                        assert!(generated.contains(&(version.as_str(), id.as_str())), "No {} component generated for {} {}", ty, version, id);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(entity::components::v1_16_0::AttackDamageComponent::ID, "minecraft:attack_damage");
+    }
+}
@@ -1,12 +1,26 @@
 use crate::core::metadata::PackMetadata;
 use crate::item::Item;
-use lazy_static::lazy_static;
 use std::sync::RwLock;
 use crate::block::Block;
 use crate::block::client::BlockTexture;
 use crate::entity::Entity;
+use crate::entity::client::ClientEntity;
 use crate::item::client::ItemTexture;
+use crate::lang::Translations;
+use crate::recipe::Recipe;
+use crate::loot::LootTable;
+use crate::geometry::Geometry;
+use crate::animation::{Animation, ClientAnimation};
+use crate::animation::controller::AnimationController;
+use crate::core::utilities::Identifier;
+use crate::sound::{BlockSounds, EntitySounds, SoundDefinition, Sounds};
+use crate::worldgen::Feature;
+use crate::biome::Biome;
+use crate::biome::client::ClientBiome;
+use crate::worldgen::rule::FeatureRule;
 
+/// Everything registered for a single pack. Each [Woah::pack](crate::core::Woah::pack) call builds its own registry.
+#[derive(Default)]
 pub(crate) struct CoreRegistry {
     pub pack_metadata: RwLock<PackMetadata>,
     pub items: RwLock<Vec<Item>>,
@@ -14,17 +28,20 @@ pub(crate) struct CoreRegistry {
     pub blocks: RwLock<Vec<Block>>,
     pub block_textures: RwLock<Vec<BlockTexture>>,
     pub entities: RwLock<Vec<Entity>>,
-}
-
-lazy_static! {
-    pub(crate) static ref REGISTRY: CoreRegistry = CoreRegistry {
-        pack_metadata: RwLock::new(PackMetadata::default()),
-        items: RwLock::new(Vec::new()),
-        item_textures: RwLock::new(Vec::new()),
-        blocks: RwLock::new(Vec::new()),
-        block_textures: RwLock::new(Vec::new()),
-        entities: RwLock::new(Vec::new())
-    };
+    pub client_entities: RwLock<Vec<ClientEntity>>,
+    pub recipes: RwLock<Vec<Recipe>>,
+    pub loot_tables: RwLock<Vec<LootTable>>,
+    pub geometries: RwLock<Vec<Geometry>>,
+    pub animations: RwLock<Vec<Animation>>,
+    pub animation_controllers: RwLock<Vec<AnimationController>>,
+    pub client_animations: RwLock<Vec<ClientAnimation>>,
+    pub client_animation_controllers: RwLock<Vec<AnimationController>>,
+    pub sounds: RwLock<Sounds>,
+    pub features: RwLock<Vec<Feature>>,
+    pub feature_rules: RwLock<Vec<FeatureRule>>,
+    pub biomes: RwLock<Vec<Biome>>,
+    pub client_biomes: RwLock<Vec<ClientBiome>>,
+    pub translations: RwLock<Translations>,
 }
 
 impl CoreRegistry {
@@ -51,4 +68,72 @@ impl CoreRegistry {
     pub fn register_entity(&self, entity: Entity) {
         self.entities.write().unwrap().push(entity);
     }
+
+    pub fn register_client_entity(&self, entity: ClientEntity) {
+        self.client_entities.write().unwrap().push(entity);
+    }
+
+    pub fn register_recipe(&self, recipe: Recipe) {
+        self.recipes.write().unwrap().push(recipe);
+    }
+
+    pub fn register_loot_table(&self, table: LootTable) {
+        self.loot_tables.write().unwrap().push(table);
+    }
+
+    pub fn register_geometry(&self, geometry: Geometry) {
+        self.geometries.write().unwrap().push(geometry);
+    }
+
+    pub fn register_animation(&self, animation: Animation) {
+        self.animations.write().unwrap().push(animation);
+    }
+
+    pub fn register_animation_controller(&self, controller: AnimationController) {
+        self.animation_controllers.write().unwrap().push(controller);
+    }
+
+    pub fn register_client_animation(&self, animation: ClientAnimation) {
+        self.client_animations.write().unwrap().push(animation);
+    }
+
+    pub fn register_client_animation_controller(&self, controller: AnimationController) {
+        self.client_animation_controllers.write().unwrap().push(controller);
+    }
+
+    pub fn register_sound(&self, definition: SoundDefinition) {
+        self.sounds.write().unwrap().definitions.push(definition);
+    }
+
+    pub fn register_block_sounds(&self, sounds: BlockSounds) {
+        self.sounds.write().unwrap().block_sounds.push(sounds);
+    }
+
+    pub fn register_entity_sounds(&self, sounds: EntitySounds) {
+        self.sounds.write().unwrap().entity_sounds.push(sounds);
+    }
+
+    pub fn bind_block_sound(&self, block: Identifier, sounds: String) {
+        self.sounds.write().unwrap().block_bindings.insert(block.render(), sounds);
+    }
+
+    pub fn register_feature(&self, feature: Feature) {
+        self.features.write().unwrap().push(feature);
+    }
+
+    pub fn register_feature_rule(&self, rule: FeatureRule) {
+        self.feature_rules.write().unwrap().push(rule);
+    }
+
+    pub fn register_biome(&self, biome: Biome) {
+        self.biomes.write().unwrap().push(biome);
+    }
+
+    pub fn register_client_biome(&self, biome: ClientBiome) {
+        self.client_biomes.write().unwrap().push(biome);
+    }
+
+    pub fn register_translation(&self, locale: String, key: String, value: String) {
+        self.translations.write().unwrap().insert(locale, key, value);
+    }
 }
\ No newline at end of file
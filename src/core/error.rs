@@ -0,0 +1,204 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::core::validation::Diagnostics;
+
+/// The phase of the build an error happened in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildPhase {
+    Validation,
+    Prepare,
+    Manifest,
+    Items,
+    ClientItems,
+    Blocks,
+    ClientBlocks,
+    Entities,
+    ClientEntities,
+    Recipes,
+    LootTables,
+    Geometries,
+    Animations,
+    ClientAnimations,
+    Sounds,
+    Features,
+    Biomes,
+    ClientBiomes,
+    Lang,
+    SchemaValidation,
+    Packaging
+}
+
+/// What went wrong, independent of where it happened.
+#[derive(Debug)]
+pub enum WoahErrorKind {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Archive(zip::result::ZipError),
+    Json(serde_json::Error),
+    Validation(Diagnostics),
+    InvalidIdentifier(String),
+    Other(String)
+}
+
+/// The error type of the Woah build pipeline. Carries the phase, the identifier and the output path it happened at, when known.
+#[derive(Debug)]
+pub struct WoahError {
+    pub kind: WoahErrorKind,
+    pub phase: Option<BuildPhase>,
+    pub identifier: Option<String>,
+    pub path: Option<PathBuf>
+}
+
+pub type WoahResult<T> = Result<T, WoahError>;
+
+impl WoahError {
+    pub fn new(kind: WoahErrorKind) -> Self {
+        Self {
+            kind,
+            phase: None,
+            identifier: None,
+            path: None
+        }
+    }
+
+    pub fn other(message: impl Into<String>) -> Self {
+        Self::new(WoahErrorKind::Other(message.into()))
+    }
+}
+
+impl Display for BuildPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match &self {
+            BuildPhase::Validation => "validation",
+            BuildPhase::Prepare => "prepare",
+            BuildPhase::Manifest => "manifest",
+            BuildPhase::Items => "items",
+            BuildPhase::ClientItems => "client items",
+            BuildPhase::Blocks => "blocks",
+            BuildPhase::ClientBlocks => "client blocks",
+            BuildPhase::Entities => "entities",
+            BuildPhase::ClientEntities => "client entities",
+            BuildPhase::Recipes => "recipes",
+            BuildPhase::LootTables => "loot tables",
+            BuildPhase::Geometries => "geometries",
+            BuildPhase::Animations => "animations",
+            BuildPhase::ClientAnimations => "client animations",
+            BuildPhase::Sounds => "sounds",
+            BuildPhase::Features => "features",
+            BuildPhase::Biomes => "biomes",
+            BuildPhase::ClientBiomes => "client biomes",
+            BuildPhase::Lang => "lang",
+            BuildPhase::SchemaValidation => "schema validation",
+            BuildPhase::Packaging => "packaging"
+        })
+    }
+}
+
+impl Display for WoahErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            WoahErrorKind::Io(err) => write!(f, "I/O error: {}", err),
+            WoahErrorKind::Image(err) => write!(f, "image error: {}", err),
+            WoahErrorKind::Archive(err) => write!(f, "archive error: {}", err),
+            WoahErrorKind::Json(err) => write!(f, "malformed JSON: {}", err),
+            WoahErrorKind::Validation(diagnostics) => write!(f, "{}", diagnostics),
+            WoahErrorKind::InvalidIdentifier(id) => write!(f, "invalid identifier `{}`, expected `namespace:path`", id),
+            WoahErrorKind::Other(message) => f.write_str(message)
+        }
+    }
+}
+
+impl Display for WoahError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.phase {
+            Some(phase) => write!(f, "[{}] ", phase)?,
+            None => f.write_str("[woah] ")?
+        }
+        if let Some(identifier) = &self.identifier {
+            write!(f, "{}: ", identifier)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for WoahError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            WoahErrorKind::Io(err) => Some(err),
+            WoahErrorKind::Image(err) => Some(err),
+            WoahErrorKind::Archive(err) => Some(err),
+            WoahErrorKind::Json(err) => Some(err),
+            WoahErrorKind::Validation(diagnostics) => Some(diagnostics),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for WoahError {
+    fn from(value: std::io::Error) -> Self {
+        Self::new(WoahErrorKind::Io(value))
+    }
+}
+
+impl From<image::ImageError> for WoahError {
+    fn from(value: image::ImageError) -> Self {
+        Self::new(WoahErrorKind::Image(value))
+    }
+}
+
+impl From<zip::result::ZipError> for WoahError {
+    fn from(value: zip::result::ZipError) -> Self {
+        Self::new(WoahErrorKind::Archive(value))
+    }
+}
+
+impl From<serde_json::Error> for WoahError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::new(WoahErrorKind::Json(value))
+    }
+}
+
+impl From<Diagnostics> for WoahError {
+    fn from(value: Diagnostics) -> Self {
+        Self::new(WoahErrorKind::Validation(value))
+    }
+}
+
+/// Attaches build context to errors. Context which is already set is kept, so the innermost context wins.
+pub trait WoahResultExt<T> {
+    fn in_phase(self, phase: BuildPhase) -> WoahResult<T>;
+
+    fn for_identifier(self, identifier: impl ToString) -> WoahResult<T>;
+
+    fn at_path(self, path: impl Into<PathBuf>) -> WoahResult<T>;
+}
+
+impl<T, E: Into<WoahError>> WoahResultExt<T> for Result<T, E> {
+    fn in_phase(self, phase: BuildPhase) -> WoahResult<T> {
+        self.map_err(|err| {
+            let mut err: WoahError = err.into();
+            err.phase.get_or_insert(phase);
+            err
+        })
+    }
+
+    fn for_identifier(self, identifier: impl ToString) -> WoahResult<T> {
+        self.map_err(|err| {
+            let mut err: WoahError = err.into();
+            err.identifier.get_or_insert_with(|| identifier.to_string());
+            err
+        })
+    }
+
+    fn at_path(self, path: impl Into<PathBuf>) -> WoahResult<T> {
+        self.map_err(|err| {
+            let mut err: WoahError = err.into();
+            err.path.get_or_insert_with(|| path.into());
+            err
+        })
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
 use derive_builder::Builder;
-use eo::sjson::{SJsonElement, SJsonMacro};
+use eo::sjson::SJsonMacro;
 use crate::core::utilities::SemVer;
 use crate::bedrock::metadata::BedrockSpecificMetadata;
+use crate::code_gen::WoahConfig;
 use crate::hold_builders;
 
 hold_builders!(PackMetadata, AdditionalMetadata);
@@ -16,7 +16,10 @@ pub struct PackMetadata {
     pub author: String,
     pub description: String,
     #[builder(default = "None")]
-    pub additional: Option<AdditionalMetadata>
+    pub additional: Option<AdditionalMetadata>,
+    /// The manifest UUIDs, set for each target during the build.
+    #[builder(setter(skip))]
+    pub(crate) uuids: Option<WoahConfig>
 }
 
 
@@ -24,9 +27,9 @@ pub struct PackMetadata {
 #[builder(setter(into))]
 pub struct AdditionalMetadata {
     #[builder(default = "None")]
-    pub(crate) bedrock_specific: Option<BedrockSpecificMetadata>,
+    pub bedrock_specific: Option<BedrockSpecificMetadata>,
     #[builder(default = "None")]
-    pub(crate) additional: Option<SJsonMacro>
+    pub additional: Option<SJsonMacro>
 }
 
 impl PackMetadata {
@@ -2,22 +2,37 @@ pub mod utilities;
 pub mod metadata;
 pub(crate) mod core_registry;
 pub mod sprite;
+pub mod audio;
 pub mod build_target;
-
-use crate::code_gen::CODE_GEN;
-use crate::core::core_registry::REGISTRY;
+pub mod error;
+pub mod validation;
+pub mod vanilla;
+pub mod component;
+#[cfg(test)]
+pub(crate) mod testing;
+
+use crate::code_gen::CodeGen;
+use crate::core::core_registry::CoreRegistry;
 use crate::core::metadata::PackMetadata;
 use crate::item::registry::{ClientItemRegistry, ItemRegistry};
 use eo::event_init;
 use eo::events::Event;
 use log::LevelFilter;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Once};
 use crate::block::registry::{BlockRegistry, ClientBlockRegistry};
 use crate::code_gen::generator::{GeneratorInstance, PackGenerator};
 use crate::bedrock::bedrock_generator::WoahBedrockGenerator;
 use crate::core::build_target::BuildTarget;
-use crate::entity::registry::EntityRegistry;
+use crate::core::error::WoahResult;
+use crate::entity::registry::{ClientEntityRegistry, EntityRegistry};
+use crate::lang::registry::LangRegistry;
+use crate::recipe::registry::RecipeRegistry;
+use crate::loot::registry::LootTableRegistry;
+use crate::geometry::registry::GeometryRegistry;
+use crate::animation::registry::{AnimationRegistry, ClientAnimationRegistry};
+use crate::sound::registry::SoundRegistry;
+use crate::worldgen::registry::WorldgenRegistry;
+use crate::biome::registry::{BiomeRegistry, ClientBiomeRegistry};
 
 /// The core trait for creating a pack. Implement this for your pack struct.
 pub trait PackImplementation {
@@ -27,7 +42,7 @@ pub trait PackImplementation {
     /// A function returning the [AddonMetadata](metadata::PackMetadata) for the addon.
     fn metadata(&self) -> PackMetadata;
 
-    /// A function returning a [PathBuf] to a path where the pack folders will be generated.
+    /// A function returning a [PathBuf](std::path::PathBuf) to a path where the pack folders will be generated.
     fn targets(&self) -> Vec<Arc<dyn BuildTarget>>;
 
     /// A function returning the [PackGenerator](crate::code_gen::generator::PackGenerator)s for the pack. Defaults to the default Minecraft Bedrock generator.
@@ -37,6 +52,12 @@ pub trait PackImplementation {
             WoahBedrockGenerator.generator()
         ]
     }
+
+    /// Whether every generated JSON file is validated against the bundled JSON schemas, before the targets are finalized.
+    /// Violations fail the build with the JSON pointer of the value and the identifier of the file. Disabled by default.
+    fn schema_validation(&self) -> bool {
+        false
+    }
 }
 
 pub struct PackFinalization {}
@@ -48,8 +69,8 @@ pub enum PackPart {
 }
 
 pub struct PackPath {
-    part: PackPart,
-    path: String
+    pub part: PackPart,
+    pub path: String
 }
 
 impl PackPath {
@@ -83,14 +104,12 @@ where T:
     }
 }
 
-impl PackFinalization {
-    fn attach_file(&self, path: impl Into<PackPath>) {
-
-    }
-}
-
 /// Events for registering stuff. Subscribe to them using `.subscribe()`.
 pub struct PackProcessingEvents<'a> {
+    /// Loot table registration events, runs before every other registration, so the returned handles can be used by blocks and entities.
+    pub loot_table_registration: Event<'a, LootTableRegistry>,
+    /// Geometry registration events, runs right after the loot tables, so the returned handles can be used by blocks and client entities.
+    pub geometry_registration: Event<'a, GeometryRegistry>,
     /// Item registration events. Register items here.
     pub item_registration: Event<'a, ItemRegistry>,
     /// Client item registration events. Register item textures here.
@@ -101,6 +120,24 @@ pub struct PackProcessingEvents<'a> {
     pub client_block_registration: Event<'a, ClientBlockRegistry>,
     /// Block registration events. Register blocks here.
     pub entity_registration: Event<'a, EntityRegistry>,
+    /// Client entity registration. Register the client side of entities here.
+    pub client_entity_registration: Event<'a, ClientEntityRegistry>,
+    /// Animation registration events. Register behavior pack animations and animation controllers here.
+    pub animation_registration: Event<'a, AnimationRegistry>,
+    /// Client animation registration events. Register resource pack animations and animation controllers here.
+    pub client_animation_registration: Event<'a, ClientAnimationRegistry>,
+    /// Sound registration events. Register sound definitions and the sounds of blocks and entities here.
+    pub sound_registration: Event<'a, SoundRegistry>,
+    /// Biome registration events. Register biomes and the tags they declare here.
+    pub biome_registration: Event<'a, BiomeRegistry>,
+    /// Client biome registration events. Register the sky, water and fog colors of biomes here.
+    pub client_biome_registration: Event<'a, ClientBiomeRegistry>,
+    /// Worldgen registration events, runs after the blocks are registered. Register features, feature rules and ores here.
+    pub worldgen_registration: Event<'a, WorldgenRegistry>,
+    /// Recipe registration events. Register recipes here.
+    pub recipe_registration: Event<'a, RecipeRegistry>,
+    /// Translation registration events, runs after every other registration. Register the texts of each locale here.
+    pub lang_registration: Event<'a, LangRegistry>,
     /// Pack finalization, runs after the registration and main codegen phases
     pub finalization: Event<'a, PackFinalization>
 }
@@ -108,11 +145,22 @@ pub struct PackProcessingEvents<'a> {
 impl<'a> PackProcessingEvents<'a> {
     pub(crate) fn new() -> Self {
         Self {
+            loot_table_registration: event_init!(LootTableRegistry),
+            geometry_registration: event_init!(GeometryRegistry),
             item_registration: event_init!(ItemRegistry),
             client_item_registration: event_init!(ClientItemRegistry),
             block_registration: event_init!(BlockRegistry),
             client_block_registration: event_init!(ClientBlockRegistry),
             entity_registration: event_init!(EntityRegistry),
+            client_entity_registration: event_init!(ClientEntityRegistry),
+            animation_registration: event_init!(AnimationRegistry),
+            client_animation_registration: event_init!(ClientAnimationRegistry),
+            sound_registration: event_init!(SoundRegistry),
+            biome_registration: event_init!(BiomeRegistry),
+            client_biome_registration: event_init!(ClientBiomeRegistry),
+            worldgen_registration: event_init!(WorldgenRegistry),
+            recipe_registration: event_init!(RecipeRegistry),
+            lang_registration: event_init!(LangRegistry),
             finalization: event_init!(PackFinalization),
         }
     }
@@ -123,32 +171,56 @@ impl<'a> PackProcessingEvents<'a> {
 pub struct Woah;
 
 impl Woah {
-    /// The entry point of Woah. This function should only be called ONCE.
-    pub fn pack(pack: impl PackImplementation) {
-        log::set_logger(&eo::logger::EoLogger).unwrap();
-        log::set_max_level(LevelFilter::max());
+    /// The entry point of Woah. Every call builds its pack from scratch, so several packs can be built in one process.<br>
+    /// Returns the first error of the build, tagged with the [BuildPhase](error::BuildPhase) it happened in.
+    pub fn pack(pack: impl PackImplementation) -> WoahResult<()> {
+        static LOGGER: Once = Once::new();
+        LOGGER.call_once(|| {
+            // Another logger may already be installed by the application.
+            if log::set_logger(&eo::logger::EoLogger).is_ok() {
+                log::set_max_level(LevelFilter::max());
+            }
+        });
+
+        let registry = Arc::new(CoreRegistry::default());
 
         let events = PackProcessingEvents::new();
         pack.initialize(&events);
-        events.item_registration.notify(ItemRegistry {});
-        events.client_item_registration.notify(ClientItemRegistry {});
-        events.block_registration.notify(BlockRegistry {});
-        events.client_block_registration.notify(ClientBlockRegistry {});
-        events.entity_registration.notify(EntityRegistry {});
-
-        REGISTRY.set_pack_metadata(pack.metadata());
-
-        // CODE_GEN.set_output_path(pack.build_path());
-        CODE_GEN.set_targets(
+        events.loot_table_registration.notify(LootTableRegistry { registry: registry.clone() });
+        events.geometry_registration.notify(GeometryRegistry { registry: registry.clone() });
+        events.item_registration.notify(ItemRegistry { registry: registry.clone() });
+        events.client_item_registration.notify(ClientItemRegistry { registry: registry.clone() });
+        events.block_registration.notify(BlockRegistry { registry: registry.clone() });
+        events.client_block_registration.notify(ClientBlockRegistry { registry: registry.clone() });
+        events.entity_registration.notify(EntityRegistry { registry: registry.clone() });
+        events.client_entity_registration.notify(ClientEntityRegistry { registry: registry.clone() });
+        events.animation_registration.notify(AnimationRegistry { registry: registry.clone() });
+        events.client_animation_registration.notify(ClientAnimationRegistry { registry: registry.clone() });
+        events.sound_registration.notify(SoundRegistry { registry: registry.clone() });
+        events.biome_registration.notify(BiomeRegistry { registry: registry.clone() });
+        events.client_biome_registration.notify(ClientBiomeRegistry { registry: registry.clone() });
+        events.worldgen_registration.notify(WorldgenRegistry { registry: registry.clone() });
+        events.recipe_registration.notify(RecipeRegistry { registry: registry.clone() });
+        events.lang_registration.notify(LangRegistry { registry: registry.clone() });
+
+        registry.set_pack_metadata(pack.metadata());
+
+        let code_gen = CodeGen::new(registry);
+        code_gen.set_targets(
             pack.targets()
         );
-        CODE_GEN.set_generators(
+        code_gen.set_generators(
             pack.generators()
         );
+        code_gen.set_schema_validation(
+            pack.schema_validation()
+        );
         
-        CODE_GEN.build().unwrap();
+        code_gen.build()?;
 
         events.finalization.notify(PackFinalization {});
+
+        Ok(())
     }
 }
 
@@ -1,6 +1,8 @@
+use std::fs;
 use std::path::PathBuf;
-use image::{Rgba, RgbaImage};
-use image::buffer::ConvertBuffer;
+use image::{ExtendedColorType, ImageEncoder, Rgba, RgbaImage};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use crate::core::error::{WoahResult, WoahResultExt};
 
 /// A struct for describing textures.
 #[derive(Clone, Debug)]
@@ -9,19 +11,49 @@ pub struct Sprite {
 }
 
 impl Sprite {
-    pub fn read(path: impl Into<PathBuf>) -> Self {
-        Self {
-            image: image::open(path.into()).unwrap().to_rgba8()
-        }
+    pub fn read(path: impl Into<PathBuf>) -> WoahResult<Self> {
+        let path = path.into();
+
+        Ok(Self {
+            image: image::open(&path).at_path(path)?.to_rgba8()
+        })
     }
     
+    /// Decodes a sprite from encoded image bytes, e.g. a texture embedded in a Blockbench model.
+    pub fn from_bytes(bytes: &[u8]) -> WoahResult<Self> {
+        Ok(Self {
+            image: image::load_from_memory(bytes)?.to_rgba8()
+        })
+    }
+
     pub fn accept(&mut self, cl: fn(u32, u32, Rgba<u8>) -> Rgba<u8>) {
         for (x, y, color) in self.image.enumerate_pixels_mut() {
             *color = cl(x, y, *color);
         }
     }
     
-    pub fn build(&self, path: impl Into<PathBuf>) {
-        self.image.save(path.into()).unwrap();
+    /// Encodes the sprite as a PNG. The encoder settings are fixed and no metadata chunks are written, so the same pixels always produce the same bytes.
+    pub fn encode(&self) -> WoahResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        PngEncoder::new_with_quality(&mut bytes, CompressionType::Best, FilterType::Adaptive)
+            .write_image(self.image.as_raw(), self.image.width(), self.image.height(), ExtendedColorType::Rgba8)?;
+
+        Ok(bytes)
     }
-}
\ No newline at end of file
+
+    pub fn build(&self, path: impl Into<PathBuf>) -> WoahResult<()> {
+        let path = path.into();
+
+        fs::write(&path, self.encode().at_path(&path)?).at_path(path)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_encoding() {
+        assert_eq!(Sprite::read("./textures/item.png").unwrap().encode().unwrap(), Sprite::read("./textures/item.png").unwrap().encode().unwrap());
+    }
+}
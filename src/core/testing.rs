@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::core::build_target::{BuildTarget, MemoryTarget};
+use crate::core::metadata::PackMetadata;
+use crate::core::{PackImplementation, PackProcessingEvents, Woah};
+use crate::bedrock::TargetInstance;
+
+/// A pack for tests, registering its content through a closure and building into a [MemoryTarget].
+pub(crate) struct TestPack<F> {
+    metadata: PackMetadata,
+    initialize: F,
+    memory: MemoryTarget
+}
+
+impl<F: Fn(&PackProcessingEvents)> PackImplementation for TestPack<F> {
+    fn initialize(&self, events: &PackProcessingEvents) {
+        (self.initialize)(events)
+    }
+
+    fn metadata(&self) -> PackMetadata {
+        self.metadata.clone()
+    }
+
+    fn targets(&self) -> Vec<Arc<dyn BuildTarget>> {
+        vec![
+            self.memory.target()
+        ]
+    }
+}
+
+/// Builds a pack named `WoahTest` with the content registered by `initialize`, and returns its files.
+pub(crate) fn build(initialize: impl Fn(&PackProcessingEvents)) -> MemoryTarget {
+    build_with(PackMetadata { name: "WoahTest".to_string(), ..PackMetadata::default() }, initialize)
+}
+
+/// Same as [build], with custom pack metadata.
+pub(crate) fn build_with(metadata: PackMetadata, initialize: impl Fn(&PackProcessingEvents)) -> MemoryTarget {
+    let memory = MemoryTarget::new();
+    Woah::pack(TestPack { metadata, initialize, memory: memory.clone() }).unwrap();
+    memory
+}
+
+/// An empty folder in the system temp folder, unique to the test named `name` and the test process.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("woah_{}_{}", name, std::process::id()));
+    if path.exists() {
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+    std::fs::create_dir_all(&path).unwrap();
+    path
+}
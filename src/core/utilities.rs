@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::str::FromStr;
-use formatjson::FormatJsonError;
+use eo::sjson::{SJsonMacro, SJsonValue};
 use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
 use crate::bedrock::BedrockSerializable;
+use crate::core::error::{WoahError, WoahErrorKind, WoahResult};
 
 // A struct for describing `namespace:path` style identifiers.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 pub struct Identifier {
     namespace: String,
     path: String
@@ -30,9 +32,10 @@ E: Into<String> {
     }
 }
 
+/// Identifiers without a namespace don't panic here, they are reported by the validation phase instead.
 impl From<&str> for Identifier {
     fn from(value: &str) -> Self {
-        let (namespace, path) = value.split_once(':').unwrap();
+        let (namespace, path) = value.split_once(':').unwrap_or(("", value));
         Self {
             namespace: namespace.to_string(),
             path: path.to_string(),
@@ -40,7 +43,26 @@ impl From<&str> for Identifier {
     }
 }
 
+impl FromStr for Identifier {
+    type Err = WoahError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((namespace, path)) if !namespace.is_empty() && !path.is_empty() => Ok(Self::new(namespace, path)),
+            _ => Err(WoahError::new(WoahErrorKind::InvalidIdentifier(s.to_string())))
+        }
+    }
+}
+
 impl Identifier {
+    pub fn namespace(&self) -> &String {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
     pub fn render(&self) -> String {
         format!("{}:{}", &self.namespace, &self.path)
     }
@@ -107,7 +129,7 @@ impl From<&str> for ScriptModuleVer {
 }
 
 /// A struct for describing `major.minor.hotfix` & `major.minor.hotfix-beta` style versions.
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq)]
 pub struct SemVer {
     major: u32,
     minor: u32,
@@ -124,6 +146,11 @@ impl SemVer {
         format!("{},{},{}", self.major, self.minor, self.hotfix)
     }
 
+    /// Renders the version as a `[major, minor, hotfix]` array, as used in manifests.
+    pub fn render_array(&self) -> Value {
+        Value::from(vec![self.major, self.minor, self.hotfix])
+    }
+
     pub fn new_beta(major: u32, minor: u32, hotfix: u32) -> Self {
         Self { major, minor, hotfix, postfix: "-beta".to_string() }
     }
@@ -141,19 +168,19 @@ impl SemVer {
     }
 }
 
-impl Into<SemVer> for (u32, u32, u32, bool) {
-    fn into(self) -> SemVer {
-        if self.3 {
-            SemVer::new_beta(self.0, self.1, self.2)
+impl From<(u32, u32, u32, bool)> for SemVer {
+    fn from(value: (u32, u32, u32, bool)) -> Self {
+        if value.3 {
+            SemVer::new_beta(value.0, value.1, value.2)
         } else {
-            SemVer::new(self.0,  self.1, self.2)
+            SemVer::new(value.0, value.1, value.2)
         }
     }
 }
 
-impl Into<SemVer> for (u32, u32, u32) {
-    fn into(self) -> SemVer {
-        SemVer::new(self.0, self.1, self.2)
+impl From<(u32, u32, u32)> for SemVer {
+    fn from(value: (u32, u32, u32)) -> Self {
+        SemVer::new(value.0, value.1, value.2)
     }
 }
 
@@ -166,41 +193,131 @@ impl Serialize for SemVer {
     }
 }
 
-pub trait ContextMultiset {
-    fn set_keys(&mut self, keys: HashMap<String, String>);
+/// An insertion-ordered map of components, so generated files keep the order components were written in.<br>
+/// A conversion into the map which failed is kept, and reported when the map is serialized.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentMap {
+    map: Map<String, Value>,
+    error: Option<String>,
+    format_version: Option<SemVer>
 }
 
-impl ContextMultiset for tera::Context {
-    fn set_keys(&mut self, keys: HashMap<String, String>) {
-        for (k, v) in keys {
-            self.insert(k, &v);
+impl ComponentMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.map.get(key)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.map.insert(key.into(), value.into());
+    }
+
+    /// Keeps the first error, a component which couldn't be converted.
+    pub fn insert_error(&mut self, error: impl Display) {
+        self.error.get_or_insert_with(|| error.to_string());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.map.iter()
+    }
+
+    /// The format version of the typed components the map was created from, `None` for [eo::sjson!] components.
+    pub fn format_version(&self) -> Option<&SemVer> {
+        self.format_version.as_ref()
+    }
+
+    pub(crate) fn set_format_version(&mut self, format_version: SemVer) {
+        self.format_version = Some(format_version);
+    }
+
+    pub fn to_value(&self) -> WoahResult<Value> {
+        match &self.error {
+            Some(error) => Err(WoahError::other(format!("Invalid components: {}", error))),
+            None => Ok(Value::Object(self.map.clone()))
         }
     }
 }
 
-pub trait JsonFormat {
-    fn json_format(&self) -> String;
+impl Serialize for ComponentMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        match &self.error {
+            Some(error) => Err(serde::ser::Error::custom(format!("Invalid components: {}", error))),
+            None => self.map.serialize(serializer)
+        }
+    }
+}
+
+/// `sjson!` only exposes its components as a [HashMap], so they're sorted like one.
+impl From<SJsonMacro> for ComponentMap {
+    fn from(value: SJsonMacro) -> Self {
+        let components: HashMap<String, SJsonValue> = value.into();
+        components.into()
+    }
 }
 
-impl JsonFormat for String {
-    fn json_format(&self) -> String {
-        match formatjson::format_json(&self) {
-            Ok(value) => value,
-            Err(err) => {
-                log::error!("A file with this contents has wrong json syntax. {}", &self);
-                self.to_string()
+/// A [HashMap] has no order to keep, so the keys are sorted instead.
+impl From<HashMap<String, SJsonValue>> for ComponentMap {
+    fn from(value: HashMap<String, SJsonValue>) -> Self {
+        let mut components = Self::default();
+
+        for (key, component) in value.into_iter().collect::<BTreeMap<String, SJsonValue>>() {
+            match serde_json::to_value(&component) {
+                Ok(component) => components.insert(key, component),
+                Err(err) => components.insert_error(format!("`{}`: {}", key, err))
             }
         }
+
+        components
+    }
+}
+
+impl From<Map<String, Value>> for ComponentMap {
+    fn from(value: Map<String, Value>) -> Self {
+        Self {
+            map: value,
+            error: None,
+            format_version: None
+        }
+    }
+}
+
+pub trait JsonFormat {
+    fn json_format(&self) -> WoahResult<String>;
+}
+
+impl JsonFormat for Value {
+    fn json_format(&self) -> WoahResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
     }
 }
 
 pub trait BedrockSerializeVec {
-    fn serialize_vec(&self) -> Vec<String>;
+    fn serialize_vec(&self) -> WoahResult<Vec<Value>>;
+
+    /// Merges the single-entry objects of keyed elements into one object.
+    fn serialize_object(&self) -> WoahResult<Value> {
+        let mut object = Map::new();
+
+        for value in self.serialize_vec()? {
+            match value {
+                Value::Object(entries) => object.extend(entries),
+                other => return Err(WoahError::other(format!("Expected a keyed object, found `{}`", other)))
+            }
+        }
+
+        Ok(Value::Object(object))
+    }
 }
 
 impl<T: BedrockSerializable> BedrockSerializeVec for Vec<T> {
-    fn serialize_vec(&self) -> Vec<String> {
-        self.into_iter().map(|s| s.bedrock_serialize()).collect::<Vec<String>>()
+    fn serialize_vec(&self) -> WoahResult<Vec<Value>> {
+        self.iter().map(|s| s.bedrock_json()).collect::<WoahResult<Vec<Value>>>()
     }
 }
 
@@ -213,7 +330,7 @@ pub trait HoldBuilder<B : Default> {
 #[macro_export]
 macro_rules! hold_builders {
     ($($id:ident),+) => {
-        use crate::core::utilities::HoldBuilder;
+        use $crate::core::utilities::HoldBuilder;
         $(
             paste::paste! {
                 impl HoldBuilder<[< $id Builder >]> for $id {}
@@ -222,3 +339,31 @@ macro_rules! hold_builders {
     };
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, ToSJson, TransformHashMap};
+
+    #[test]
+    fn sorted_components() {
+        let sorted = ComponentMap::from(sjson! {
+            minecraft:max_stack_size = 16,
+            minecraft:display_name = "Sorted",
+            minecraft:hand_equipped = true,
+        });
+        assert_eq!(sorted.iter().map(|(k, _)| k.as_str()).collect::<Vec<&str>>(), vec![
+            "minecraft:display_name", "minecraft:hand_equipped", "minecraft:max_stack_size"
+        ]);
+    }
+
+    #[test]
+    fn broken_components() {
+        let mut broken = ComponentMap::new();
+        broken.insert("minecraft:max_stack_size", 16);
+        broken.insert_error("`minecraft:display_name`: not serializable");
+        assert!(broken.to_value().is_err());
+        assert!(serde_json::to_value(&broken).is_err());
+    }
+}
@@ -0,0 +1,537 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use crate::animation::validate_animations;
+use crate::core::core_registry::CoreRegistry;
+use crate::core::utilities::{ComponentMap, Identifier, SemVer};
+use crate::core::vanilla;
+use crate::entity::filter::{EntityFilter, FilterValue};
+use crate::geometry::validate_geometries;
+use crate::lang::{block_key, entity_key, item_key, spawn_egg_key};
+use crate::molang::Molang;
+use crate::worldgen::validate_features;
+
+/// The severity of a [Diagnostic]. Errors fail the build, warnings are only logged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning
+}
+
+/// A problem found in the registered content before the pack is generated.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The identifier of the content the problem was found in.
+    pub subject: String,
+    pub message: String
+}
+
+/// All diagnostics reported by the validation phase.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    pub diagnostics: Vec<Diagnostic>
+}
+
+impl Diagnostics {
+    pub fn error(&mut self, subject: impl ToString, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic { severity: Severity::Error, subject: subject.to_string(), message: message.into() });
+    }
+
+    pub fn warning(&mut self, subject: impl ToString, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic { severity: Severity::Warning, subject: subject.to_string(), message: message.into() });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning"
+        };
+
+        write!(f, "{}[{}]: {}", severity, self.subject, self.message)
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Validation failed with {} error(s):", self.errors().count())?;
+
+        for diagnostic in self.errors() {
+            writeln!(f, "  {}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// Runs every check over the registry. The checks of each kind of content live next to it, this only collects what content may reference.
+pub(crate) fn validate_registry(registry: &CoreRegistry) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+
+    let items = registry.items.read().unwrap();
+    let item_textures = registry.item_textures.read().unwrap();
+    let blocks = registry.blocks.read().unwrap();
+    let block_textures = registry.block_textures.read().unwrap();
+    let entities = registry.entities.read().unwrap();
+    let client_entities = registry.client_entities.read().unwrap();
+    let recipes = registry.recipes.read().unwrap();
+    let loot_tables = registry.loot_tables.read().unwrap();
+    let geometries = registry.geometries.read().unwrap();
+    let animations = registry.animations.read().unwrap();
+    let animation_controllers = registry.animation_controllers.read().unwrap();
+    let client_animations = registry.client_animations.read().unwrap();
+    let client_animation_controllers = registry.client_animation_controllers.read().unwrap();
+    let sounds = registry.sounds.read().unwrap();
+    let features = registry.features.read().unwrap();
+    let feature_rules = registry.feature_rules.read().unwrap();
+    let biomes = registry.biomes.read().unwrap();
+    let client_biomes = registry.client_biomes.read().unwrap();
+    let translations = registry.translations.read().unwrap();
+
+    check_identifiers(&mut diagnostics, "item", items.iter().map(|i| &i.id), &[]);
+    check_identifiers(&mut diagnostics, "item texture", item_textures.iter().map(|t| &t.id), &[]);
+    check_identifiers(&mut diagnostics, "block", blocks.iter().map(|b| &b.id), &[]);
+    check_identifiers(&mut diagnostics, "block texture", block_textures.iter().map(|t| &t.id), &[]);
+    check_identifiers(&mut diagnostics, "entity", entities.iter().map(|e| &e.id), vanilla::ENTITIES);
+    check_identifiers(&mut diagnostics, "client entity", client_entities.iter().map(|e| &e.id), vanilla::ENTITIES);
+    check_identifiers(&mut diagnostics, "recipe", recipes.iter().map(|r| &r.id), &[]);
+    check_identifiers(&mut diagnostics, "loot table", loot_tables.iter().map(|t| &t.id), &[]);
+    check_identifiers(&mut diagnostics, "feature", features.iter().map(|f| &f.id), &[]);
+    check_identifiers(&mut diagnostics, "feature rule", feature_rules.iter().map(|r| &r.id), &[]);
+    check_identifiers(&mut diagnostics, "biome", biomes.iter().map(|b| &b.id), vanilla::BIOMES);
+    check_identifiers(&mut diagnostics, "client biome", client_biomes.iter().map(|b| &b.id), vanilla::BIOMES);
+    check_file_paths(&mut diagnostics, "feature", features.iter().map(|f| &f.id));
+    check_file_paths(&mut diagnostics, "feature rule", feature_rules.iter().map(|r| &r.id));
+    check_file_paths(&mut diagnostics, "biome", biomes.iter().map(|b| &b.id));
+    check_file_paths(&mut diagnostics, "client biome", client_biomes.iter().map(|b| &b.id));
+
+    let registered = Registered {
+        items: items.iter().map(|i| i.id.render()).chain(blocks.iter().map(|b| b.id.render())).collect(),
+        item_textures: item_textures.iter().map(|t| t.id.render()).collect(),
+        blocks: blocks.iter().map(|b| b.id.render()).collect(),
+        block_textures: block_textures.iter().map(|t| t.id.render()).collect(),
+        entities: entities.iter().map(|e| e.id.render()).collect(),
+        geometries: geometries.iter().map(|g| g.id.clone()).collect(),
+        animations: animations.iter().map(|a| a.id.clone()).chain(animation_controllers.iter().map(|c| c.id.clone())).collect(),
+        client_animations: client_animations.iter().map(|a| a.id.clone()).chain(client_animation_controllers.iter().map(|c| c.id.clone())).collect(),
+        biomes: biomes.iter().map(|b| b.id.render()).collect(),
+        biome_tags: biomes.iter().flat_map(|b| b.tags.iter().map(|t| t.name().to_string())).collect(),
+        loot_tables: loot_tables.iter().map(|t| t.handle().path().clone()).collect()
+    };
+
+    validate_geometries(&mut diagnostics, &geometries);
+    validate_animations(&mut diagnostics, &animations, &animation_controllers, &client_animations, &client_animation_controllers);
+
+    for item in items.iter() {
+        item.validate(&mut diagnostics, &registered);
+    }
+    for block in blocks.iter() {
+        block.validate(&mut diagnostics, &registered);
+    }
+    for entity in entities.iter() {
+        entity.validate(&mut diagnostics, &registered);
+    }
+    for entity in client_entities.iter() {
+        entity.validate(&mut diagnostics, &registered);
+    }
+    for biome in biomes.iter() {
+        biome.validate(&mut diagnostics, &registered);
+    }
+    for biome in client_biomes.iter() {
+        biome.validate(&mut diagnostics, &registered);
+    }
+
+    sounds.validate(&mut diagnostics, &registered);
+    validate_features(&mut diagnostics, &features, &feature_rules, &registered);
+
+    for recipe in recipes.iter() {
+        recipe.validate(&mut diagnostics, &registered);
+    }
+    for table in loot_tables.iter() {
+        table.validate(&mut diagnostics, &registered);
+    }
+
+    translations.validate(&mut diagnostics);
+
+    if !translations.is_empty() {
+        let names = items.iter().filter(|i| i.components.get("minecraft:display_name").is_none()).map(|i| (&i.id, item_key(&i.id)))
+            .chain(blocks.iter().filter(|b| b.components.get("minecraft:display_name").is_none()).map(|b| (&b.id, block_key(&b.id))))
+            .chain(entities.iter().map(|e| (&e.id, entity_key(&e.id))))
+            .chain(client_entities.iter().filter(|e| e.spawn_egg.is_some()).map(|e| (&e.id, spawn_egg_key(&e.id))));
+
+        translations.validate_names(&mut diagnostics, names);
+    }
+
+    diagnostics
+}
+
+/// The identifiers of the registered content, which other content may reference.
+pub(crate) struct Registered {
+    /// Items and blocks, which recipes and loot tables may both use.
+    pub items: HashSet<String>,
+    pub item_textures: HashSet<String>,
+    pub blocks: HashSet<String>,
+    pub block_textures: HashSet<String>,
+    pub entities: HashSet<String>,
+    pub geometries: HashSet<String>,
+    /// Behavior pack animations and animation controllers.
+    pub animations: HashSet<String>,
+    /// Resource pack animations and animation controllers.
+    pub client_animations: HashSet<String>,
+    pub biomes: HashSet<String>,
+    pub biome_tags: HashSet<String>,
+    /// Loot tables by their path, e.g. `loot_tables/woah/block.json`.
+    pub loot_tables: HashSet<String>
+}
+
+impl Registered {
+    /// Vanilla items and blocks are looked up in the bundled schemas, everything else has to be registered.
+    pub fn is_known_item(&self, id: &Identifier) -> bool {
+        if id.namespace() == "minecraft" {
+            // Brewing recipes reference potions as `minecraft:potion_type:<potion>`.
+            vanilla::ITEMS.contains(&id.render().as_str()) || vanilla::BLOCKS.contains(&id.render().as_str()) || id.path().starts_with("potion_type:")
+        } else {
+            self.items.contains(&id.render())
+        }
+    }
+
+    pub fn is_known_block(&self, id: &Identifier) -> bool {
+        self.blocks.contains(&id.render()) || vanilla::BLOCKS.contains(&id.render().as_str())
+    }
+
+    pub fn is_known_item_texture(&self, texture: &str) -> bool {
+        self.item_textures.contains(texture) || vanilla::ITEM_TEXTURES.contains(&texture)
+    }
+
+    /// Registered entities, and vanilla ones, whose client side, sounds etc. can be overridden.
+    pub fn is_known_entity(&self, id: &Identifier) -> bool {
+        self.entities.contains(&id.render()) || vanilla::ENTITIES.contains(&id.render().as_str())
+    }
+
+    pub fn is_known_biome(&self, id: &Identifier) -> bool {
+        self.biomes.contains(&id.render()) || vanilla::BIOMES.contains(&id.render().as_str())
+    }
+
+    pub fn is_known_geometry(&self, id: &str) -> bool {
+        self.geometries.contains(id) || vanilla::GEOMETRIES.contains(&id) || id.starts_with("minecraft:")
+    }
+
+    pub fn is_known_loot_table(&self, path: &str) -> bool {
+        self.loot_tables.contains(path) || vanilla::LOOT_TABLES.contains(&path)
+    }
+}
+
+/// Identifiers need a namespace and have to be unique per kind of content.<br>
+/// The `minecraft` namespace is reserved, except for identifiers in `overridable`: the vanilla content of that kind, which packs may redefine.
+fn check_identifiers<'a>(diagnostics: &mut Diagnostics, kind: &str, ids: impl Iterator<Item = &'a Identifier>, overridable: &[&str]) {
+    let mut seen = HashSet::new();
+
+    for id in ids {
+        if id.namespace().is_empty() {
+            diagnostics.error(id, format!("The {} identifier is missing a namespace", kind));
+        }
+        if id.namespace() == "minecraft" && !overridable.contains(&id.render().as_str()) {
+            diagnostics.error(id, format!("The {} uses the reserved `minecraft` namespace", kind));
+        }
+        if !seen.insert(id.render()) {
+            diagnostics.error(id, format!("The {} identifier is registered more than once", kind));
+        }
+    }
+}
+
+/// Some content is written to a file named after the path of its identifier alone, e.g. `biomes/plains.biome.json`,
+/// so two identifiers with the same path in different namespaces would overwrite each other's file.
+fn check_file_paths<'a>(diagnostics: &mut Diagnostics, kind: &str, ids: impl Iterator<Item = &'a Identifier>) {
+    let mut seen: HashMap<&String, &Identifier> = HashMap::new();
+
+    for id in ids {
+        let other = *seen.entry(id.path()).or_insert(id);
+        if other.namespace() != id.namespace() {
+            diagnostics.error(id, format!("The {} is written to the same file as `{}`, as both identifiers have the path `{}`", kind, other.render(), id.path()));
+        }
+    }
+}
+
+/// Identifiers which aren't namespaced, like geometries and animations, start with a prefix instead.
+pub(crate) fn check_unique_ids<'a>(diagnostics: &mut Diagnostics, kind: &str, prefix: &str, ids: impl Iterator<Item = &'a String>) {
+    let mut seen = HashSet::new();
+
+    for id in ids {
+        if !id.starts_with(prefix) {
+            diagnostics.error(id, format!("The {} identifier has to start with `{}`", kind, prefix));
+        }
+        if !seen.insert(id) {
+            diagnostics.error(id, format!("The {} identifier is registered more than once", kind));
+        }
+    }
+}
+
+pub(crate) fn check_molang(diagnostics: &mut Diagnostics, subject: &impl Display, molang: &Molang) {
+    if let Err(err) = molang.validate() {
+        diagnostics.error(subject, err.to_string());
+    }
+
+    for warning in molang.warnings() {
+        diagnostics.warning(subject, warning);
+    }
+}
+
+/// `has_biome_tag` tests may only use vanilla tags or tags declared by a registered biome.
+pub(crate) fn check_biome_filter(diagnostics: &mut Diagnostics, subject: &Identifier, filter: &EntityFilter, registered: &Registered) {
+    for test in filter.tests().into_iter().filter(|t| t.test() == "has_biome_tag") {
+        if let Some(FilterValue::String(tag)) = test.value() && !registered.biome_tags.contains(tag) && !vanilla::BIOME_TAGS.contains(&tag.as_str()) {
+            diagnostics.error(subject, format!("The biome filter tests for an unknown biome tag `{}`", tag));
+        }
+    }
+}
+
+/// Typed components only fit the format version of their module.
+pub(crate) fn check_components_version(diagnostics: &mut Diagnostics, subject: &Identifier, kind: &str, components: &ComponentMap, format_version: &SemVer) {
+    if let Some(version) = components.format_version().filter(|v| *v != format_version) {
+        diagnostics.error(subject, format!(
+            "The components are typed for format version `{}`, but the {} uses `{}`", version.render_dotted(), kind, format_version.render_dotted()
+        ));
+    }
+}
+
+/// Component groups and permutations are written with the format version of their entity or block.
+pub(crate) fn check_group_version(diagnostics: &mut Diagnostics, subject: &Identifier, group: &str, components: &ComponentMap, format_version: &SemVer) {
+    if let Some(version) = components.format_version().filter(|v| *v != format_version) {
+        diagnostics.error(subject, format!(
+            "The {} is typed for format version `{}`, but its parent uses `{}`", group, version.render_dotted(), format_version.render_dotted()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::RangeInclusive;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, ToSJson, TransformHashMap};
+    use crate::animation::controller::{AnimationController, AnimationState};
+    use crate::biome::{Biome, BiomeTag};
+    use crate::core::utilities::HoldBuilder;
+    use crate::entity::Entity;
+    use crate::entity::client::ClientEntity;
+    use crate::entity::component_group::EntityComponentGroup;
+    use crate::entity::components::v1_26_20;
+    use crate::entity::components::v1_26_20::BehaviorFloat;
+    use crate::entity::event::{EntityEvent, EntityEventComponentGroups, NamedEntityEvent};
+    use crate::entity::property::{EntityEnumProperty, EntityIntProperty, EntityProperty};
+    use crate::item::Item;
+    use crate::item::components::v1_26_10::{components, MaxStackSize};
+    use crate::recipe::{Recipe, ShapedRecipe};
+    use crate::worldgen::{AggregateFeature, Feature};
+    use crate::worldgen::rule::{Distribution, FeatureRule};
+    use crate::woah;
+
+    #[test]
+    fn validation() {
+        let registry = CoreRegistry::default();
+        let item: Item = woah! {
+            @Item {
+                id = "minecraft:stick";
+                components = sjson! {
+                    minecraft:icon = "woah:missing"
+                };
+            }
+        };
+        registry.register_item(item.clone());
+        registry.register_item(item);
+        registry.register_recipe(woah! {
+            @Recipe {
+                id = "woah:recipe";
+                recipe = @ShapedRecipe {
+                    pattern = map vec!["#X"];
+                    key = ('#', "woah:unknown");
+                    result = "minecraft:stick";
+                };
+            }
+        });
+
+        // Broken entities are reported by the validation instead of failing their builders.
+        registry.register_entity(woah! {
+            @Entity {
+                id = "woah:broken_entity";
+                components = sjson! {};
+                properties = vec![
+                    @EntityProperty {
+                        id = "woah:level";
+                        property = @EntityIntProperty {
+                            range = RangeInclusive::new(5, 1);
+                            default = 2;
+                        }
+                    },
+                    @EntityProperty {
+                        id = "woah:mood";
+                        property = @EntityEnumProperty {
+                            values = map vec!["calm"];
+                            default = "angry";
+                        }
+                    }
+                ];
+                events = vec![
+                    @NamedEntityEvent {
+                        name = "woah:grow";
+                        event = @EntityEvent {
+                            add = @EntityEventComponentGroups {
+                                component_groups = map vec!["woah:adult"];
+                            };
+                        };
+                    },
+                    @NamedEntityEvent {
+                        name = "woah:grow";
+                        event = @EntityEvent {};
+                    }
+                ];
+            }
+        });
+
+        // Typed components only fit the format version of their module.
+        registry.register_entity(woah! {
+            @Entity {
+                id = "woah:old_entity";
+                format_version = (1, 21, 0);
+                components = sjson! {};
+                component_groups = vec![
+                    @EntityComponentGroup {
+                        id = "woah:floating";
+                        components = v1_26_20::components((
+                            @BehaviorFloat { priority = Some(0); },
+                        ));
+                    }
+                ];
+            }
+        });
+        registry.register_item(woah! {
+            @Item {
+                id = "woah:old_item";
+                format_version = (1, 21, 0);
+                components = components((
+                    MaxStackSize::Integer(16),
+                ));
+            }
+        });
+
+        // Vanilla entities may be overridden, other `minecraft` identifiers are reserved.
+        registry.register_entity(woah! {
+            @Entity {
+                id = "minecraft:pig";
+                components = sjson! {};
+            }
+        });
+        registry.register_client_entity(woah! {
+            @ClientEntity {
+                id = "minecraft:pig";
+            }
+        });
+        registry.register_client_entity(woah! {
+            @ClientEntity {
+                id = "minecraft:not_a_mob";
+            }
+        });
+
+        registry.register_animation_controller(woah! {
+            @AnimationController {
+                id = "controller.animation.woah.broken";
+                states = vec![
+                    @AnimationState {
+                        name = "default";
+                        transition = ("missing", Molang::new("q.is_baby"));
+                    }
+                ];
+            }
+        });
+
+        registry.register_feature_rule(woah! {
+            @FeatureRule {
+                id = "woah:broken_feature_rule";
+                places_feature = "woah:missing_feature";
+                biome_filter = BiomeTag::new("woah_unknown");
+                distribution = @Distribution {
+                    iterations = "4";
+                };
+            }
+        });
+
+        let diagnostics = validate_registry(&registry);
+        let messages = diagnostics.errors().map(|d| d.message.clone()).collect::<Vec<String>>();
+
+        assert_eq!(messages, vec![
+            "The item uses the reserved `minecraft` namespace",
+            "The item uses the reserved `minecraft` namespace",
+            "The item identifier is registered more than once",
+            "The client entity uses the reserved `minecraft` namespace",
+            "The state `default` transitions to an undeclared state `missing`",
+            "`minecraft:icon` references an unregistered item texture `woah:missing`",
+            "`minecraft:icon` references an unregistered item texture `woah:missing`",
+            "The components are typed for format version `1.26.10`, but the item uses `1.21.0`",
+            "The event `woah:grow` references an undeclared component group `woah:adult`",
+            "The event `woah:grow` is declared more than once",
+            "The property `woah:level` has an empty range 5..=1",
+            "The property `woah:mood` has a default `angry` which isn't one of its values [\"calm\"]",
+            "The component group `woah:floating` is typed for format version `1.26.20`, but its parent uses `1.21.0`",
+            "The client entity has no matching registered entity",
+            "The feature rule places an unknown feature `woah:missing_feature`",
+            "The biome filter tests for an unknown biome tag `woah_unknown`",
+            "The recipe references an unknown item `woah:unknown`",
+            "The pattern symbol `X` isn't mapped by the key",
+        ]);
+    }
+
+    #[test]
+    fn file_paths() {
+        let registry = CoreRegistry::default();
+        for id in ["woah:plains", "minecraft:plains", "woah:crystal_caves"] {
+            registry.register_biome(woah! {
+                @Biome {
+                    id = id;
+                }
+            });
+        }
+        for (id, rule) in [("woah:ruby_ore_feature", "woah:ruby_ore_feature_rule"), ("gems:ruby_ore_feature", "gems:ruby_ore_feature_rule")] {
+            registry.register_feature(woah! {
+                @Feature {
+                    id = id;
+                    feature = @AggregateFeature {
+                        features = map vec!["minecraft:coal_ore_feature"];
+                    };
+                }
+            });
+            registry.register_feature_rule(woah! {
+                @FeatureRule {
+                    id = rule;
+                    places_feature = id;
+                    distribution = @Distribution {
+                        iterations = "1";
+                    };
+                }
+            });
+        }
+
+        let diagnostics = validate_registry(&registry);
+        let messages = diagnostics.errors().map(|d| (d.subject.clone(), d.message.clone())).collect::<Vec<(String, String)>>();
+
+        assert_eq!(messages, vec![
+            ("gems:ruby_ore_feature".to_string(), "The feature is written to the same file as `woah:ruby_ore_feature`, as both identifiers have the path `ruby_ore_feature`".to_string()),
+            ("gems:ruby_ore_feature_rule".to_string(), "The feature rule is written to the same file as `woah:ruby_ore_feature_rule`, as both identifiers have the path `ruby_ore_feature_rule`".to_string()),
+            ("minecraft:plains".to_string(), "The biome is written to the same file as `woah:plains`, as both identifiers have the path `plains`".to_string())
+        ]);
+    }
+}
@@ -0,0 +1,2 @@
+// Vanilla names, extracted from `schemas/minecraftBedrock/schema/general/vanilla` at build time.
+include!(concat!(env!("OUT_DIR"), "/core/vanilla.rs"));
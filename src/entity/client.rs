@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::animation::{validate_animate, Animate};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::sprite::Sprite;
+use crate::core::utilities::{BedrockSerializeVec, Identifier, SemVer};
+use crate::core::validation::{check_molang, Diagnostics, Registered};
+use crate::core::vanilla;
+use crate::hold_builders;
+use crate::molang::ast::{MolangExpr, MolangNamespace};
+use crate::molang::Molang;
+
+hold_builders!(ClientEntity, RenderController);
+
+/// A struct for describing the client side of an Entity, written to `RP/entity/`. It's linked to the [Entity](crate::entity::Entity) with the same identifier.<br>
+/// Textures, geometries, materials and animations are keyed by short names (e.g. `default`), which render controllers refer to as `texture.default` etc.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ClientEntity {
+    #[builder(default = "SemVer::new(1, 10, 0)")]
+    pub format_version: SemVer,
+    pub id: Identifier,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub materials: BTreeMap<String, String>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub textures: BTreeMap<String, Sprite>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub geometry: BTreeMap<String, String>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub animations: BTreeMap<String, String>,
+    /// The short names of the animations played by `scripts.animate`.
+    #[builder(setter(into = false), default = "Vec::new()")]
+    pub animate: Vec<Animate>,
+    /// Falls back to the vanilla `controller.render.default` when empty.
+    #[builder(default = "Vec::new()")]
+    pub render_controllers: Vec<RenderController>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub spawn_egg: Option<SpawnEgg>
+}
+
+/// A render controller, written to `RP/render_controllers/` next to the client entity using it.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct RenderController {
+    /// Has to start with `controller.render.`.
+    pub id: String,
+    #[builder(default = "Molang::new(\"geometry.default\")")]
+    geometry: Molang,
+    #[builder(default = "vec![Molang::new(\"texture.default\")]")]
+    textures: Vec<Molang>,
+    /// Bone name patterns mapped to materials, `*` matches every bone.
+    #[builder(default = "vec![(\"*\".to_string(), Molang::new(\"material.default\"))]")]
+    materials: Vec<(String, Molang)>
+}
+
+/// The spawn egg of an entity, either colored or using a registered [ItemTexture](crate::item::client::ItemTexture).
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpawnEgg {
+    /// `0xRRGGBB` base and overlay colors.
+    Colors(u32, u32),
+    Texture(Identifier)
+}
+
+impl ClientEntityBuilder {
+    /// Adds a material under a short name, e.g. `material = ("default", "entity_alphatest");`.
+    pub fn material<N: Into<String>, M: Into<String>>(&mut self, (name, material): (N, M)) -> &mut Self {
+        self.materials.get_or_insert_with(BTreeMap::new).insert(name.into(), material.into());
+        self
+    }
+
+    /// Adds a texture under a short name. It's generated to `RP/textures/entity/<pack>/<entity>_<name>.png`.
+    pub fn texture<N: Into<String>>(&mut self, (name, sprite): (N, Sprite)) -> &mut Self {
+        self.textures.get_or_insert_with(BTreeMap::new).insert(name.into(), sprite);
+        self
+    }
+
+    /// Adds a geometry identifier under a short name, e.g. `geometry = ("default", "geometry.woah.cow");`.
+    pub fn geometry<N: Into<String>, G: Into<String>>(&mut self, (name, geometry): (N, G)) -> &mut Self {
+        self.geometry.get_or_insert_with(BTreeMap::new).insert(name.into(), geometry.into());
+        self
+    }
+
+    /// Adds an animation or animation controller under a short name, e.g. `animation = ("walk", "animation.woah.cow.walk");`.
+    pub fn animation<N: Into<String>, A: Into<String>>(&mut self, (name, animation): (N, A)) -> &mut Self {
+        self.animations.get_or_insert_with(BTreeMap::new).insert(name.into(), animation.into());
+        self
+    }
+}
+
+impl ClientEntity {
+    /// The path of a texture relative to the RP, without the extension as the client entity references it.
+    pub fn texture_path(&self, pack_name: &str, name: &str) -> String {
+        format!("textures/entity/{}/{}_{}", pack_name, self.id.render_underscore(), name)
+    }
+
+    /// The client entity file of the pack named `pack_name`. Texture paths depend on the pack name, so this isn't [BedrockSerializable].
+    pub fn bedrock_json(&self, pack_name: &str) -> WoahResult<Value> {
+        let mut description = Map::new();
+        description.insert("identifier".to_string(), Value::from(self.id.render()));
+
+        let materials = if self.materials.is_empty() {
+            json!({ "default": "entity_alphatest" })
+        } else {
+            json!(self.materials)
+        };
+        description.insert("materials".to_string(), materials);
+
+        description.insert("textures".to_string(), Value::Object(
+            self.textures.keys().map(|name| (name.clone(), Value::from(self.texture_path(pack_name, name)))).collect()
+        ));
+        description.insert("geometry".to_string(), json!(self.geometry));
+
+        if !self.animations.is_empty() {
+            description.insert("animations".to_string(), json!(self.animations));
+        }
+        if !self.animate.is_empty() {
+            description.insert("scripts".to_string(), json!({ "animate": self.animate.serialize_vec()? }));
+        }
+
+        let render_controllers = if self.render_controllers.is_empty() {
+            vec!["controller.render.default".to_string()]
+        } else {
+            self.render_controllers.iter().map(|c| c.id.clone()).collect()
+        };
+        description.insert("render_controllers".to_string(), Value::from(render_controllers));
+
+        if let Some(spawn_egg) = &self.spawn_egg {
+            description.insert("spawn_egg".to_string(), spawn_egg.bedrock_json()?);
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:client_entity": {
+                "description": description
+            }
+        }))
+    }
+
+    /// The client entity may override a vanilla entity, otherwise its entity has to be registered.
+    /// Animations, geometries and textures may only reference registered or vanilla content.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        for animation in self.animations.values() {
+            let vanilla = vanilla::CLIENT_ANIMATIONS.contains(&animation.as_str()) || vanilla::CLIENT_ANIMATION_CONTROLLERS.contains(&animation.as_str());
+
+            if !registered.client_animations.contains(animation) && !vanilla {
+                diagnostics.error(&self.id, format!("The client entity references an unregistered client animation `{}`", animation));
+            }
+        }
+
+        validate_animate(diagnostics, &self.id, &self.animate, &self.animations);
+
+        if !registered.is_known_entity(&self.id) {
+            diagnostics.error(&self.id, "The client entity has no matching registered entity");
+        }
+
+        if let Some(SpawnEgg::Texture(texture)) = &self.spawn_egg && !registered.is_known_item_texture(&texture.render()) {
+            diagnostics.error(&self.id, format!("The spawn egg references an unregistered item texture `{}`", texture));
+        }
+
+        for geometry in self.geometry.values() {
+            if !registered.is_known_geometry(geometry) {
+                diagnostics.error(&self.id, format!("The client entity references an unregistered geometry `{}`", geometry));
+            }
+        }
+
+        self.validate_render_controllers(diagnostics);
+    }
+
+    /// Render controllers may only pick the geometries, textures and materials the client entity declares.
+    fn validate_render_controllers(&self, diagnostics: &mut Diagnostics) {
+        for controller in &self.render_controllers {
+            if !controller.id.starts_with("controller.render.") {
+                diagnostics.error(&self.id, format!("The render controller `{}` has to start with `controller.render.`", controller.id));
+            }
+
+            for statement in controller.statements() {
+                check_molang(diagnostics, &self.id, statement);
+
+                let Ok(expr) = statement.expr() else { continue };
+                expr.walk(&mut |e| {
+                    let MolangExpr::Access(access) = e else { return };
+                    let declared = match access.namespace {
+                        MolangNamespace::Geometry => self.geometry.contains_key(&access.name()),
+                        MolangNamespace::Texture => self.textures.contains_key(&access.name()),
+                        MolangNamespace::Material => self.materials.contains_key(&access.name()) || (self.materials.is_empty() && access.name() == "default"),
+                        _ => return
+                    };
+
+                    if !declared {
+                        diagnostics.error(&self.id, format!("The render controller `{}` references an undeclared `{}`", controller.id, access));
+                    }
+                });
+            }
+        }
+    }
+}
+
+impl RenderController {
+    /// Every Molang statement of the controller.
+    pub fn statements(&self) -> Vec<&Molang> {
+        std::iter::once(&self.geometry)
+            .chain(self.textures.iter())
+            .chain(self.materials.iter().map(|(_, material)| material))
+            .collect()
+    }
+}
+
+impl BedrockSerializable for RenderController {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            &self.id: {
+                "geometry": self.geometry,
+                "materials": self.materials.iter().map(|(bones, material)| json!({ bones: material })).collect::<Vec<Value>>(),
+                "textures": self.textures
+            }
+        }))
+    }
+}
+
+impl BedrockSerializable for SpawnEgg {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            SpawnEgg::Colors(base, overlay) => json!({
+                "base_color": format!("#{:06X}", base),
+                "overlay_color": format!("#{:06X}", overlay)
+            }),
+            SpawnEgg::Texture(texture) => json!({
+                "texture": texture.render()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::animation::ClientAnimation;
+    use crate::animation::controller::{AnimationController, AnimationState};
+    use crate::core::testing;
+    use crate::entity::Entity;
+    use crate::geometry::{Geometry, GeometryBone};
+    use crate::woah;
+
+    fn geometry() -> Geometry {
+        woah! {
+            @Geometry {
+                id = "geometry.cool.entity";
+                bones = vec![
+                    @GeometryBone {
+                        name = "body";
+                    }
+                ];
+            }
+        }
+    }
+
+    #[test]
+    fn client_entity() {
+        let memory = testing::build(|events| {
+            events.geometry_registration.subscribe(|reg| {
+                reg.register_geometry(geometry());
+            });
+            events.entity_registration.subscribe(|reg| {
+                reg.register_entity(woah! {
+                    @Entity {
+                        id = "cool:entity";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.client_animation_registration.subscribe(|reg| {
+                reg.register_client_animation(woah! {
+                    @ClientAnimation {
+                        id = "animation.cool.entity.walk";
+                    }
+                });
+                reg.register_animation_controller(woah! {
+                    @AnimationController {
+                        id = "controller.animation.cool.entity.move";
+                        states = vec![
+                            @AnimationState {
+                                name = "default";
+                            }
+                        ];
+                    }
+                });
+            });
+            events.client_entity_registration.subscribe(|reg| {
+                reg.register_client_entity(woah! {
+                    @ClientEntity {
+                        id = "cool:entity";
+                        texture = ("default", Sprite::read("./textures/item.png").unwrap());
+                        geometry = ("default", geometry().handle());
+                        animation = ("walk", "animation.cool.entity.walk");
+                        animation = ("move", "controller.animation.cool.entity.move");
+                        animate = map vec!["move"];
+                        render_controllers = vec![
+                            @RenderController {
+                                id = "controller.render.cool.entity";
+                            }
+                        ];
+                        spawn_egg = SpawnEgg::Colors(0x2B5C1F, 0xFFFFFF);
+                    }
+                })
+            });
+        });
+
+        let client_entity = memory.read_json("WoahTest_RP/entity/cool_entity.entity.json").unwrap();
+        assert_eq!(client_entity["minecraft:client_entity"]["description"], json!({
+            "identifier": "cool:entity",
+            "materials": { "default": "entity_alphatest" },
+            "textures": { "default": "textures/entity/WoahTest/cool_entity_default" },
+            "geometry": { "default": "geometry.cool.entity" },
+            "animations": { "move": "controller.animation.cool.entity.move", "walk": "animation.cool.entity.walk" },
+            "scripts": { "animate": ["move"] },
+            "render_controllers": ["controller.render.cool.entity"],
+            "spawn_egg": { "base_color": "#2B5C1F", "overlay_color": "#FFFFFF" }
+        }));
+        let controllers = memory.read_json("WoahTest_RP/render_controllers/cool_entity.render_controllers.json").unwrap();
+        assert_eq!(controllers["render_controllers"]["controller.render.cool.entity"]["textures"], json!(["texture.default"]));
+        assert!(memory.read("WoahTest_RP/textures/entity/WoahTest/cool_entity_default.png").is_some());
+    }
+}
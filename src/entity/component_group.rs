@@ -1,9 +1,8 @@
-use std::collections::HashMap;
 use derive_builder::Builder;
-use eo::sjson::SJsonValue;
-use tera::Context;
+use serde_json::{json, Value};
 use crate::bedrock::BedrockSerializable;
-use crate::code_gen::TEMPLATES;
+use crate::core::error::WoahResult;
+use crate::core::utilities::ComponentMap;
 use crate::hold_builders;
 
 hold_builders!(EntityComponentGroup);
@@ -12,25 +11,28 @@ hold_builders!(EntityComponentGroup);
 #[builder(setter(into))]
 pub struct EntityComponentGroup {
     id: String,
-    components: HashMap<String, SJsonValue>
+    components: ComponentMap
 }
 
 impl EntityComponentGroup {
     pub fn new(
         id: impl Into<String>,
-        components: HashMap<String, SJsonValue>
+        components: impl Into<ComponentMap>
     ) -> Self {
-        Self { id: id.into(), components }
+        Self { id: id.into(), components: components.into() }
+    }
+
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn components(&self) -> &ComponentMap {
+        &self.components
     }
 }
 
 impl BedrockSerializable for EntityComponentGroup {
-    fn bedrock_serialize(&self) -> String {
-        let components = serde_json::to_string(&self.components).unwrap();
-        let mut context = Context::new();
-        context.insert("id", &self.id);
-        context.insert("components", &components);
-
-        TEMPLATES.render("entity/component_group.jsont", &context).unwrap()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({ &self.id: self.components.to_value()? }))
     }
 }
\ No newline at end of file
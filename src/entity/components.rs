@@ -0,0 +1,5 @@
+pub trait Component {
+    const ID: &'static str;
+}
+
+include!(concat!(env!("OUT_DIR"), "/entity/components.rs"));
@@ -1,12 +1,35 @@
-use std::collections::HashMap;
 use derive_builder::Builder;
-use eo::sjson::{SJsonElement, SJsonMacro, SJsonValue};
-use tera::Context;
+use eo::sjson::SJsonMacro;
+use serde_json::{json, Map, Value};
 use crate::bedrock::BedrockSerializable;
-use crate::code_gen::TEMPLATES;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, ComponentMap};
+use crate::entity::filter::EntityFilter;
 use crate::hold_builders;
 
-hold_builders!(EntityEvent, EntityEventComponentGroups, EntityEventQueueCommand, EntityFilter);
+hold_builders!(NamedEntityEvent, EntityEvent, EntityEventComponentGroups, EntityEventQueueCommand);
+
+/// A top-level Entity event, keyed by its name (e.g. `woah:become_angry`) in the `events` object.
+#[derive(Builder, Clone, Debug)]
+#[builder(setter(into))]
+pub struct NamedEntityEvent {
+    name: String,
+    event: EntityEvent
+}
+
+impl NamedEntityEvent {
+    pub fn new(name: impl Into<String>, event: EntityEvent) -> Self {
+        Self { name: name.into(), event }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn event(&self) -> &EntityEvent {
+        &self.event
+    }
+}
 
 #[derive(Builder, Clone, Debug)]
 #[builder(setter(into))]
@@ -21,8 +44,8 @@ pub struct EntityEvent {
     randomize: Option<Vec<EntityEvent>>,
     #[builder(default = "None")]
     weight: Option<f64>,
-    #[builder(default = "None")]
-    filters: Option<Vec<EntityFilter>>,
+    #[builder(setter(into, strip_option), default = "None")]
+    filters: Option<EntityFilter>,
     #[builder(default = "None")]
     sequence: Option<Vec<EntityEvent>>,
     #[builder(default = "None")]
@@ -32,6 +55,7 @@ pub struct EntityEvent {
 #[derive(Clone, Debug, Builder)]
 #[builder(setter(into))]
 pub struct EntityEventComponentGroups {
+    #[builder(setter(into = false))]
     component_groups: Vec<String>
 }
 
@@ -45,115 +69,127 @@ pub struct EntityEventQueueCommand {
 impl EntityEventQueueCommandBuilder {
     pub fn command(&mut self, command: impl Into<String>) -> &mut Self {
         if self.commands.is_none() { self.commands = Some(Vec::new()) }
-        self.commands.as_mut().map(|v| v.push(command.into()));
+        if let Some(v) = self.commands.as_mut() { v.push(command.into()) }
         self
     }
 }
 
 
-#[derive(Clone, Debug, Builder)]
-#[builder(setter(into))]
-pub struct EntityFilter {
-    test: String,
-    operator: String,
-    value: String
-}
-
-impl BedrockSerializable for EntityFilter
-{
-    fn bedrock_serialize(&self) -> String {
-        let mut ctx = Context::new();
+impl EntityEvent {
+    /// Returns every component group this event (and its `randomize`/`sequence` children) adds or removes.
+    pub fn referenced_component_groups(&self) -> Vec<&String> {
+        let mut groups = Vec::new();
 
-        ctx.insert("test", &self.test);
-        ctx.insert("operator", &self.operator);
-        ctx.insert("value", &format!("{:?}", &self.value));
+        if let Some(add) = &self.add {
+            groups.extend(add.component_groups.iter());
+        }
+        if let Some(remove) = &self.remove {
+            groups.extend(remove.component_groups.iter());
+        }
+        for child in self.randomize.iter().chain(self.sequence.iter()).flatten() {
+            groups.extend(child.referenced_component_groups());
+        }
 
-        TEMPLATES.render("entity/events/filter.jsont", &ctx).unwrap()
+        groups
     }
 }
 
 impl BedrockSerializable for EntityEvent {
-    fn bedrock_serialize(&self) -> String {
-        let mut res = "{".to_string();
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut event = Map::new();
 
         if let Some(add) = &self.add {
-            let mut ctx = Context::new();
-            ctx.insert("component_groups", &format!("{:?}", &add.component_groups));
-            res.push_str(
-                &TEMPLATES.render("entity/events/add.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("add".to_string(), json!({ "component_groups": add.component_groups }));
         }
         if let Some(remove) = &self.remove {
-            let mut ctx = Context::new();
-            ctx.insert("component_groups", &format!("{:?}", &remove.component_groups));
-            res.push_str(
-                &TEMPLATES.render("entity/events/remove.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("remove".to_string(), json!({ "component_groups": remove.component_groups }));
         }
         if let Some(cmd) = &self.queue_command {
-            let mut ctx = Context::new();
-            ctx.insert("commands", &format!("{:?}", &cmd.commands));
-            ctx.insert("target", &cmd.target);
-            res.push_str(
-                &TEMPLATES.render("entity/events/queue_command.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("queue_command".to_string(), json!({ "target": cmd.target, "command": cmd.commands }));
         }
         if let Some(rnd) = &self.randomize {
-            let mut ctx = Context::new();
-            ctx.insert("other", &rnd.iter().map(|v| v.bedrock_serialize()).collect::<Vec<String>>().join(","));
-
-            res.push_str(
-                &TEMPLATES.render("entity/events/randomize.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("randomize".to_string(), Value::Array(rnd.serialize_vec()?));
         }
         if let Some(w) = &self.weight {
-            let mut ctx = Context::new();
-            ctx.insert("k", "weight");
-            ctx.insert("v", w);
-
-            res.push_str(
-                &TEMPLATES.render("generic/kv.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("weight".to_string(), json!(w));
         }
         if let Some(filters) = &self.filters {
-            let mut ctx = Context::new();
-            ctx.insert("k", "filters");
-            ctx.insert("v", &format!("[{}]", &filters.iter().map(|v| v.bedrock_serialize()).collect::<Vec<String>>().join(",")));
-
-            res.push_str(
-                &TEMPLATES.render("generic/kv.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("filters".to_string(), filters.bedrock_json()?);
         }
         if let Some(seq) = &self.sequence {
-            let mut ctx = Context::new();
-            ctx.insert("k", "sequence");
-            ctx.insert("v", &format!("[{}]", seq.iter().map(|v| v.bedrock_serialize()).collect::<Vec<String>>().join(",")));
-
-            res.push_str(
-                &TEMPLATES.render("generic/kv.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("sequence".to_string(), Value::Array(seq.serialize_vec()?));
         }
         if let Some(set) = &self.set_property {
-            let mut ctx = Context::new();
-            ctx.insert("k", "set_property");
-            ctx.insert("v", &set.serialize());
-
-            res.push_str(
-                &TEMPLATES.render("generic/kv.jsont", &ctx).unwrap()
-            );
-            res.push(',');
+            event.insert("set_property".to_string(), ComponentMap::from(set.clone()).to_value()?);
         }
-        if res.ends_with(',') { res = res.strip_suffix(',').unwrap().to_string() }
 
-        res.push('}');
+        Ok(Value::Object(event))
+    }
+}
 
-        res
+impl BedrockSerializable for NamedEntityEvent {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({ &self.name: self.event.bedrock_json()? }))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, ToSJson, TransformHashMap};
+    use crate::entity::filter::EntityFilterTest;
+    use crate::woah;
+
+    #[test]
+    fn named_event() {
+        let event: NamedEntityEvent = woah! {
+            @NamedEntityEvent {
+                name = "woah:grow_up";
+                event = @EntityEvent {
+                    add = @EntityEventComponentGroups {
+                        component_groups = map vec!["woah:adult"];
+                    };
+                    remove = @EntityEventComponentGroups {
+                        component_groups = map vec!["woah:baby"];
+                    };
+                    randomize = vec![
+                        @EntityEvent {
+                            weight = 3.0;
+                            queue_command = @EntityEventQueueCommand {
+                                target = "self";
+                                command("say grown");
+                            };
+                        },
+                        @EntityEvent {
+                            weight = 1.0;
+                            sequence = vec![
+                                @EntityEvent {
+                                    filters = @EntityFilterTest {
+                                        test = "is_baby";
+                                    };
+                                    set_property = sjson! {
+                                        woah:level = 2,
+                                        woah:angry = true,
+                                    };
+                                }
+                            ];
+                        }
+                    ];
+                };
+            }
+        };
+        assert_eq!(event.bedrock_json().unwrap(), json!({
+            "woah:grow_up": {
+                "add": { "component_groups": ["woah:adult"] },
+                "remove": { "component_groups": ["woah:baby"] },
+                "randomize": [
+                    { "weight": 3.0, "queue_command": { "target": "self", "command": ["say grown"] } },
+                    { "weight": 1.0, "sequence": [
+                        { "filters": { "test": "is_baby" }, "set_property": { "woah:angry": true, "woah:level": 2 } }
+                    ] }
+                ]
+            }
+        }));
+    }
+}
@@ -0,0 +1,278 @@
+use std::ops::{BitAnd, BitOr, Not};
+use derive_builder::Builder;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeMap;
+use serde_json::Value;
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::hold_builders;
+
+hold_builders!(EntityFilterTest);
+
+/// A tree of Entity filters, used by event `filters` and by components taking filters.<br>
+/// Filters can be combined with the operators & for `all_of`, | for `any_of` and ! for `none_of`.
+/// ```rust
+/// use woah::entity::filter::{EntityFilterTest, FilterSubject};
+/// use woah::core::utilities::HoldBuilder;
+///
+/// let is_baby = EntityFilterTest::builder().test("is_baby").build().unwrap();
+/// let in_plains = EntityFilterTest::builder().test("has_biome_tag").value("plains").build().unwrap();
+/// let is_player = EntityFilterTest::builder().test("is_family").subject(FilterSubject::Other).value("player").build().unwrap();
+///
+/// (is_baby | in_plains) & !is_player;
+/// ```
+#[derive(Clone, Debug)]
+pub enum EntityFilter {
+    Test(EntityFilterTest),
+    AllOf(Vec<EntityFilter>),
+    AnyOf(Vec<EntityFilter>),
+    NoneOf(Vec<EntityFilter>)
+}
+
+/// A single filter test, e.g. `{ "test": "has_biome_tag", "value": "plains" }`.
+#[derive(Clone, Debug, Builder, Serialize)]
+#[builder(setter(into))]
+pub struct EntityFilterTest {
+    test: String,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<FilterSubject>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operator: Option<String>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<FilterValue>
+}
+
+/// The entity a filter test is run against.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterSubject {
+    #[serde(rename = "self")]
+    Self_,
+    Other,
+    Parent,
+    Player,
+    Target,
+    Baby,
+    Damager,
+    Block
+}
+
+/// A value compared by a filter test.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum FilterValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool)
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::String(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::String(value)
+    }
+}
+
+impl From<i32> for FilterValue {
+    fn from(value: i32) -> Self {
+        FilterValue::Int(value as i64)
+    }
+}
+
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        FilterValue::Int(value)
+    }
+}
+
+impl From<f32> for FilterValue {
+    fn from(value: f32) -> Self {
+        FilterValue::Float(value as f64)
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        FilterValue::Float(value)
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        FilterValue::Bool(value)
+    }
+}
+
+impl EntityFilter {
+    pub fn all_of(filters: Vec<EntityFilter>) -> Self {
+        EntityFilter::AllOf(filters)
+    }
+
+    pub fn any_of(filters: Vec<EntityFilter>) -> Self {
+        EntityFilter::AnyOf(filters)
+    }
+
+    pub fn none_of(filters: Vec<EntityFilter>) -> Self {
+        EntityFilter::NoneOf(filters)
+    }
+
+    pub fn and(&self, other: impl Into<EntityFilter>) -> Self {
+        match self.clone() {
+            EntityFilter::AllOf(mut filters) => {
+                filters.push(other.into());
+                EntityFilter::AllOf(filters)
+            }
+            sc => EntityFilter::AllOf(vec![sc, other.into()])
+        }
+    }
+
+    pub fn or(&self, other: impl Into<EntityFilter>) -> Self {
+        match self.clone() {
+            EntityFilter::AnyOf(mut filters) => {
+                filters.push(other.into());
+                EntityFilter::AnyOf(filters)
+            }
+            sc => EntityFilter::AnyOf(vec![sc, other.into()])
+        }
+    }
+
+    /// Every test of the filter tree, regardless of how they're combined.
+    pub fn tests(&self) -> Vec<&EntityFilterTest> {
+        match &self {
+            EntityFilter::Test(test) => vec![test],
+            EntityFilter::AllOf(filters) | EntityFilter::AnyOf(filters) | EntityFilter::NoneOf(filters) => {
+                filters.iter().flat_map(|f| f.tests()).collect()
+            }
+        }
+    }
+}
+
+impl EntityFilterTest {
+    /// A test against a value only, e.g. `EntityFilterTest::with_value("has_biome_tag", "plains")`.
+    pub fn with_value(test: impl Into<String>, value: impl Into<FilterValue>) -> Self {
+        Self { test: test.into(), subject: None, domain: None, operator: None, value: Some(value.into()) }
+    }
+
+    pub fn test(&self) -> &str {
+        &self.test
+    }
+
+    pub fn value(&self) -> Option<&FilterValue> {
+        self.value.as_ref()
+    }
+}
+
+impl From<EntityFilterTest> for EntityFilter {
+    fn from(value: EntityFilterTest) -> Self {
+        EntityFilter::Test(value)
+    }
+}
+
+/// A list of filters is treated as `all_of`, the same way Bedrock treats filter arrays.
+impl<T: Into<EntityFilter>> From<Vec<T>> for EntityFilter {
+    fn from(value: Vec<T>) -> Self {
+        EntityFilter::AllOf(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<EntityFilter>> BitAnd<T> for EntityFilter {
+    type Output = EntityFilter;
+
+    fn bitand(self, rhs: T) -> Self::Output {
+        self.and(rhs)
+    }
+}
+
+impl<T: Into<EntityFilter>> BitOr<T> for EntityFilter {
+    type Output = EntityFilter;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        self.or(rhs)
+    }
+}
+
+impl Not for EntityFilter {
+    type Output = EntityFilter;
+
+    fn not(self) -> Self::Output {
+        EntityFilter::NoneOf(vec![self])
+    }
+}
+
+impl<T: Into<EntityFilter>> BitAnd<T> for EntityFilterTest {
+    type Output = EntityFilter;
+
+    fn bitand(self, rhs: T) -> Self::Output {
+        EntityFilter::from(self).and(rhs)
+    }
+}
+
+impl<T: Into<EntityFilter>> BitOr<T> for EntityFilterTest {
+    type Output = EntityFilter;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        EntityFilter::from(self).or(rhs)
+    }
+}
+
+impl Not for EntityFilterTest {
+    type Output = EntityFilter;
+
+    fn not(self) -> Self::Output {
+        EntityFilter::NoneOf(vec![self.into()])
+    }
+}
+
+impl Serialize for EntityFilter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        let (key, filters) = match &self {
+            EntityFilter::Test(test) => return test.serialize(serializer),
+            EntityFilter::AllOf(filters) => ("all_of", filters),
+            EntityFilter::AnyOf(filters) => ("any_of", filters),
+            EntityFilter::NoneOf(filters) => ("none_of", filters)
+        };
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(key, filters)?;
+        map.end()
+    }
+}
+
+/// Filters are written into components and conditions through [BedrockSerializable::bedrock_json], which reports serialization errors.
+impl BedrockSerializable for EntityFilter {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn combined_filters() {
+        let filter = (EntityFilterTest::with_value("is_family", "player") | EntityFilterTest::with_value("is_family", "villager")) & !EntityFilterTest::with_value("is_baby", true);
+        assert_eq!(filter.bedrock_json().unwrap(), json!({
+            "all_of": [
+                { "any_of": [{ "test": "is_family", "value": "player" }, { "test": "is_family", "value": "villager" }] },
+                { "none_of": [{ "test": "is_baby", "value": true }] }
+            ]
+        }));
+    }
+}
@@ -1,19 +1,25 @@
 pub mod event;
+pub mod filter;
 pub mod component_group;
 pub mod registry;
 pub mod property;
+pub mod client;
+pub mod spawn_rule;
+pub mod components;
 
-use std::collections::HashMap;
-use std::marker::PhantomData;
+use std::collections::BTreeMap;
 use derive_builder::Builder;
-use eo::sjson::SJsonValue;
-use tera::Context;
+use serde_json::{json, Map, Value};
+use crate::animation::{validate_animate, Animate};
 use crate::bedrock::BedrockSerializable;
-use crate::code_gen::TEMPLATES;
-use crate::core::utilities::{Identifier, SemVer};
+use crate::core::component::Components;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, ComponentMap, Identifier, SemVer};
+use crate::core::validation::{check_biome_filter, check_components_version, check_group_version, Diagnostics, Registered};
 use crate::entity::component_group::EntityComponentGroup;
-use crate::entity::event::EntityEvent;
+use crate::entity::event::NamedEntityEvent;
 use crate::entity::property::EntityProperty;
+use crate::entity::spawn_rule::{SpawnCategory, SpawnRule};
 use crate::hold_builders;
 
 hold_builders!(Entity);
@@ -24,44 +30,231 @@ pub struct Entity {
     #[builder(default = "SemVer::latest()")]
     pub format_version: SemVer,
     pub id: Identifier,
-    pub components: HashMap<String, SJsonValue>,
+    #[builder(setter(custom))]
+    pub components: ComponentMap,
     #[builder(default = "Vec::new()")]
-    pub events: Vec<EntityEvent>,
+    pub events: Vec<NamedEntityEvent>,
     #[builder(default = "Vec::new()")]
     pub properties: Vec<EntityProperty>,
     #[builder(default = "Vec::new()")]
-    pub component_groups: Vec<EntityComponentGroup>
+    pub component_groups: Vec<EntityComponentGroup>,
+    /// Short names mapped to [Animation](crate::animation::Animation) or animation controller identifiers.
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub animations: BTreeMap<String, String>,
+    /// The short names of the animations played by `scripts.animate`.
+    #[builder(setter(into = false), default = "Vec::new()")]
+    pub animate: Vec<Animate>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub spawn_category: Option<SpawnCategory>,
+    /// Whether the entity has a spawn egg.
+    #[builder(default = "true")]
+    pub is_spawnable: bool,
+    /// Whether the entity can be summoned with commands.
+    #[builder(default = "true")]
+    pub is_summonable: bool,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub spawn_rule: Option<SpawnRule>
+}
+
+impl EntityBuilder {
+    /// Typed components, e.g. `components = v1_26_20::components((...));`, also set the format version of their module,
+    /// unless it's set explicitly. The validation reports an explicit format version other than the one of the components.
+    pub fn components(&mut self, components: impl Into<Components>) -> &mut Self {
+        let components = components.into();
+
+        if self.format_version.is_none() {
+            self.format_version = components.format_version;
+        }
+        self.components = Some(components.components);
+        self
+    }
+
+    /// Adds an animation or animation controller under a short name, e.g. `animation = ("moo", "animation.woah.cow.moo");`.
+    pub fn animation<N: Into<String>, A: Into<String>>(&mut self, (name, animation): (N, A)) -> &mut Self {
+        self.animations.get_or_insert_with(BTreeMap::new).insert(name.into(), animation.into());
+        self
+    }
+}
+
+impl Entity {
+    /// Event names have to be unique, events may only add or remove declared component groups, and properties have to be well-formed.<br>
+    /// Animations, spawn rules and loot tables may only reference registered or vanilla content.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        let groups = self.component_groups.iter().map(|g| g.id()).collect::<Vec<&String>>();
+        let mut names = Vec::new();
+
+        for event in &self.events {
+            if names.contains(&event.name()) {
+                diagnostics.error(&self.id, format!("The event `{}` is declared more than once", event.name()));
+            }
+            names.push(event.name());
+
+            for group in event.event().referenced_component_groups() {
+                if !groups.contains(&group) {
+                    diagnostics.error(&self.id, format!("The event `{}` references an undeclared component group `{}`", event.name(), group));
+                }
+            }
+        }
+
+        for property in &self.properties {
+            property.validate(&self.id, diagnostics);
+        }
+
+        check_components_version(diagnostics, &self.id, "entity", &self.components, &self.format_version);
+        for group in &self.component_groups {
+            check_group_version(diagnostics, &self.id, &format!("component group `{}`", group.id()), group.components(), &self.format_version);
+        }
+
+        for animation in self.animations.values() {
+            if !registered.animations.contains(animation) {
+                diagnostics.error(&self.id, format!("The entity references an unregistered animation `{}`", animation));
+            }
+        }
+
+        validate_animate(diagnostics, &self.id, &self.animate, &self.animations);
+
+        if let Some(spawn_rule) = &self.spawn_rule {
+            spawn_rule.validate(diagnostics, &self.id, registered);
+
+            for filter in spawn_rule.conditions.iter().filter_map(|c| c.biome_filter.as_ref()) {
+                check_biome_filter(diagnostics, &self.id, filter, registered);
+            }
+        }
+
+        let component_sets = std::iter::once(&self.components).chain(self.component_groups.iter().map(|g| g.components()));
+
+        for components in component_sets {
+            if let Some(path) = components.get("minecraft:loot").and_then(|l| l.get("table")).and_then(|t| t.as_str()) && !registered.is_known_loot_table(path) {
+                diagnostics.error(&self.id, format!("`minecraft:loot` references an unregistered loot table `{}`", path));
+            }
+        }
+    }
 }
 
 impl BedrockSerializable for Entity {
-    fn bedrock_serialize(&self) -> String {
-        let mut ctx = Context::new();
-
-        ctx.insert("format_version", &self.format_version.render_dotted());
-        ctx.insert("id", &self.id.render());
-        ctx.insert("spawn_category", "");
-        ctx.insert("is_summonable", &true);
-        ctx.insert("is_spawnable", &true);
-        ctx.insert("properties", "{}");
-
-        let mut component_groups = "{".to_string();
-
-        for g in &self.component_groups {
-            component_groups.push_str(
-                &g.bedrock_serialize()
-            );
-            component_groups.push(',');
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut description = Map::new();
+        description.insert("identifier".to_string(), Value::from(self.id.render()));
+        if let Some(category) = &self.spawn_category {
+            description.insert("spawn_category".to_string(), Value::from(category.render()));
+        }
+        description.insert("is_summonable".to_string(), Value::Bool(self.is_summonable));
+        description.insert("is_spawnable".to_string(), Value::Bool(self.is_spawnable));
+        description.insert("properties".to_string(), self.properties.serialize_object()?);
+
+        if !self.animations.is_empty() {
+            description.insert("animations".to_string(), json!(self.animations));
         }
-        component_groups = component_groups.strip_suffix(',').unwrap().to_string();
-        component_groups.push('}');
-        ctx.insert("component_groups", &component_groups);
-        ctx.insert("components", &serde_json::to_string(&self.components).unwrap());
-        ctx.insert("events", &format!("[{}]", &self.events.iter()
-            .map(|v| v.bedrock_serialize())
-            .collect::<Vec<String>>()
-            .join(",")
-        ));
-
-        TEMPLATES.render("entity/entity.jsont", &ctx).unwrap()
+        if !self.animate.is_empty() {
+            description.insert("scripts".to_string(), json!({ "animate": self.animate.serialize_vec()? }));
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:entity": {
+                "description": description,
+                "component_groups": self.component_groups.serialize_object()?,
+                "components": self.components.to_value()?,
+                "events": self.events.serialize_object()?
+            }
+        }))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::animation::Animation;
+    use crate::core::testing;
+    use crate::entity::components::v1_26_20::{BehaviorFloat, Health, HealthValue};
+    use crate::entity::property::{EntityBoolProperty, EntityEnumProperty, EntityIntProperty};
+    use crate::woah;
+
+    #[test]
+    fn description() {
+        let memory = testing::build(|events| {
+            events.animation_registration.subscribe(|reg| {
+                reg.register_animation(woah! {
+                    @Animation {
+                        id = "animation.cool.entity.moo";
+                    }
+                });
+            });
+            events.entity_registration.subscribe(|reg| {
+                reg.register_entity(woah! {
+                    @Entity {
+                        id = "cool:entity";
+                        components = sjson! {};
+                        animation = ("moo", "animation.cool.entity.moo");
+                        animate = vec![Animate::when("moo", "q.is_baby")];
+                        spawn_category = SpawnCategory::Creature;
+                        is_summonable = false;
+                        properties = vec![
+                            @EntityProperty {
+                                id = "a:b";
+                                client_sync = true;
+                                property = @EntityEnumProperty {
+                                    values = map vec!["a", "b", "c"];
+                                    default = "a";
+                                }
+                            },
+                            @EntityProperty {
+                                id = "a:counter";
+                                property = @EntityIntProperty {
+                                    range = 0..=10;
+                                    default = 2;
+                                }
+                            },
+                            @EntityProperty {
+                                id = "a:angry";
+                                property = @EntityBoolProperty {
+                                    default = false;
+                                }
+                            }
+                        ];
+                    }
+                })
+            });
+        });
+
+        let entity = memory.read_json("WoahTest_BP/entities/WoahTest/cool_entity.json").unwrap();
+        assert_eq!(entity["minecraft:entity"]["description"]["scripts"], json!({ "animate": [{ "moo": "q.is_baby" }] }));
+        assert_eq!(entity["minecraft:entity"]["description"]["spawn_category"], "creature");
+        assert_eq!(entity["minecraft:entity"]["description"]["is_summonable"], false);
+        assert_eq!(entity["minecraft:entity"]["description"]["properties"], json!({
+            "a:b": { "type": "enum", "values": ["a", "b", "c"], "default": "a", "client_sync": true },
+            "a:counter": { "type": "int", "range": [0, 10], "default": 2, "client_sync": false },
+            "a:angry": { "type": "bool", "default": false, "client_sync": false }
+        }));
+    }
+
+    #[test]
+    fn typed_components() {
+        let entity = woah! {
+            @Entity {
+                id = "woah:typed_entity";
+                components = components::v1_26_20::components((
+                    @Health {
+                        value = Some(HealthValue::Integer(20));
+                        max = Some(20);
+                    },
+                ));
+                component_groups = vec![
+                    @EntityComponentGroup {
+                        id = "woah:floating";
+                        components = components::v1_26_20::components((
+                            @BehaviorFloat { priority = Some(0); },
+                        ));
+                    }
+                ];
+            }
+        };
+        let entity_json = entity.bedrock_json().unwrap();
+        assert_eq!(entity_json["format_version"], "1.26.20");
+        assert_eq!(entity_json["minecraft:entity"]["components"], json!({ "minecraft:health": { "max": 20, "value": 20 } }));
+        assert_eq!(entity_json["minecraft:entity"]["component_groups"], json!({
+            "woah:floating": { "minecraft:behavior.float": { "priority": 0 } }
+        }));
+    }
+}
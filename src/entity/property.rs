@@ -1,40 +1,45 @@
-use std::collections::HashMap;
 use std::fmt::Debug;
-use std::range::Range;
-use std::rc::Rc;
-use std::sync::Arc;
+use std::ops::RangeInclusive;
 use derive_builder::Builder;
+use serde_json::{json, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
 use crate::core::utilities::Identifier;
+use crate::core::validation::Diagnostics;
 use crate::hold_builders;
 
-hold_builders!(EntityProperty, EntityFloatProperty, EntityIntProperty, EntityEnumProperty);
+hold_builders!(EntityProperty, EntityFloatProperty, EntityIntProperty, EntityEnumProperty, EntityBoolProperty);
 
+/// A struct for describing Entity properties, declared in `minecraft:entity.description.properties`.
 #[derive(Clone, Debug, Builder)]
 #[builder(setter(into))]
 pub struct EntityProperty {
     id: Identifier,
+    #[builder(default = "false")]
     client_sync: bool,
     property: EntityPropertyType
 }
 
+/// An enum for all Entity property types.
 #[derive(Clone, Debug)]
 pub enum EntityPropertyType {
     Enum(EntityEnumProperty),
     Int(EntityIntProperty),
-    Float(EntityFloatProperty)
+    Float(EntityFloatProperty),
+    Bool(EntityBoolProperty)
 }
 
 #[derive(Clone, Debug, Builder)]
 #[builder(setter(into))]
 pub struct EntityFloatProperty {
-    range: Range<f32>,
+    range: RangeInclusive<f32>,
     default: f32
 }
 
 #[derive(Clone, Debug, Builder)]
 #[builder(setter(into))]
 pub struct EntityIntProperty {
-    range: Range<i32>,
+    range: RangeInclusive<i32>,
     default: i32
 }
 
@@ -45,6 +50,41 @@ pub struct EntityEnumProperty {
     values: Vec<String>
 }
 
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct EntityBoolProperty {
+    default: bool
+}
+
+impl EntityProperty {
+    /// Ranges have to be ordered and contain the default, and enums have to declare their default.
+    pub(crate) fn validate(&self, subject: &Identifier, diagnostics: &mut Diagnostics) {
+        let problem = match &self.property {
+            EntityPropertyType::Int(i) => check_range(&i.range, &i.default),
+            EntityPropertyType::Float(f) => check_range(&f.range, &f.default),
+            EntityPropertyType::Enum(e) if e.values.is_empty() => Some("declares no values".to_string()),
+            EntityPropertyType::Enum(e) if !e.values.contains(&e.default) => {
+                Some(format!("has a default `{}` which isn't one of its values {:?}", e.default, e.values))
+            }
+            _ => None
+        };
+
+        if let Some(problem) = problem {
+            diagnostics.error(subject, format!("The property `{}` {}", self.id, problem));
+        }
+    }
+}
+
+fn check_range<T: PartialOrd + Debug>(range: &RangeInclusive<T>, default: &T) -> Option<String> {
+    if range.start() > range.end() {
+        Some(format!("has an empty range {:?}", range))
+    } else if !range.contains(default) {
+        Some(format!("has a default {:?} outside of its range {:?}", default, range))
+    } else {
+        None
+    }
+}
+
 impl From<EntityIntProperty> for EntityPropertyType {
     fn from(value: EntityIntProperty) -> Self {
         EntityPropertyType::Int(value)
@@ -61,4 +101,57 @@ impl From<EntityEnumProperty> for EntityPropertyType {
     fn from(value: EntityEnumProperty) -> Self {
         EntityPropertyType::Enum(value)
     }
-}
\ No newline at end of file
+}
+
+impl From<EntityBoolProperty> for EntityPropertyType {
+    fn from(value: EntityBoolProperty) -> Self {
+        EntityPropertyType::Bool(value)
+    }
+}
+
+impl EntityPropertyType {
+    pub fn render_type(&self) -> String {
+        match &self {
+            EntityPropertyType::Enum(_) => "enum",
+            EntityPropertyType::Int(_) => "int",
+            EntityPropertyType::Float(_) => "float",
+            EntityPropertyType::Bool(_) => "bool"
+        }.to_string()
+    }
+}
+
+impl BedrockSerializable for EntityPropertyType {
+    /// Only the type-specific keys, [EntityProperty] adds `type` and `client_sync`.
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            EntityPropertyType::Enum(e) => json!({
+                "values": e.values,
+                "default": e.default
+            }),
+            EntityPropertyType::Int(i) => json!({
+                "range": [i.range.start(), i.range.end()],
+                "default": i.default
+            }),
+            EntityPropertyType::Float(f) => json!({
+                "range": [f.range.start(), f.range.end()],
+                "default": f.default
+            }),
+            EntityPropertyType::Bool(b) => json!({
+                "default": b.default
+            })
+        })
+    }
+}
+
+impl BedrockSerializable for EntityProperty {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut property = self.property.bedrock_json()?;
+
+        if let Value::Object(property) = &mut property {
+            property.insert("type".to_string(), Value::from(self.property.render_type()));
+            property.insert("client_sync".to_string(), Value::from(self.client_sync));
+        }
+
+        Ok(json!({ self.id.render(): property }))
+    }
+}
@@ -1,14 +1,31 @@
 use log::info;
-use crate::core::core_registry::REGISTRY;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::entity::client::ClientEntity;
 use crate::entity::Entity;
-use crate::item::client::ItemTexture;
 
-pub struct EntityRegistry {}
+pub struct EntityRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
 
 impl EntityRegistry {
     pub fn register_entity(&self, entity: Entity) {
         info!("@entity Registering entity {}", &entity.id);
 
-        REGISTRY.register_entity(entity);
+        self.registry.register_entity(entity);
     }
-}
\ No newline at end of file
+}
+
+
+/// The client entity registry. Used for registering the client side of Entities.
+pub struct ClientEntityRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl ClientEntityRegistry {
+    pub fn register_client_entity(&self, entity: ClientEntity) {
+        info!("@client:entity Registering client entity {}", &entity.id);
+
+        self.registry.register_client_entity(entity);
+    }
+}
@@ -0,0 +1,320 @@
+use std::ops::RangeInclusive;
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, Identifier, SemVer};
+use crate::core::validation::{Diagnostics, Registered};
+use crate::entity::filter::EntityFilter;
+use crate::hold_builders;
+
+hold_builders!(SpawnRule, SpawnCondition, Herd);
+
+/// The natural spawning of an [Entity](crate::entity::Entity), written to `BP/spawn_rules/` under the identifier of the entity it's attached to.<br>
+/// The entity spawns when any of the conditions holds.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SpawnRule {
+    #[builder(default = "SemVer::new(1, 8, 0)")]
+    pub format_version: SemVer,
+    pub population_control: PopulationControl,
+    #[builder(default = "Vec::new()")]
+    pub conditions: Vec<SpawnCondition>
+}
+
+/// A single spawn condition. Unset filters don't restrict spawning.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SpawnCondition {
+    #[builder(default = "SpawnSurface::Surface")]
+    pub surface: SpawnSurface,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub biome_filter: Option<EntityFilter>,
+    /// The light level range, from 0 to 15.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub brightness_filter: Option<RangeInclusive<u8>>,
+    #[builder(default = "false")]
+    pub adjust_for_weather: bool,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub height_filter: Option<RangeInclusive<i32>>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub density_limit: Option<DensityLimit>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub herd: Option<Herd>,
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub weight: Option<u32>,
+    /// Blocks the entity may spawn on, e.g. `minecraft:grass_block`.
+    #[builder(setter(into = false), default = "Vec::new()")]
+    pub spawns_on_block_filter: Vec<Identifier>
+}
+
+/// A group of entities spawned together.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Herd {
+    #[builder(setter(into = false))]
+    pub min_size: u32,
+    #[builder(setter(into = false))]
+    pub max_size: u32,
+    /// An event run on the entities spawned after the first `event_skip_count` ones.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub event: Option<String>,
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub event_skip_count: Option<u32>
+}
+
+/// How many entities of the population may exist around a player.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityLimit {
+    pub surface: Option<u32>,
+    pub underground: Option<u32>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnSurface {
+    Surface,
+    Underground,
+    Underwater
+}
+
+/// The population pool the spawn rule counts against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PopulationControl {
+    Animal,
+    Monster,
+    Pillager,
+    WaterAnimal,
+    Cat,
+    Villager
+}
+
+/// The `spawn_category` of an entity description.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnCategory {
+    Ambient,
+    Axolotls,
+    Creature,
+    Monster,
+    UndergroundWaterCreature,
+    WaterAmbient
+}
+
+impl DensityLimit {
+    pub fn new(surface: u32, underground: u32) -> Self {
+        Self { surface: Some(surface), underground: Some(underground) }
+    }
+
+    pub fn surface(limit: u32) -> Self {
+        Self { surface: Some(limit), underground: None }
+    }
+
+    pub fn underground(limit: u32) -> Self {
+        Self { surface: None, underground: Some(limit) }
+    }
+}
+
+impl SpawnRule {
+    /// The spawn rule file of the entity identified by `id`. The identifier comes from the entity, so this isn't [BedrockSerializable].
+    pub fn bedrock_json(&self, id: &Identifier) -> WoahResult<Value> {
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:spawn_rules": {
+                "description": {
+                    "identifier": id.render(),
+                    "population_control": self.population_control.render()
+                },
+                "conditions": self.conditions.serialize_vec()?
+            }
+        }))
+    }
+
+    /// Ranges have to be ordered, and light levels only go up to 15.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, subject: &Identifier, registered: &Registered) {
+        if self.conditions.is_empty() {
+            diagnostics.warning(subject, "The spawn rule has no conditions, so the entity never spawns");
+        }
+
+        for condition in &self.conditions {
+            if let Some(brightness) = &condition.brightness_filter && (brightness.start() > brightness.end() || *brightness.end() > 15) {
+                diagnostics.error(subject, format!("The brightness filter {:?} isn't within 0..=15", brightness));
+            }
+            if let Some(height) = &condition.height_filter && height.start() > height.end() {
+                diagnostics.error(subject, format!("The height filter {:?} is empty", height));
+            }
+            if let Some(herd) = &condition.herd && herd.min_size > herd.max_size {
+                diagnostics.error(subject, format!("The herd size {}..={} is empty", herd.min_size, herd.max_size));
+            }
+
+            for block in &condition.spawns_on_block_filter {
+                if !registered.is_known_block(block) {
+                    diagnostics.error(subject, format!("The spawn rule references an unknown block `{}`", block));
+                }
+            }
+        }
+    }
+}
+
+impl SpawnSurface {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            SpawnSurface::Surface => "minecraft:spawns_on_surface",
+            SpawnSurface::Underground => "minecraft:spawns_underground",
+            SpawnSurface::Underwater => "minecraft:spawns_underwater"
+        }
+    }
+}
+
+impl PopulationControl {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            PopulationControl::Animal => "animal",
+            PopulationControl::Monster => "monster",
+            PopulationControl::Pillager => "pillager",
+            PopulationControl::WaterAnimal => "water_animal",
+            PopulationControl::Cat => "cat",
+            PopulationControl::Villager => "villager"
+        }
+    }
+}
+
+impl SpawnCategory {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            SpawnCategory::Ambient => "ambient",
+            SpawnCategory::Axolotls => "axolotls",
+            SpawnCategory::Creature => "creature",
+            SpawnCategory::Monster => "monster",
+            SpawnCategory::UndergroundWaterCreature => "underground_water_creature",
+            SpawnCategory::WaterAmbient => "water_ambient"
+        }
+    }
+}
+
+impl BedrockSerializable for Herd {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut herd = Map::new();
+        herd.insert("min_size".to_string(), json!(self.min_size));
+        herd.insert("max_size".to_string(), json!(self.max_size));
+
+        if let Some(event) = &self.event {
+            herd.insert("event".to_string(), Value::from(event.clone()));
+        }
+        if let Some(skip) = self.event_skip_count {
+            herd.insert("event_skip_count".to_string(), json!(skip));
+        }
+
+        Ok(Value::Object(herd))
+    }
+}
+
+impl BedrockSerializable for DensityLimit {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut limit = Map::new();
+
+        if let Some(surface) = self.surface {
+            limit.insert("surface".to_string(), json!(surface));
+        }
+        if let Some(underground) = self.underground {
+            limit.insert("underground".to_string(), json!(underground));
+        }
+
+        Ok(Value::Object(limit))
+    }
+}
+
+impl BedrockSerializable for SpawnCondition {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut condition = Map::new();
+        condition.insert(self.surface.render().to_string(), json!({}));
+
+        if let Some(filter) = &self.biome_filter {
+            condition.insert("minecraft:biome_filter".to_string(), filter.bedrock_json()?);
+        }
+        if let Some(brightness) = &self.brightness_filter {
+            condition.insert("minecraft:brightness_filter".to_string(), json!({
+                "min": brightness.start(),
+                "max": brightness.end(),
+                "adjust_for_weather": self.adjust_for_weather
+            }));
+        }
+        if let Some(height) = &self.height_filter {
+            condition.insert("minecraft:height_filter".to_string(), json!({ "min": height.start(), "max": height.end() }));
+        }
+        if let Some(density) = &self.density_limit {
+            condition.insert("minecraft:density_limit".to_string(), density.bedrock_json()?);
+        }
+        if let Some(herd) = &self.herd {
+            condition.insert("minecraft:herd".to_string(), herd.bedrock_json()?);
+        }
+        if let Some(weight) = self.weight {
+            condition.insert("minecraft:weight".to_string(), json!({ "default": weight }));
+        }
+        if !self.spawns_on_block_filter.is_empty() {
+            condition.insert("minecraft:spawns_on_block_filter".to_string(), Value::from(
+                self.spawns_on_block_filter.iter().map(|b| b.render()).collect::<Vec<String>>()
+            ));
+        }
+
+        Ok(Value::Object(condition))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::core::testing;
+    use crate::entity::Entity;
+    use crate::entity::filter::EntityFilterTest;
+    use crate::woah;
+
+    #[test]
+    fn spawn_rule() {
+        let memory = testing::build(|events| {
+            events.entity_registration.subscribe(|reg| {
+                reg.register_entity(woah! {
+                    @Entity {
+                        id = "cool:entity";
+                        components = sjson! {};
+                        spawn_rule = @SpawnRule {
+                            population_control = PopulationControl::Animal;
+                            conditions = vec![
+                                @SpawnCondition {
+                                    biome_filter = @EntityFilterTest {
+                                        test = "has_biome_tag";
+                                        value = "plains";
+                                    };
+                                    brightness_filter = 7..=15;
+                                    density_limit = DensityLimit::surface(5);
+                                    herd = @Herd {
+                                        min_size = 2;
+                                        max_size = 4;
+                                    };
+                                    weight = 8;
+                                    spawns_on_block_filter = map vec!["minecraft:grass_block"];
+                                }
+                            ];
+                        };
+                    }
+                })
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_BP/spawn_rules/cool_entity.json").unwrap(), json!({
+            "format_version": "1.8.0",
+            "minecraft:spawn_rules": {
+                "description": { "identifier": "cool:entity", "population_control": "animal" },
+                "conditions": [{
+                    "minecraft:spawns_on_surface": {},
+                    "minecraft:biome_filter": { "test": "has_biome_tag", "value": "plains" },
+                    "minecraft:brightness_filter": { "min": 7, "max": 15, "adjust_for_weather": false },
+                    "minecraft:density_limit": { "surface": 5 },
+                    "minecraft:herd": { "min_size": 2, "max_size": 4 },
+                    "minecraft:weight": { "default": 8 },
+                    "minecraft:spawns_on_block_filter": ["minecraft:grass_block"]
+                }]
+            }
+        }));
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::Value;
+use crate::core::error::{WoahError, WoahResult, WoahResultExt};
+use crate::core::sprite::Sprite;
+use crate::core::utilities::SemVer;
+use crate::geometry::{CubeFace, CubeUv, FaceUv, Geometry, GeometryBone, GeometryCube};
+
+/// A model imported from a Blockbench `.bbmodel` file, with the textures embedded in it.
+#[derive(Clone, Debug)]
+pub struct BlockbenchModel {
+    pub geometry: Geometry,
+    /// Embedded textures by their name in Blockbench, without the `.png` extension.
+    pub textures: Vec<(String, Sprite)>
+}
+
+/// The bone loose cubes end up in, named like Blockbench's own Bedrock export does.
+const ROOT_BONE: &str = "bb_main";
+
+impl BlockbenchModel {
+    pub fn read(path: impl Into<PathBuf>) -> WoahResult<Self> {
+        let path = path.into();
+        let content = fs::read_to_string(&path).at_path(&path)?;
+
+        Self::parse(&content).at_path(path)
+    }
+
+    /// Parses the contents of a `.bbmodel` file. Blockbench's coordinates are converted to Bedrock's, which mirror the X axis.
+    pub fn parse(content: &str) -> WoahResult<Self> {
+        let model = serde_json::from_str::<Value>(content)?;
+
+        let name = model.get("model_identifier").and_then(|v| v.as_str()).filter(|v| !v.is_empty())
+            .or_else(|| model.get("name").and_then(|v| v.as_str()))
+            .ok_or_else(|| WoahError::other("The Blockbench model has no identifier or name"))?;
+
+        let box_uv = model.pointer("/meta/box_uv").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let elements = model.get("elements").and_then(|v| v.as_array()).into_iter().flatten()
+            .filter(|e| e.get("type").and_then(|t| t.as_str()).unwrap_or("cube") == "cube")
+            .filter_map(|e| Some((e.get("uuid")?.as_str()?.to_string(), e)))
+            .collect::<HashMap<String, &Value>>();
+
+        let mut bones = Vec::new();
+        let mut root_cubes = Vec::new();
+
+        for node in model.get("outliner").and_then(|v| v.as_array()).into_iter().flatten() {
+            match node {
+                Value::String(uuid) => root_cubes.extend(elements.get(uuid).map(|e| convert_cube(e, box_uv))),
+                group => collect_bones(group, None, &elements, box_uv, &mut bones)
+            }
+        }
+
+        if !root_cubes.is_empty() {
+            bones.insert(0, GeometryBone {
+                name: ROOT_BONE.to_string(),
+                parent: None,
+                pivot: [0.0, 0.0, 0.0],
+                rotation: None,
+                mirror: None,
+                cubes: root_cubes
+            });
+        }
+
+        let resolution = |key: &str| model.pointer(&format!("/resolution/{}", key)).and_then(|v| v.as_u64()).unwrap_or(16) as u32;
+        let visible_box = model.get("visible_box").and_then(|v| v.as_array()).map(|v| v.iter().filter_map(|n| n.as_f64()).collect::<Vec<f64>>());
+
+        let geometry = Geometry {
+            format_version: SemVer::new(1, 12, 0),
+            id: format!("geometry.{}", name),
+            texture_width: resolution("width"),
+            texture_height: resolution("height"),
+            visible_bounds_width: visible_box.as_ref().and_then(|v| v.first().copied()).unwrap_or(1.0),
+            visible_bounds_height: visible_box.as_ref().and_then(|v| v.get(1).copied()).unwrap_or(1.0),
+            visible_bounds_offset: [0.0, visible_box.as_ref().and_then(|v| v.get(2).copied()).unwrap_or(0.5), 0.0],
+            bones
+        };
+
+        let mut textures = Vec::new();
+
+        for texture in model.get("textures").and_then(|v| v.as_array()).into_iter().flatten() {
+            let Some(source) = texture.get("source").and_then(|v| v.as_str()) else { continue };
+            let Some(data) = source.strip_prefix("data:image/png;base64,") else { continue };
+
+            let name = texture.get("name").and_then(|v| v.as_str()).unwrap_or("texture");
+            let bytes = STANDARD.decode(data)
+                .map_err(|err| WoahError::other(format!("The embedded texture `{}` isn't valid base64: {}", name, err)))?;
+
+            textures.push((name.trim_end_matches(".png").to_string(), Sprite::from_bytes(&bytes)?));
+        }
+
+        Ok(Self { geometry, textures })
+    }
+}
+
+fn vector<const N: usize>(value: Option<&Value>) -> Option<[f64; N]> {
+    let values = value?.as_array()?.iter().map(|v| v.as_f64()).collect::<Option<Vec<f64>>>()?;
+    values.try_into().ok()
+}
+
+/// Blockbench rotates around X and Y the other way around.
+fn rotation(value: Option<&Value>) -> Option<[f64; 3]> {
+    vector::<3>(value).filter(|r| r.iter().any(|v| *v != 0.0)).map(|[x, y, z]| [-x, -y, z])
+}
+
+fn pivot(value: Option<&Value>) -> Option<[f64; 3]> {
+    vector::<3>(value).map(|[x, y, z]| [-x, y, z])
+}
+
+fn collect_bones(group: &Value, parent: Option<&str>, elements: &HashMap<String, &Value>, box_uv: bool, bones: &mut Vec<GeometryBone>) {
+    let name = group.get("name").and_then(|v| v.as_str()).unwrap_or("bone").to_string();
+
+    let mut cubes = Vec::new();
+    let mut children = Vec::new();
+
+    for child in group.get("children").and_then(|v| v.as_array()).into_iter().flatten() {
+        match child {
+            Value::String(uuid) => cubes.extend(elements.get(uuid).map(|e| convert_cube(e, box_uv))),
+            child => children.push(child)
+        }
+    }
+
+    bones.push(GeometryBone {
+        name: name.clone(),
+        parent: parent.map(|p| p.to_string()),
+        pivot: pivot(group.get("origin")).unwrap_or([0.0, 0.0, 0.0]),
+        rotation: rotation(group.get("rotation")),
+        mirror: None,
+        cubes
+    });
+
+    for child in children {
+        collect_bones(child, Some(&name), elements, box_uv, bones);
+    }
+}
+
+fn convert_cube(element: &Value, box_uv: bool) -> GeometryCube {
+    let from = vector::<3>(element.get("from")).unwrap_or([0.0; 3]);
+    let to = vector::<3>(element.get("to")).unwrap_or([0.0; 3]);
+
+    let box_uv = element.get("box_uv").and_then(|v| v.as_bool()).unwrap_or(box_uv);
+    let uv = if box_uv {
+        CubeUv::Box(vector::<2>(element.get("uv_offset")).unwrap_or([0.0, 0.0]))
+    } else {
+        let mut faces = BTreeMap::new();
+
+        for face in CubeFace::ALL {
+            let Some(data) = element.pointer(&format!("/faces/{}", face.render())) else { continue };
+            if data.get("texture").is_none_or(|t| t.is_null()) {
+                continue;
+            }
+            let Some([u1, v1, u2, v2]) = vector::<4>(data.get("uv")) else { continue };
+
+            // Up and down faces are stored flipped in Bedrock geometry.
+            let uv = match face {
+                CubeFace::Up | CubeFace::Down => FaceUv { uv: [u2, v2], uv_size: [u1 - u2, v1 - v2] },
+                _ => FaceUv { uv: [u1, v1], uv_size: [u2 - u1, v2 - v1] }
+            };
+            faces.insert(face, uv);
+        }
+
+        CubeUv::PerFace(faces)
+    };
+
+    GeometryCube {
+        origin: [-to[0], from[1], from[2]],
+        size: [to[0] - from[0], to[1] - from[1], to[2] - from[2]],
+        uv,
+        inflate: element.get("inflate").and_then(|v| v.as_f64()).filter(|v| *v != 0.0),
+        pivot: rotation(element.get("rotation")).and(pivot(element.get("origin"))),
+        rotation: rotation(element.get("rotation")),
+        mirror: element.get("mirror_uv").and_then(|v| v.as_bool()).filter(|m| *m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::bedrock::BedrockSerializable;
+
+    #[test]
+    fn import() {
+        let model = BlockbenchModel::parse(r#"{
+            "meta": { "box_uv": false },
+            "model_identifier": "woah.crate",
+            "resolution": { "width": 32, "height": 32 },
+            "elements": [{
+                "uuid": "c1",
+                "type": "cube",
+                "from": [2, 0, 2],
+                "to": [6, 4, 6],
+                "faces": {
+                    "north": { "uv": [0, 0, 4, 4], "texture": 0 },
+                    "up": { "uv": [4, 4, 0, 0], "texture": 0 },
+                    "down": { "uv": [0, 0, 4, 4], "texture": null }
+                }
+            }],
+            "outliner": [{ "name": "lid", "origin": [4, 4, 4], "children": ["c1"] }],
+            "textures": []
+        }"#).unwrap();
+
+        assert_eq!(model.geometry.id, "geometry.woah.crate");
+        assert_eq!(model.geometry.texture_width, 32);
+        assert_eq!(model.geometry.bones[0].pivot, [-4.0, 4.0, 4.0]);
+        assert_eq!(model.geometry.bones[0].cubes[0].origin, [-6.0, 0.0, 2.0]);
+        assert_eq!(model.geometry.bones[0].cubes[0].bedrock_json().unwrap()["uv"], json!({
+            "north": { "uv": [0.0, 0.0], "uv_size": [4.0, 4.0] },
+            "up": { "uv": [0.0, 0.0], "uv_size": [4.0, 4.0] }
+        }));
+        assert!(model.textures.is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+pub mod bbmodel;
+pub mod registry;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use derive_builder::Builder;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, SemVer};
+use crate::core::validation::{check_unique_ids, Diagnostics};
+use crate::hold_builders;
+
+hold_builders!(Geometry, GeometryBone, GeometryCube);
+
+/// A struct for describing geometry models, written to `RP/models/<id>.geo.json`.<br>
+/// Reference it from `minecraft:geometry` components and client entities through its [GeometryHandle].
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Geometry {
+    #[builder(default = "SemVer::new(1, 12, 0)")]
+    pub format_version: SemVer,
+    /// Has to start with `geometry.`, e.g. `geometry.woah.cow`.
+    pub id: String,
+    #[builder(setter(into = false), default = "16")]
+    pub texture_width: u32,
+    #[builder(setter(into = false), default = "16")]
+    pub texture_height: u32,
+    #[builder(default = "1.0")]
+    pub visible_bounds_width: f64,
+    #[builder(default = "1.0")]
+    pub visible_bounds_height: f64,
+    #[builder(default = "[0.0, 0.5, 0.0]")]
+    pub visible_bounds_offset: [f64; 3],
+    #[builder(default = "Vec::new()")]
+    pub bones: Vec<GeometryBone>
+}
+
+/// The identifier of a geometry, e.g. `geometry.woah.cow`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GeometryHandle {
+    id: String
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct GeometryBone {
+    pub name: String,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub parent: Option<String>,
+    #[builder(default = "[0.0, 0.0, 0.0]")]
+    pub pivot: [f64; 3],
+    #[builder(setter(into, strip_option), default = "None")]
+    pub rotation: Option<[f64; 3]>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub mirror: Option<bool>,
+    #[builder(default = "Vec::new()")]
+    pub cubes: Vec<GeometryCube>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct GeometryCube {
+    pub origin: [f64; 3],
+    pub size: [f64; 3],
+    #[builder(default = "CubeUv::Box([0.0, 0.0])")]
+    pub uv: CubeUv,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub inflate: Option<f64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub pivot: Option<[f64; 3]>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub rotation: Option<[f64; 3]>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub mirror: Option<bool>
+}
+
+/// Either a box UV offset, or a UV rectangle per face.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CubeUv {
+    Box([f64; 2]),
+    PerFace(BTreeMap<CubeFace, FaceUv>)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CubeFace {
+    North,
+    East,
+    South,
+    West,
+    Up,
+    Down
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceUv {
+    pub uv: [f64; 2],
+    pub uv_size: [f64; 2]
+}
+
+impl Geometry {
+    pub fn handle(&self) -> GeometryHandle {
+        GeometryHandle { id: self.id.clone() }
+    }
+
+    /// The name of the model file, `geometry.woah.cow` is written to `models/woah.cow.geo.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.geo.json", self.id.strip_prefix("geometry.").unwrap_or(&self.id))
+    }
+}
+
+/// Geometry identifiers need the `geometry.` prefix, and bones may only be parented to bones of the same geometry.
+pub(crate) fn validate_geometries(diagnostics: &mut Diagnostics, geometries: &[Geometry]) {
+    check_unique_ids(diagnostics, "geometry", "geometry.", geometries.iter().map(|g| &g.id));
+
+    for geometry in geometries {
+        let mut bones = HashSet::new();
+        for bone in &geometry.bones {
+            if !bones.insert(&bone.name) {
+                diagnostics.error(&geometry.id, format!("The bone `{}` is defined more than once", bone.name));
+            }
+        }
+
+        for bone in &geometry.bones {
+            if let Some(parent) = &bone.parent && !bones.contains(parent) {
+                diagnostics.error(&geometry.id, format!("The bone `{}` has an undefined parent `{}`", bone.name, parent));
+            }
+        }
+    }
+}
+
+impl GeometryHandle {
+    /// A handle to a geometry which isn't generated by Woah, like `geometry.cow` or `minecraft:geometry.full_block`.
+    pub fn external(id: impl Into<String>) -> Self {
+        Self { id: id.into() }
+    }
+
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+}
+
+impl Display for GeometryHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl Serialize for GeometryHandle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+impl From<GeometryHandle> for String {
+    fn from(value: GeometryHandle) -> Self {
+        value.id
+    }
+}
+
+impl From<GeometryHandle> for Value {
+    fn from(value: GeometryHandle) -> Self {
+        Value::String(value.id)
+    }
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [CubeFace::North, CubeFace::East, CubeFace::South, CubeFace::West, CubeFace::Up, CubeFace::Down];
+
+    pub fn render(&self) -> &'static str {
+        match &self {
+            CubeFace::North => "north",
+            CubeFace::East => "east",
+            CubeFace::South => "south",
+            CubeFace::West => "west",
+            CubeFace::Up => "up",
+            CubeFace::Down => "down"
+        }
+    }
+}
+
+impl BedrockSerializable for CubeUv {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            CubeUv::Box(uv) => json!(uv),
+            CubeUv::PerFace(faces) => Value::Object(faces.iter().map(|(face, uv)| {
+                (face.render().to_string(), json!({ "uv": uv.uv, "uv_size": uv.uv_size }))
+            }).collect())
+        })
+    }
+}
+
+/// Inserts `value` under `key` when it's set, the optional fields of bones and cubes are left out otherwise.
+fn insert_some<T: Serialize>(object: &mut Map<String, Value>, key: &str, value: &Option<T>) {
+    if let Some(value) = value {
+        object.insert(key.to_string(), json!(value));
+    }
+}
+
+impl BedrockSerializable for GeometryCube {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut cube = Map::new();
+        cube.insert("origin".to_string(), json!(self.origin));
+        cube.insert("size".to_string(), json!(self.size));
+        cube.insert("uv".to_string(), self.uv.bedrock_json()?);
+        insert_some(&mut cube, "inflate", &self.inflate);
+        insert_some(&mut cube, "pivot", &self.pivot);
+        insert_some(&mut cube, "rotation", &self.rotation);
+        insert_some(&mut cube, "mirror", &self.mirror);
+
+        Ok(Value::Object(cube))
+    }
+}
+
+impl BedrockSerializable for GeometryBone {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut bone = Map::new();
+        bone.insert("name".to_string(), Value::from(self.name.clone()));
+        insert_some(&mut bone, "parent", &self.parent);
+        bone.insert("pivot".to_string(), json!(self.pivot));
+        insert_some(&mut bone, "rotation", &self.rotation);
+        insert_some(&mut bone, "mirror", &self.mirror);
+
+        if !self.cubes.is_empty() {
+            bone.insert("cubes".to_string(), Value::Array(self.cubes.serialize_vec()?));
+        }
+
+        Ok(Value::Object(bone))
+    }
+}
+
+impl BedrockSerializable for Geometry {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:geometry": [
+                {
+                    "description": {
+                        "identifier": self.id,
+                        "texture_width": self.texture_width,
+                        "texture_height": self.texture_height,
+                        "visible_bounds_width": self.visible_bounds_width,
+                        "visible_bounds_height": self.visible_bounds_height,
+                        "visible_bounds_offset": self.visible_bounds_offset
+                    },
+                    "bones": self.bones.serialize_vec()?
+                }
+            ]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn entity_geometry() {
+        let memory = testing::build(|events| {
+            events.geometry_registration.subscribe(|reg| {
+                reg.register_geometry(woah! {
+                    @Geometry {
+                        id = "geometry.cool.entity";
+                        texture_width = 64;
+                        texture_height = 32;
+                        bones = vec![
+                            @GeometryBone {
+                                name = "body";
+                                pivot = [0.0, 12.0, 0.0];
+                                cubes = vec![
+                                    @GeometryCube {
+                                        origin = [-4.0, 6.0, -4.0];
+                                        size = [8.0, 12.0, 8.0];
+                                        uv = CubeUv::Box([0.0, 16.0]);
+                                    }
+                                ];
+                            },
+                            @GeometryBone {
+                                name = "head";
+                                parent = "body";
+                                pivot = [0.0, 18.0, 0.0];
+                            }
+                        ];
+                    }
+                });
+            });
+        });
+
+        let geometry = memory.read_json("WoahTest_RP/models/cool.entity.geo.json").unwrap();
+        assert_eq!(geometry["minecraft:geometry"][0]["description"]["identifier"], "geometry.cool.entity");
+        assert_eq!(geometry["minecraft:geometry"][0]["description"]["texture_width"], 64);
+        assert_eq!(geometry["minecraft:geometry"][0]["bones"], json!([
+            {
+                "name": "body",
+                "pivot": [0.0, 12.0, 0.0],
+                "cubes": [{ "origin": [-4.0, 6.0, -4.0], "size": [8.0, 12.0, 8.0], "uv": [0.0, 16.0] }]
+            },
+            { "name": "head", "parent": "body", "pivot": [0.0, 18.0, 0.0] }
+        ]));
+    }
+}
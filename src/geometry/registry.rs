@@ -0,0 +1,21 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::geometry::{Geometry, GeometryHandle};
+
+/// The geometry registry. Used for registering Geometry models.
+pub struct GeometryRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl GeometryRegistry {
+    /// Registers a geometry, returning the handle to reference it by from `minecraft:geometry` components and client entities.
+    pub fn register_geometry(&self, geometry: Geometry) -> GeometryHandle {
+        info!("@geometry Registering geometry {}", &geometry.id);
+
+        let handle = geometry.handle();
+        self.registry.register_geometry(geometry);
+
+        handle
+    }
+}
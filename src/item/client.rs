@@ -1,8 +1,6 @@
-use crate::code_gen::TEMPLATES;
-use crate::core::core_registry::REGISTRY;
-use crate::bedrock::BedrockSerializable;
 use crate::core::sprite::Sprite;
 use crate::core::utilities::Identifier;
+use serde_json::{json, Value};
 
 /// A struct for describing Item textures.
 #[derive(Clone, Debug)]
@@ -15,17 +13,11 @@ impl ItemTexture {
     pub fn new(id: Identifier, sprite: Sprite) -> Self {
         Self { id, sprite }
     }
-}
 
-impl BedrockSerializable for ItemTexture {
-    fn bedrock_serialize(&self) -> String {
-        let mut c = tera::Context::new();
-        
-        let texture_path = format!("textures/items/{}/{}.png", &REGISTRY.pack_metadata.read().unwrap().name, &self.id.render_underscore());
-        
-        c.insert("texture_path", &texture_path);
-        c.insert("id", &self.id.render());
-        
-        TEMPLATES.render("generic/atlas.json", &c).unwrap()
+    /// The `texture_data` entry of this texture in the atlas of the pack named `pack_name`.
+    pub fn atlas_entry(&self, pack_name: &str) -> (String, Value) {
+        (self.id.render(), json!({
+            "textures": format!("textures/items/{}/{}.png", pack_name, &self.id.render_underscore())
+        }))
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,3 @@
-use eo::sjson::HasSJsonIdent;
-
 pub trait Component {
     const ID: &'static str;
 }
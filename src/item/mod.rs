@@ -1,8 +1,9 @@
-use crate::code_gen::TEMPLATES;
-use crate::core::utilities::{Identifier, SemVer};
+use crate::core::utilities::{ComponentMap, Identifier, SemVer};
 use crate::bedrock::BedrockSerializable;
-use eo::sjson::{SJsonElement, SJsonMacro, SJsonValue, TransformHashMap};
-use std::collections::HashMap;
+use crate::core::component::Components;
+use crate::core::error::WoahResult;
+use crate::core::validation::{check_components_version, Diagnostics, Registered};
+use serde_json::{json, Value};
 use derive_builder::Builder;
 use crate::hold_builders;
 
@@ -12,28 +13,129 @@ pub mod components;
 
 hold_builders!(Item);
 
-/// A struct for describing Items. Use [eo::sjson!] for components.
+/// A struct for describing Items. Use the generated [components](crate::item::components) or [eo::sjson!] for components.
 #[derive(Debug, Clone, Builder)]
 #[builder(setter(into))]
 pub struct Item {
     pub id: Identifier,
     #[builder(default = "SemVer::latest()")]
     pub format_version: SemVer,
-    pub components: SJsonMacro
+    #[builder(setter(custom))]
+    pub components: ComponentMap
 }
 
+impl ItemBuilder {
+    /// Typed components, e.g. `components = v1_26_10::components((...));`, also set the format version of their module,
+    /// unless it's set explicitly. The validation reports an explicit format version other than the one of the components.
+    pub fn components(&mut self, components: impl Into<Components>) -> &mut Self {
+        let components = components.into();
 
+        if self.format_version.is_none() {
+            self.format_version = components.format_version;
+        }
+        self.components = Some(components.components);
+        self
+    }
+}
+
+impl Item {
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        check_components_version(diagnostics, &self.id, "item", &self.components, &self.format_version);
+
+        for texture in icon_textures(self.components.get("minecraft:icon")) {
+            if !registered.is_known_item_texture(&texture) {
+                diagnostics.error(&self.id, format!("`minecraft:icon` references an unregistered item texture `{}`", texture));
+            }
+        }
+    }
+}
 
 impl BedrockSerializable for Item {
-    fn bedrock_serialize(&self) -> String {
-        let components_serialized = self.components.serialize();
-
-        let mut c = tera::Context::new();
-        
-        c.insert("format_version", &self.format_version.render_dotted());
-        c.insert("components", &components_serialized);
-        c.insert("id", &self.id.render());
-        
-        TEMPLATES.render("items/item.json", &c).unwrap()
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:item": {
+                "description": {
+                    "identifier": self.id.render()
+                },
+                "components": self.components.to_value()?
+            }
+        }))
+    }
+}
+
+/// `minecraft:icon` is either a texture name, `{ "texture": ... }` or `{ "textures": { "default": ... } }`.
+fn icon_textures(icon: Option<&Value>) -> Vec<String> {
+    match icon {
+        Some(Value::String(texture)) => vec![texture.clone()],
+        Some(Value::Object(icon)) => {
+            let mut textures = icon.get("texture").and_then(|t| t.as_str()).map(|t| t.to_string()).into_iter().collect::<Vec<String>>();
+            if let Some(Value::Object(all)) = icon.get("textures") {
+                textures.extend(all.values().filter_map(|t| t.as_str().map(|t| t.to_string())));
+            }
+            textures
+        }
+        _ => Vec::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, ToSJson, TransformHashMap};
+    use crate::core::sprite::Sprite;
+    use crate::core::testing;
+    use crate::item::client::ItemTexture;
+    use crate::item::components::v1_26_10::*;
+    use crate::woah;
+
+    #[test]
+    fn item_and_icon() {
+        let memory = testing::build(|events| {
+            events.item_registration.subscribe(|reg| {
+                reg.register_item(woah! {
+                    @Item {
+                        id = "woah:ruby";
+                        components = sjson! {
+                            minecraft:display_name = "Ruby",
+                            minecraft:icon = "woah:ruby",
+                        };
+                    }
+                })
+            });
+            events.client_item_registration.subscribe(|reg| {
+                reg.register_texture(ItemTexture::new(
+                    Identifier::new("woah", "ruby"),
+                    Sprite::read("./textures/item.png").unwrap()
+                ))
+            });
+        });
+
+        let item = memory.read_json("WoahTest_BP/items/woah_ruby.json").unwrap();
+        assert_eq!(item["minecraft:item"]["components"]["minecraft:icon"], "woah:ruby");
+
+        let icon = memory.read("WoahTest_RP/textures/items/WoahTest/woah_ruby.png").unwrap();
+        assert!(icon.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn typed_components() {
+        let item = woah! {
+            @Item {
+                id = "woah:typed_item";
+                components = components((
+                    @DisplayName { value = Some("Typed".to_string()); },
+                    Icon::String("woah:item_icon_1".to_string()),
+                    MaxStackSize::Integer(16)
+                ));
+            }
+        };
+        assert_eq!(item.format_version.render_dotted(), "1.26.10");
+        assert_eq!(item.bedrock_json().unwrap()["minecraft:item"]["components"], json!({
+            "minecraft:display_name": { "value": "Typed" },
+            "minecraft:icon": "woah:item_icon_1",
+            "minecraft:max_stack_size": 16
+        }));
+    }
+}
@@ -1,27 +1,32 @@
 use log::info;
-use crate::core::core_registry::REGISTRY;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
 use crate::item::client::ItemTexture;
 use crate::item::Item;
 
 /// The item registry. Used for registering Items.
-pub struct ItemRegistry {}
+pub struct ItemRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
 
 impl ItemRegistry {
     pub fn register_item(&self, item: Item) {
         info!("@item Registering item {}", &item.id.render());
 
-        REGISTRY.register_item(item);
+        self.registry.register_item(item);
     }
 }
 
 
 /// The item registry. Used for registering Item textures.
-pub struct ClientItemRegistry {}
+pub struct ClientItemRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
 
 impl ClientItemRegistry {
     pub fn register_texture(&self, texture: ItemTexture) {
         info!("@client:item Registering item texture {}", &texture.id);
         
-        REGISTRY.register_item_texture(texture);
+        self.registry.register_item_texture(texture);
     }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+pub mod registry;
+
+use std::collections::{BTreeMap, BTreeSet};
+use crate::core::utilities::Identifier;
+use crate::core::validation::Diagnostics;
+
+/// The locales the game ships with. Lang files for other locales are still written, but they are reported by the validation phase.
+pub const LANGUAGES: &[&str] = &[
+    "bg_BG", "cs_CZ", "da_DK", "de_DE", "el_GR", "en_GB", "en_US", "es_ES", "es_MX", "fi_FI",
+    "fr_CA", "fr_FR", "hu_HU", "id_ID", "it_IT", "ja_JP", "ko_KR", "nb_NO", "nl_NL", "pl_PL",
+    "pt_BR", "pt_PT", "ru_RU", "sk_SK", "sv_SE", "tr_TR", "uk_UA", "zh_CN", "zh_TW"
+];
+
+/// The translations of a pack, keyed by locale (`en_US`) and then by translation key.<br>
+/// Both levels are sorted, so the generated lang files are stable across runs.
+#[derive(Clone, Debug, Default)]
+pub struct Translations {
+    locales: BTreeMap<String, BTreeMap<String, String>>
+}
+
+impl Translations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, locale: impl Into<String>, key: impl Into<String>, value: impl Into<String>) {
+        self.locales.entry(locale.into()).or_default().insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, locale: &str, key: &str) -> Option<&String> {
+        self.locales.get(locale).and_then(|entries| entries.get(key))
+    }
+
+    pub fn locales(&self) -> impl Iterator<Item = &String> {
+        self.locales.keys()
+    }
+
+    pub fn entries(&self, locale: &str) -> impl Iterator<Item = (&String, &String)> {
+        self.locales.get(locale).into_iter().flatten()
+    }
+
+    /// Every key translated in at least one locale.
+    pub fn keys(&self) -> BTreeSet<&String> {
+        self.locales.values().flat_map(|entries| entries.keys()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locales.is_empty()
+    }
+
+    /// Renders the `.lang` file of a locale, one `key=value` line per translation.
+    pub fn render(&self, locale: &str) -> String {
+        self.entries(locale)
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect()
+    }
+
+    /// Checks that every key and value fits on a single `key=value` line, and that every locale translates the same keys.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics) {
+        let keys = self.keys();
+
+        for locale in self.locales() {
+            if !LANGUAGES.contains(&locale.as_str()) {
+                diagnostics.warning(locale, "The locale isn't one of the languages known to the game");
+            }
+
+            for (key, value) in self.entries(locale) {
+                if key.is_empty() || key.contains(|c: char| c == '=' || c.is_whitespace()) {
+                    diagnostics.error(locale, format!("The translation key `{}` can't be empty or contain `=` or whitespace", key));
+                }
+                if value.contains(['\n', '\r']) {
+                    diagnostics.error(locale, format!("The translation of `{}` can't span multiple lines", key));
+                }
+            }
+
+            for key in &keys {
+                if self.get(locale, key).is_none() {
+                    diagnostics.warning(locale, format!("The translation key `{}` is missing", key));
+                }
+            }
+        }
+    }
+
+    /// Warns about content whose default name, e.g. `item.woah:ruby.name`, isn't translated in every locale.
+    pub(crate) fn validate_names<'a>(&self, diagnostics: &mut Diagnostics, names: impl Iterator<Item = (&'a Identifier, String)>) {
+        for (id, key) in names {
+            for locale in self.locales() {
+                if self.get(locale, &key).is_none() {
+                    diagnostics.warning(id, format!("No `{}` translation in locale `{}`", key, locale));
+                }
+            }
+        }
+    }
+}
+
+/// The translation key of an item's name, `item.<id>.name`.
+pub fn item_key(id: &Identifier) -> String {
+    format!("item.{}.name", id.render())
+}
+
+/// The translation key of a block's name, `tile.<id>.name`.
+pub fn block_key(id: &Identifier) -> String {
+    format!("tile.{}.name", id.render())
+}
+
+/// The translation key of an entity's name, `entity.<id>.name`.
+pub fn entity_key(id: &Identifier) -> String {
+    format!("entity.{}.name", id.render())
+}
+
+/// The translation key of an entity's spawn egg, `item.spawn_egg.entity.<id>.name`.
+pub fn spawn_egg_key(id: &Identifier) -> String {
+    format!("item.spawn_egg.entity.{}.name", id.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use serde_json::json;
+    use crate::core::testing;
+    use crate::core::utilities::HoldBuilder;
+    use crate::entity::Entity;
+    use crate::woah;
+
+    #[test]
+    fn lang_files() {
+        let memory = testing::build(|events| {
+            events.entity_registration.subscribe(|reg| {
+                reg.register_entity(woah! {
+                    @Entity {
+                        id = "cool:entity";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.lang_registration.subscribe(|reg| {
+                reg.entity_name("en_US", "cool:entity", "Cool Entity");
+                reg.entity_name("de_DE", "cool:entity", "Coole Entität");
+                reg.spawn_egg_name("en_US", "cool:entity", "Spawn Cool Entity");
+                reg.spawn_egg_name("de_DE", "cool:entity", "Coole Entität erzeugen");
+                reg.translate("en_US", "action.hint.woah", "Woah!");
+                reg.translate("de_DE", "action.hint.woah", "Wow!");
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_RP/texts/languages.json").unwrap(), json!(["de_DE", "en_US"]));
+        assert_eq!(memory.read_string("WoahTest_BP/texts/en_US.lang").unwrap(), "action.hint.woah=Woah!\nentity.cool:entity.name=Cool Entity\nitem.spawn_egg.entity.cool:entity.name=Spawn Cool Entity\n");
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(block_key(&Identifier::from("woah:block")), "tile.woah:block.name");
+    }
+}
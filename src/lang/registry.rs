@@ -0,0 +1,40 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::core::utilities::Identifier;
+use crate::lang::{block_key, entity_key, item_key, spawn_egg_key};
+
+/// The translation registry. Used for registering the texts of every locale, which end up in `texts/<locale>.lang` of both packs.
+pub struct LangRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl LangRegistry {
+    /// Registers a translation of a raw key, like `action.hint.woah` in `en_US`.
+    pub fn translate(&self, locale: impl Into<String>, key: impl Into<String>, value: impl Into<String>) {
+        let (locale, key) = (locale.into(), key.into());
+        info!("@lang Registering translation {} for {}", &key, &locale);
+
+        self.registry.register_translation(locale, key, value.into());
+    }
+
+    /// Registers the name of an item, under `item.<id>.name`.
+    pub fn item_name(&self, locale: impl Into<String>, id: impl Into<Identifier>, name: impl Into<String>) {
+        self.translate(locale, item_key(&id.into()), name);
+    }
+
+    /// Registers the name of a block, under `tile.<id>.name`.
+    pub fn block_name(&self, locale: impl Into<String>, id: impl Into<Identifier>, name: impl Into<String>) {
+        self.translate(locale, block_key(&id.into()), name);
+    }
+
+    /// Registers the name of an entity, under `entity.<id>.name`.
+    pub fn entity_name(&self, locale: impl Into<String>, id: impl Into<Identifier>, name: impl Into<String>) {
+        self.translate(locale, entity_key(&id.into()), name);
+    }
+
+    /// Registers the name of an entity's spawn egg, under `item.spawn_egg.entity.<id>.name`.
+    pub fn spawn_egg_name(&self, locale: impl Into<String>, id: impl Into<Identifier>, name: impl Into<String>) {
+        self.translate(locale, spawn_egg_key(&id.into()), name);
+    }
+}
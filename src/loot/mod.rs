@@ -0,0 +1,491 @@
+pub mod registry;
+
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use derive_builder::Builder;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{BedrockSerializeVec, Identifier};
+use crate::core::validation::{Diagnostics, Registered};
+use crate::hold_builders;
+use crate::item::Item;
+
+hold_builders!(LootTable, LootPool, LootEntry);
+
+/// A struct for describing Loot tables, written to `BP/loot_tables/<namespace>/<path>.json`.<br>
+/// Reference it from `minecraft:loot` components through its [LootTableHandle], instead of repeating the path.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct LootTable {
+    pub id: Identifier,
+    #[builder(default = "Vec::new()")]
+    pub pools: Vec<LootPool>
+}
+
+/// The path of a loot table relative to the BP, e.g. `loot_tables/woah/blocks/ore.json`.<br>
+/// Handles of registered tables are derived from their identifiers, so renaming a table renames every reference to it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LootTableHandle {
+    path: String
+}
+
+/// A pool of entries, rolled a number of times.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct LootPool {
+    #[builder(default = "LootRange::Constant(1.0)")]
+    rolls: LootRange,
+    #[builder(default = "Vec::new()")]
+    conditions: Vec<LootCondition>,
+    #[builder(default = "Vec::new()")]
+    entries: Vec<LootEntry>
+}
+
+/// A single entry of a [LootPool], picked by its weight.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct LootEntry {
+    entry: LootEntryType,
+    #[builder(setter(into, strip_option), default = "None")]
+    weight: Option<i32>,
+    #[builder(setter(into, strip_option), default = "None")]
+    quality: Option<i32>,
+    #[builder(default = "Vec::new()")]
+    conditions: Vec<LootCondition>,
+    #[builder(default = "Vec::new()")]
+    functions: Vec<LootFunction>
+}
+
+/// What a [LootEntry] drops.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LootEntryType {
+    Item(Identifier),
+    LootTable(LootTableHandle),
+    Empty
+}
+
+/// A number or a `{ "min", "max" }` range, used by rolls and most functions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LootRange {
+    Constant(f64),
+    Uniform(f64, f64)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LootCondition {
+    KilledByPlayer,
+    KilledByPlayerOrPets,
+    RandomChance {
+        chance: f64
+    },
+    RandomChanceWithLooting {
+        chance: f64,
+        looting_multiplier: f64
+    },
+    RandomRegionalDifficultyChance {
+        max_chance: f64
+    },
+    HasMarkVariant {
+        value: i32
+    },
+    EntityOnFire(bool),
+    MatchTool {
+        item: Identifier
+    },
+    /// Any other condition, `params` are merged into the condition object.
+    Other {
+        condition: String,
+        params: Map<String, Value>
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LootFunction {
+    SetCount {
+        count: LootRange
+    },
+    SetDamage {
+        damage: LootRange
+    },
+    SetData {
+        data: i32
+    },
+    SetName {
+        name: String
+    },
+    SetLore {
+        lore: Vec<String>
+    },
+    LootingEnchant {
+        count: LootRange
+    },
+    EnchantRandomly {
+        treasure: bool
+    },
+    EnchantWithLevels {
+        levels: LootRange,
+        treasure: bool
+    },
+    SpecificEnchants {
+        enchants: Vec<(String, i32)>
+    },
+    FurnaceSmelt,
+    /// Any other function, `params` are merged into the function object.
+    Other {
+        function: String,
+        params: Map<String, Value>
+    }
+}
+
+impl LootTable {
+    pub fn handle(&self) -> LootTableHandle {
+        LootTableHandle::new(&self.id)
+    }
+
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        for pool in &self.pools {
+            if pool.entries().is_empty() {
+                diagnostics.warning(&self.id, "A loot pool has no entries");
+            }
+
+            for entry in pool.entries() {
+                match entry.entry() {
+                    LootEntryType::Item(id) if !registered.is_known_item(id) => {
+                        diagnostics.error(&self.id, format!("The loot table references an unknown item `{}`", id));
+                    }
+                    LootEntryType::LootTable(handle) if !registered.is_known_loot_table(handle.path()) => {
+                        diagnostics.error(&self.id, format!("The loot table references an unregistered loot table `{}`", handle));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl LootTableHandle {
+    pub fn new(id: &Identifier) -> Self {
+        Self {
+            path: format!("loot_tables/{}/{}.json", id.namespace(), id.path())
+        }
+    }
+
+    /// A handle to a table which isn't generated by Woah, like `loot_tables/entities/zombie.json`.
+    pub fn external(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+}
+
+impl Display for LootTableHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl Serialize for LootTableHandle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        serializer.serialize_str(&self.path)
+    }
+}
+
+impl From<LootTableHandle> for String {
+    fn from(value: LootTableHandle) -> Self {
+        value.path
+    }
+}
+
+impl From<LootTableHandle> for Value {
+    fn from(value: LootTableHandle) -> Self {
+        Value::String(value.path)
+    }
+}
+
+impl LootPool {
+    pub fn entries(&self) -> &Vec<LootEntry> {
+        &self.entries
+    }
+}
+
+impl LootEntry {
+    pub fn entry(&self) -> &LootEntryType {
+        &self.entry
+    }
+}
+
+impl From<&str> for LootEntryType {
+    fn from(value: &str) -> Self {
+        LootEntryType::Item(value.into())
+    }
+}
+
+impl From<Identifier> for LootEntryType {
+    fn from(value: Identifier) -> Self {
+        LootEntryType::Item(value)
+    }
+}
+
+impl From<&Item> for LootEntryType {
+    fn from(value: &Item) -> Self {
+        LootEntryType::Item(value.id.clone())
+    }
+}
+
+impl From<LootTableHandle> for LootEntryType {
+    fn from(value: LootTableHandle) -> Self {
+        LootEntryType::LootTable(value)
+    }
+}
+
+impl From<i32> for LootRange {
+    fn from(value: i32) -> Self {
+        LootRange::Constant(value as f64)
+    }
+}
+
+impl From<f64> for LootRange {
+    fn from(value: f64) -> Self {
+        LootRange::Constant(value)
+    }
+}
+
+impl From<RangeInclusive<i32>> for LootRange {
+    fn from(value: RangeInclusive<i32>) -> Self {
+        LootRange::Uniform(*value.start() as f64, *value.end() as f64)
+    }
+}
+
+impl From<RangeInclusive<f64>> for LootRange {
+    fn from(value: RangeInclusive<f64>) -> Self {
+        LootRange::Uniform(*value.start(), *value.end())
+    }
+}
+
+/// Whole numbers are written without a fraction, so `1.0` renders as `1`.
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    }
+}
+
+impl BedrockSerializable for LootRange {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            LootRange::Constant(value) => number(*value),
+            LootRange::Uniform(min, max) => json!({ "min": number(*min), "max": number(*max) })
+        })
+    }
+}
+
+/// Builds a `{ "<kind>": <name>, ...params }` object, the shape of both conditions and functions.
+fn tagged(kind: &str, name: &str, params: Map<String, Value>) -> Value {
+    let mut object = Map::new();
+    object.insert(kind.to_string(), Value::from(name));
+    object.extend(params);
+    Value::Object(object)
+}
+
+fn params(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new()
+    }
+}
+
+impl BedrockSerializable for LootCondition {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            LootCondition::KilledByPlayer => tagged("condition", "killed_by_player", Map::new()),
+            LootCondition::KilledByPlayerOrPets => tagged("condition", "killed_by_player_or_pets", Map::new()),
+            LootCondition::RandomChance { chance } => tagged("condition", "random_chance", params(json!({
+                "chance": chance
+            }))),
+            LootCondition::RandomChanceWithLooting { chance, looting_multiplier } => tagged("condition", "random_chance_with_looting", params(json!({
+                "chance": chance,
+                "looting_multiplier": looting_multiplier
+            }))),
+            LootCondition::RandomRegionalDifficultyChance { max_chance } => tagged("condition", "random_regional_difficulty_chance", params(json!({
+                "max_chance": max_chance
+            }))),
+            LootCondition::HasMarkVariant { value } => tagged("condition", "has_mark_variant", params(json!({
+                "value": value
+            }))),
+            LootCondition::EntityOnFire(on_fire) => tagged("condition", "entity_properties", params(json!({
+                "entity": "this",
+                "properties": { "on_fire": on_fire }
+            }))),
+            LootCondition::MatchTool { item } => tagged("condition", "match_tool", params(json!({
+                "item": item.render()
+            }))),
+            LootCondition::Other { condition, params } => tagged("condition", condition, params.clone())
+        })
+    }
+}
+
+impl BedrockSerializable for LootFunction {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            LootFunction::SetCount { count } => tagged("function", "set_count", params(json!({
+                "count": count.bedrock_json()?
+            }))),
+            LootFunction::SetDamage { damage } => tagged("function", "set_damage", params(json!({
+                "damage": damage.bedrock_json()?
+            }))),
+            LootFunction::SetData { data } => tagged("function", "set_data", params(json!({
+                "data": data
+            }))),
+            LootFunction::SetName { name } => tagged("function", "set_name", params(json!({
+                "name": name
+            }))),
+            LootFunction::SetLore { lore } => tagged("function", "set_lore", params(json!({
+                "lore": lore
+            }))),
+            LootFunction::LootingEnchant { count } => tagged("function", "looting_enchant", params(json!({
+                "count": count.bedrock_json()?
+            }))),
+            LootFunction::EnchantRandomly { treasure } => tagged("function", "enchant_randomly", params(json!({
+                "treasure": treasure
+            }))),
+            LootFunction::EnchantWithLevels { levels, treasure } => tagged("function", "enchant_with_levels", params(json!({
+                "levels": levels.bedrock_json()?,
+                "treasure": treasure
+            }))),
+            LootFunction::SpecificEnchants { enchants } => tagged("function", "specific_enchants", params(json!({
+                "enchants": enchants.iter().map(|(id, level)| json!({ "id": id, "level": level })).collect::<Vec<Value>>()
+            }))),
+            LootFunction::FurnaceSmelt => tagged("function", "furnace_smelt", Map::new()),
+            LootFunction::Other { function, params } => tagged("function", function, params.clone())
+        })
+    }
+}
+
+impl BedrockSerializable for LootEntry {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut entry = Map::new();
+
+        match &self.entry {
+            LootEntryType::Item(id) => {
+                entry.insert("type".to_string(), Value::from("item"));
+                entry.insert("name".to_string(), Value::from(id.render()));
+            }
+            LootEntryType::LootTable(handle) => {
+                entry.insert("type".to_string(), Value::from("loot_table"));
+                entry.insert("name".to_string(), Value::from(handle.path.clone()));
+            }
+            LootEntryType::Empty => {
+                entry.insert("type".to_string(), Value::from("empty"));
+            }
+        }
+
+        if let Some(weight) = self.weight {
+            entry.insert("weight".to_string(), Value::from(weight));
+        }
+        if let Some(quality) = self.quality {
+            entry.insert("quality".to_string(), Value::from(quality));
+        }
+        if !self.conditions.is_empty() {
+            entry.insert("conditions".to_string(), Value::Array(self.conditions.serialize_vec()?));
+        }
+        if !self.functions.is_empty() {
+            entry.insert("functions".to_string(), Value::Array(self.functions.serialize_vec()?));
+        }
+
+        Ok(Value::Object(entry))
+    }
+}
+
+impl BedrockSerializable for LootPool {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut pool = Map::new();
+        pool.insert("rolls".to_string(), self.rolls.bedrock_json()?);
+
+        if !self.conditions.is_empty() {
+            pool.insert("conditions".to_string(), Value::Array(self.conditions.serialize_vec()?));
+        }
+        pool.insert("entries".to_string(), Value::Array(self.entries.serialize_vec()?));
+
+        Ok(Value::Object(pool))
+    }
+}
+
+impl BedrockSerializable for LootTable {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({
+            "pools": self.pools.serialize_vec()?
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, ToSJson, TransformHashMap};
+    use crate::block::Block;
+    use crate::core::testing;
+    use crate::woah;
+
+    fn block_loot() -> LootTable {
+        woah! {
+            @LootTable {
+                id = "woah:blocks/block";
+                pools = vec![
+                    @LootPool {
+                        rolls = 1..=2;
+                        entries = vec![
+                            @LootEntry {
+                                entry = "minecraft:diamond";
+                                weight = 3;
+                                functions = vec![LootFunction::SetCount { count: (1..=4).into() }];
+                            }
+                        ];
+                    }
+                ];
+            }
+        }
+    }
+
+    #[test]
+    fn block_loot_table() {
+        let memory = testing::build(|events| {
+            events.loot_table_registration.subscribe(|reg| {
+                reg.register_loot_table(block_loot());
+            });
+            events.block_registration.subscribe(|reg| {
+                let loot = block_loot().handle().to_string();
+                reg.register_block(woah! {
+                    @Block {
+                        id = "woah:block";
+                        components = sjson! {
+                            minecraft:loot = $loot
+                        };
+                    }
+                })
+            });
+        });
+
+        let block = memory.read_json("WoahTest_BP/blocks/woah_block.json").unwrap();
+        assert_eq!(block["minecraft:block"]["components"]["minecraft:loot"], "loot_tables/woah/blocks/block.json");
+        assert_eq!(memory.read_json("WoahTest_BP/loot_tables/woah/blocks/block.json").unwrap(), json!({
+            "pools": [{
+                "rolls": { "min": 1, "max": 2 },
+                "entries": [{
+                    "type": "item",
+                    "name": "minecraft:diamond",
+                    "weight": 3,
+                    "functions": [{ "function": "set_count", "count": { "min": 1, "max": 4 } }]
+                }]
+            }]
+        }));
+    }
+}
@@ -0,0 +1,21 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::loot::{LootTable, LootTableHandle};
+
+/// The loot table registry. Used for registering Loot tables.
+pub struct LootTableRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl LootTableRegistry {
+    /// Registers a loot table, returning the handle to reference it by from `minecraft:loot` components.
+    pub fn register_loot_table(&self, table: LootTable) -> LootTableHandle {
+        info!("@loot Registering loot table {}", &table.id);
+
+        let handle = table.handle();
+        self.registry.register_loot_table(table);
+
+        handle
+    }
+}
@@ -0,0 +1,422 @@
+use std::fmt::{Display, Formatter};
+
+/// A Molang expression tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MolangExpr {
+    Number(f64),
+    String(String),
+    /// An access like `q.is_baby`, `v.speed` or `math.pi`.
+    Access(MolangAccess),
+    /// A function call like `q.block_state('woah:val')` or `math.clamp(v.x, 0, 1)`.
+    Call(MolangAccess, Vec<MolangExpr>),
+    Unary(UnaryOp, Box<MolangExpr>),
+    Binary(BinaryOp, Box<MolangExpr>, Box<MolangExpr>),
+    /// `condition ? then : otherwise`
+    Ternary(Box<MolangExpr>, Box<MolangExpr>, Box<MolangExpr>),
+    /// `condition ? then`, evaluates to `0.0` when the condition is false.
+    Conditional(Box<MolangExpr>, Box<MolangExpr>),
+    /// `v.x = value`
+    Assign(MolangAccess, Box<MolangExpr>),
+    Return(Box<MolangExpr>),
+    /// `;`-separated statements, e.g. `v.x = 1; return v.x;`.
+    Statements(Vec<MolangExpr>),
+    /// Statements in braces, e.g. `{ v.x = 1; }`, the body of loops and conditionals.
+    Block(Vec<MolangExpr>),
+    /// `loop(count, body)`
+    Loop(Box<MolangExpr>, Box<MolangExpr>),
+    /// `for_each(t.item, array, body)`
+    ForEach(MolangAccess, Box<MolangExpr>, Box<MolangExpr>),
+    Break,
+    Continue,
+    /// The current value of an animation channel or an entity component.
+    This,
+    /// `array.skins[q.variant]`
+    Index(Box<MolangExpr>, Box<MolangExpr>),
+    /// `v.target->q.health`, evaluating the right side on the entity on the left side.
+    Arrow(Box<MolangExpr>, Box<MolangExpr>)
+}
+
+/// A namespaced Molang path, e.g. `query.block_state` or `v.speed`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MolangAccess {
+    pub namespace: MolangNamespace,
+    pub path: Vec<String>
+}
+
+/// Molang namespaces. Aliases (`q`, `v`, `t`, `c`) are normalized to their short form when printed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MolangNamespace {
+    Query,
+    Variable,
+    Temp,
+    Context,
+    Math,
+    Geometry,
+    Material,
+    Texture,
+    Array
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Negate
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    NullCoalesce,
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide
+}
+
+impl MolangNamespace {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "q" | "query" => MolangNamespace::Query,
+            "v" | "variable" => MolangNamespace::Variable,
+            "t" | "temp" => MolangNamespace::Temp,
+            "c" | "context" => MolangNamespace::Context,
+            "math" => MolangNamespace::Math,
+            "geometry" => MolangNamespace::Geometry,
+            "material" => MolangNamespace::Material,
+            "texture" => MolangNamespace::Texture,
+            "array" => MolangNamespace::Array,
+            _ => return None
+        })
+    }
+
+    pub fn render(&self) -> &'static str {
+        match &self {
+            MolangNamespace::Query => "q",
+            MolangNamespace::Variable => "v",
+            MolangNamespace::Temp => "t",
+            MolangNamespace::Context => "c",
+            MolangNamespace::Math => "math",
+            MolangNamespace::Geometry => "geometry",
+            MolangNamespace::Material => "material",
+            MolangNamespace::Texture => "texture",
+            MolangNamespace::Array => "array"
+        }
+    }
+}
+
+impl MolangAccess {
+    pub fn new(namespace: MolangNamespace, path: impl Into<String>) -> Self {
+        Self {
+            namespace,
+            path: path.into().split('.').map(|s| s.to_string()).collect()
+        }
+    }
+
+    /// The path without the namespace, e.g. `block_state` for `q.block_state`.
+    pub fn name(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+impl Display for MolangAccess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.namespace.render(), self.name())
+    }
+}
+
+impl BinaryOp {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            BinaryOp::NullCoalesce => "??",
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/"
+        }
+    }
+
+    /// Binding power of the operator, higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        match &self {
+            BinaryOp::NullCoalesce => 3,
+            BinaryOp::Or => 4,
+            BinaryOp::And => 5,
+            BinaryOp::Equal | BinaryOp::NotEqual => 6,
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 7,
+            BinaryOp::Add | BinaryOp::Subtract => 8,
+            BinaryOp::Multiply | BinaryOp::Divide => 9
+        }
+    }
+
+    pub fn apply(&self, lhs: f64, rhs: f64) -> f64 {
+        let bool_num = |b: bool| if b { 1.0 } else { 0.0 };
+
+        match &self {
+            BinaryOp::NullCoalesce => lhs,
+            BinaryOp::Or => bool_num(lhs != 0.0 || rhs != 0.0),
+            BinaryOp::And => bool_num(lhs != 0.0 && rhs != 0.0),
+            BinaryOp::Equal => bool_num(lhs == rhs),
+            BinaryOp::NotEqual => bool_num(lhs != rhs),
+            BinaryOp::Less => bool_num(lhs < rhs),
+            BinaryOp::LessEqual => bool_num(lhs <= rhs),
+            BinaryOp::Greater => bool_num(lhs > rhs),
+            BinaryOp::GreaterEqual => bool_num(lhs >= rhs),
+            BinaryOp::Add => lhs + rhs,
+            BinaryOp::Subtract => lhs - rhs,
+            BinaryOp::Multiply => lhs * rhs,
+            BinaryOp::Divide => if rhs == 0.0 { 0.0 } else { lhs / rhs }
+        }
+    }
+}
+
+impl UnaryOp {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            UnaryOp::Not => "!",
+            UnaryOp::Negate => "-"
+        }
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        match &self {
+            UnaryOp::Not => if value == 0.0 { 1.0 } else { 0.0 },
+            UnaryOp::Negate => -value
+        }
+    }
+}
+
+const PRECEDENCE_STATEMENT: u8 = 0;
+const PRECEDENCE_ASSIGN: u8 = 1;
+const PRECEDENCE_TERNARY: u8 = 2;
+const PRECEDENCE_UNARY: u8 = 10;
+const PRECEDENCE_ATOM: u8 = 11;
+
+impl MolangExpr {
+    pub fn precedence(&self) -> u8 {
+        match &self {
+            MolangExpr::Statements(_) => PRECEDENCE_STATEMENT,
+            MolangExpr::Assign(_, _) | MolangExpr::Return(_) => PRECEDENCE_ASSIGN,
+            MolangExpr::Ternary(_, _, _) | MolangExpr::Conditional(_, _) => PRECEDENCE_TERNARY,
+            MolangExpr::Binary(op, _, _) => op.precedence(),
+            MolangExpr::Unary(_, _) => PRECEDENCE_UNARY,
+            _ => PRECEDENCE_ATOM
+        }
+    }
+
+    pub fn binary(op: BinaryOp, lhs: MolangExpr, rhs: MolangExpr) -> Self {
+        MolangExpr::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    /// Visits this expression and all of its children, depth-first.
+    pub fn walk(&self, visitor: &mut impl FnMut(&MolangExpr)) {
+        visitor(self);
+
+        match &self {
+            MolangExpr::Call(_, args) | MolangExpr::Statements(args) | MolangExpr::Block(args) => args.iter().for_each(|a| a.walk(visitor)),
+            MolangExpr::Unary(_, v) | MolangExpr::Assign(_, v) | MolangExpr::Return(v) => v.walk(visitor),
+            MolangExpr::Binary(_, l, r) | MolangExpr::Conditional(l, r) | MolangExpr::Loop(l, r)
+            | MolangExpr::Index(l, r) | MolangExpr::Arrow(l, r) | MolangExpr::ForEach(_, l, r) => {
+                l.walk(visitor);
+                r.walk(visitor);
+            }
+            MolangExpr::Ternary(c, t, o) => {
+                c.walk(visitor);
+                t.walk(visitor);
+                o.walk(visitor);
+            }
+            MolangExpr::Number(_) | MolangExpr::String(_) | MolangExpr::Access(_)
+            | MolangExpr::Break | MolangExpr::Continue | MolangExpr::This => {}
+        }
+    }
+
+    /// Folds constant sub-expressions, e.g. `1 + 2 * 3` into `7` and `math.abs(-2)` into `2`.<br>
+    /// Results which aren't finite, like `math.ln(0)`, can't be written as Molang, so those expressions are kept.
+    pub fn fold(&self) -> MolangExpr {
+        match &self {
+            MolangExpr::Unary(op, v) => match v.fold() {
+                MolangExpr::Number(n) if op.apply(n).is_finite() => MolangExpr::Number(op.apply(n)),
+                v => MolangExpr::Unary(*op, Box::new(v))
+            },
+            MolangExpr::Binary(op, l, r) => match (l.fold(), r.fold()) {
+                (MolangExpr::Number(l), MolangExpr::Number(r)) if op.apply(l, r).is_finite() => MolangExpr::Number(op.apply(l, r)),
+                (MolangExpr::Number(l), _) if *op == BinaryOp::And && l == 0.0 => MolangExpr::Number(0.0),
+                (MolangExpr::Number(l), _) if *op == BinaryOp::Or && l != 0.0 => MolangExpr::Number(1.0),
+                (l, r) => MolangExpr::binary(*op, l, r)
+            },
+            MolangExpr::Ternary(c, t, o) => match c.fold() {
+                MolangExpr::Number(n) => if n != 0.0 { t.fold() } else { o.fold() },
+                c => MolangExpr::Ternary(Box::new(c), Box::new(t.fold()), Box::new(o.fold()))
+            },
+            MolangExpr::Conditional(c, t) => match c.fold() {
+                MolangExpr::Number(n) => if n != 0.0 { t.fold() } else { MolangExpr::Number(0.0) },
+                c => MolangExpr::Conditional(Box::new(c), Box::new(t.fold()))
+            },
+            MolangExpr::Call(access, args) => {
+                let args = args.iter().map(|a| a.fold()).collect::<Vec<MolangExpr>>();
+                let numbers = args.iter().map(|a| match a {
+                    MolangExpr::Number(n) => Some(*n),
+                    _ => None
+                }).collect::<Option<Vec<f64>>>();
+
+                if access.namespace == MolangNamespace::Math && let Some(value) = numbers.and_then(|n| fold_math(&access.name(), &n)).filter(|v| v.is_finite()) {
+                    return MolangExpr::Number(value);
+                }
+
+                MolangExpr::Call(access.clone(), args)
+            },
+            MolangExpr::Access(access) if access.namespace == MolangNamespace::Math && access.name() == "pi" => {
+                MolangExpr::Number(std::f64::consts::PI)
+            },
+            MolangExpr::Assign(access, v) => MolangExpr::Assign(access.clone(), Box::new(v.fold())),
+            MolangExpr::Return(v) => MolangExpr::Return(Box::new(v.fold())),
+            MolangExpr::Statements(s) => MolangExpr::Statements(s.iter().map(|s| s.fold()).collect()),
+            MolangExpr::Block(s) => MolangExpr::Block(s.iter().map(|s| s.fold()).collect()),
+            MolangExpr::Loop(c, b) => MolangExpr::Loop(Box::new(c.fold()), Box::new(b.fold())),
+            MolangExpr::ForEach(v, a, b) => MolangExpr::ForEach(v.clone(), Box::new(a.fold()), Box::new(b.fold())),
+            MolangExpr::Index(a, i) => MolangExpr::Index(Box::new(a.fold()), Box::new(i.fold())),
+            MolangExpr::Arrow(l, r) => MolangExpr::Arrow(Box::new(l.fold()), Box::new(r.fold())),
+            _ => self.clone()
+        }
+    }
+
+    fn fmt_child(&self, f: &mut Formatter<'_>, child: &MolangExpr, parenthesize_equal: bool) -> std::fmt::Result {
+        let own = self.precedence();
+        let other = child.precedence();
+
+        if other < own || (parenthesize_equal && other == own) {
+            write!(f, "({})", child)
+        } else {
+            write!(f, "{}", child)
+        }
+    }
+}
+
+/// Evaluates pure `math.*` functions with constant arguments. Random functions are never folded.
+pub(crate) fn fold_math(name: &str, args: &[f64]) -> Option<f64> {
+    Some(match (name, args) {
+        ("abs", [v]) => v.abs(),
+        ("acos", [v]) => v.acos().to_degrees(),
+        ("asin", [v]) => v.asin().to_degrees(),
+        ("atan", [v]) => v.atan().to_degrees(),
+        ("atan2", [y, x]) => y.atan2(*x).to_degrees(),
+        ("ceil", [v]) => v.ceil(),
+        ("clamp", [v, min, max]) => v.max(*min).min(*max),
+        ("cos", [v]) => v.to_radians().cos(),
+        ("exp", [v]) => v.exp(),
+        ("floor", [v]) => v.floor(),
+        ("hermite_blend", [v]) => 3.0 * v * v - 2.0 * v * v * v,
+        ("lerp", [start, end, t]) => start + (end - start) * t,
+        ("ln", [v]) => v.ln(),
+        ("max", [a, b]) => a.max(*b),
+        ("min", [a, b]) => a.min(*b),
+        ("mod", [v, d]) => if *d == 0.0 { 0.0 } else { v % d },
+        ("pow", [b, e]) => b.powf(*e),
+        ("round", [v]) => v.round(),
+        ("sin", [v]) => v.to_radians().sin(),
+        ("sqrt", [v]) => v.sqrt(),
+        ("trunc", [v]) => v.trunc(),
+        _ => return None
+    })
+}
+
+fn fmt_number(f: &mut Formatter<'_>, n: f64) -> std::fmt::Result {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        write!(f, "{}", n as i64)
+    } else {
+        write!(f, "{}", n)
+    }
+}
+
+fn fmt_statements(f: &mut Formatter<'_>, statements: &[MolangExpr]) -> std::fmt::Result {
+    for (i, statement) in statements.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{};", statement)?;
+    }
+    Ok(())
+}
+
+impl Display for MolangExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            MolangExpr::Number(n) => fmt_number(f, *n),
+            MolangExpr::String(s) => write!(f, "'{}'", s),
+            MolangExpr::Access(access) => write!(f, "{}", access),
+            MolangExpr::Call(access, args) => {
+                write!(f, "{}(", access)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                f.write_str(")")
+            }
+            MolangExpr::Unary(op, v) => {
+                f.write_str(op.render())?;
+                self.fmt_child(f, v, false)
+            }
+            MolangExpr::Binary(op, l, r) => {
+                self.fmt_child(f, l, false)?;
+                write!(f, " {} ", op.render())?;
+                self.fmt_child(f, r, true)
+            }
+            MolangExpr::Ternary(c, t, o) => {
+                self.fmt_child(f, c, true)?;
+                f.write_str(" ? ")?;
+                self.fmt_child(f, t, true)?;
+                f.write_str(" : ")?;
+                self.fmt_child(f, o, false)
+            }
+            MolangExpr::Conditional(c, t) => {
+                self.fmt_child(f, c, true)?;
+                f.write_str(" ? ")?;
+                self.fmt_child(f, t, true)
+            }
+            MolangExpr::Assign(access, v) => {
+                write!(f, "{} = ", access)?;
+                self.fmt_child(f, v, false)
+            }
+            MolangExpr::Return(v) => {
+                f.write_str("return ")?;
+                self.fmt_child(f, v, false)
+            }
+            MolangExpr::Statements(s) => fmt_statements(f, s),
+            MolangExpr::Block(s) => {
+                f.write_str("{")?;
+                fmt_statements(f, s)?;
+                f.write_str("}")
+            }
+            MolangExpr::Loop(c, b) => write!(f, "loop({}, {})", c, b),
+            MolangExpr::ForEach(v, a, b) => write!(f, "for_each({}, {}, {})", v, a, b),
+            MolangExpr::Break => f.write_str("break"),
+            MolangExpr::Continue => f.write_str("continue"),
+            MolangExpr::This => f.write_str("this"),
+            MolangExpr::Index(a, i) => {
+                self.fmt_child(f, a, false)?;
+                write!(f, "[{}]", i)
+            }
+            MolangExpr::Arrow(l, r) => {
+                self.fmt_child(f, l, false)?;
+                f.write_str("->")?;
+                self.fmt_child(f, r, false)
+            }
+        }
+    }
+}
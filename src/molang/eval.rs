@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use crate::molang::ast::{fold_math, BinaryOp, MolangAccess, MolangExpr, MolangNamespace};
+
+/// A value produced by evaluating Molang. Booleans are represented as `1.0` and `0.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MolangValue {
+    Number(f64),
+    String(String)
+}
+
+/// An error produced when a statement can't be evaluated offline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MolangEvalError {
+    pub message: String
+}
+
+impl Display for MolangEvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Molang evaluation error: {}", self.message)
+    }
+}
+
+impl std::error::Error for MolangEvalError {}
+
+type QueryStub = Arc<dyn Fn(&[MolangValue]) -> MolangValue + Send + Sync>;
+
+/// A mock Molang context for evaluating statements outside of the game.<br>
+/// `q.block_state`, `q.has_block_state`, `q.property` and `q.has_property` are answered from the provided block states and entity properties,
+/// every other query has to be stubbed with [MolangContext::with_query].
+/// ```rust
+/// use woah::molang::Molang;
+/// use woah::molang::eval::{MolangContext, MolangValue};
+///
+/// let mut context = MolangContext::new()
+///     .with_block_state("woah:val", 3)
+///     .with_query("is_baby", |_| true.into());
+///
+/// assert_eq!(Molang::new("q.block_state('woah:val') > 2 && q.is_baby").evaluate(&mut context), Ok(MolangValue::Number(1.0)));
+/// ```
+#[derive(Clone, Default)]
+pub struct MolangContext {
+    block_states: HashMap<String, MolangValue>,
+    properties: HashMap<String, MolangValue>,
+    variables: HashMap<String, MolangValue>,
+    temps: HashMap<String, MolangValue>,
+    context: HashMap<String, MolangValue>,
+    queries: HashMap<String, QueryStub>
+}
+
+impl MolangValue {
+    pub fn as_number(&self) -> Result<f64, MolangEvalError> {
+        match &self {
+            MolangValue::Number(n) => Ok(*n),
+            MolangValue::String(s) => Err(MolangEvalError { message: format!("Expected a number, found string '{}'", s) })
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, MolangEvalError> {
+        Ok(self.as_number()? != 0.0)
+    }
+}
+
+impl From<f64> for MolangValue {
+    fn from(value: f64) -> Self {
+        MolangValue::Number(value)
+    }
+}
+
+impl From<f32> for MolangValue {
+    fn from(value: f32) -> Self {
+        MolangValue::Number(value as f64)
+    }
+}
+
+impl From<i32> for MolangValue {
+    fn from(value: i32) -> Self {
+        MolangValue::Number(value as f64)
+    }
+}
+
+impl From<i64> for MolangValue {
+    fn from(value: i64) -> Self {
+        MolangValue::Number(value as f64)
+    }
+}
+
+impl From<bool> for MolangValue {
+    fn from(value: bool) -> Self {
+        MolangValue::Number(if value { 1.0 } else { 0.0 })
+    }
+}
+
+impl From<&str> for MolangValue {
+    fn from(value: &str) -> Self {
+        MolangValue::String(value.to_string())
+    }
+}
+
+impl From<String> for MolangValue {
+    fn from(value: String) -> Self {
+        MolangValue::String(value)
+    }
+}
+
+fn eval_error<T>(message: impl Into<String>) -> Result<T, MolangEvalError> {
+    Err(MolangEvalError { message: message.into() })
+}
+
+enum Flow {
+    Value(MolangValue),
+    Return(MolangValue),
+    Break,
+    Continue
+}
+
+/// Bedrock stops loops after this many iterations.
+const MAX_LOOP_ITERATIONS: usize = 1024;
+
+impl MolangContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_block_state(mut self, id: impl Into<String>, value: impl Into<MolangValue>) -> Self {
+        self.block_states.insert(id.into(), value.into());
+        self
+    }
+
+    pub fn with_property(mut self, id: impl Into<String>, value: impl Into<MolangValue>) -> Self {
+        self.properties.insert(id.into(), value.into());
+        self
+    }
+
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<MolangValue>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_context(mut self, name: impl Into<String>, value: impl Into<MolangValue>) -> Self {
+        self.context.insert(name.into(), value.into());
+        self
+    }
+
+    /// Stubs a query (without the `q.` prefix), called with the evaluated arguments.
+    pub fn with_query(mut self, name: impl Into<String>, query: impl Fn(&[MolangValue]) -> MolangValue + Send + Sync + 'static) -> Self {
+        self.queries.insert(name.into(), Arc::new(query));
+        self
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&MolangValue> {
+        self.variables.get(name)
+    }
+
+    /// Evaluates an expression. `t.` values are cleared afterwards, `v.` values persist between calls.
+    pub fn evaluate(&mut self, expr: &MolangExpr) -> Result<MolangValue, MolangEvalError> {
+        let result = match self.eval(expr)? {
+            Flow::Value(v) | Flow::Return(v) => v,
+            Flow::Break | Flow::Continue => return eval_error("`break` and `continue` can only be used in a loop")
+        };
+        self.temps.clear();
+
+        Ok(result)
+    }
+
+    fn storage(&mut self, namespace: &MolangNamespace) -> Option<&mut HashMap<String, MolangValue>> {
+        match namespace {
+            MolangNamespace::Variable => Some(&mut self.variables),
+            MolangNamespace::Temp => Some(&mut self.temps),
+            MolangNamespace::Context => Some(&mut self.context),
+            _ => None
+        }
+    }
+
+    fn value(&mut self, expr: &MolangExpr) -> Result<MolangValue, MolangEvalError> {
+        match self.eval(expr)? {
+            Flow::Value(v) => Ok(v),
+            Flow::Return(_) => eval_error("`return` can only be used as a statement"),
+            Flow::Break | Flow::Continue => eval_error("`break` and `continue` can only be used as statements in a loop")
+        }
+    }
+
+    fn eval(&mut self, expr: &MolangExpr) -> Result<Flow, MolangEvalError> {
+        let value = match expr {
+            MolangExpr::Number(n) => MolangValue::Number(*n),
+            MolangExpr::String(s) => MolangValue::String(s.clone()),
+            MolangExpr::Access(access) => self.access(access, &[])?,
+            MolangExpr::Call(access, args) => {
+                let args = args.iter().map(|a| self.value(a)).collect::<Result<Vec<MolangValue>, MolangEvalError>>()?;
+                self.access(access, &args)?
+            }
+            MolangExpr::Unary(op, v) => MolangValue::Number(op.apply(self.value(v)?.as_number()?)),
+            MolangExpr::Binary(op, l, r) => self.binary(*op, l, r)?,
+            // Branches can be blocks, `break` or `continue`, so their flow is passed on.
+            MolangExpr::Ternary(c, t, o) => return if self.value(c)?.as_bool()? { self.eval(t) } else { self.eval(o) },
+            MolangExpr::Conditional(c, t) => {
+                if self.value(c)?.as_bool()? {
+                    return self.eval(t);
+                }
+                MolangValue::Number(0.0)
+            }
+            MolangExpr::Assign(access, v) => {
+                let value = self.value(v)?;
+                match self.storage(&access.namespace) {
+                    Some(storage) => storage.insert(access.name(), value.clone()),
+                    None => return eval_error(format!("`{}` can't be assigned to", access))
+                };
+                value
+            }
+            MolangExpr::Return(v) => return Ok(Flow::Return(self.value(v)?)),
+            MolangExpr::Statements(statements) | MolangExpr::Block(statements) => {
+                for statement in statements {
+                    match self.eval(statement)? {
+                        Flow::Value(_) => {}
+                        flow => return Ok(flow)
+                    }
+                }
+                MolangValue::Number(0.0)
+            }
+            MolangExpr::Loop(count, body) => {
+                let count = self.value(count)?.as_number()?.max(0.0) as usize;
+
+                for _ in 0..count.min(MAX_LOOP_ITERATIONS) {
+                    match self.eval(body)? {
+                        Flow::Break => break,
+                        Flow::Return(v) => return Ok(Flow::Return(v)),
+                        Flow::Value(_) | Flow::Continue => {}
+                    }
+                }
+                MolangValue::Number(0.0)
+            }
+            MolangExpr::Break => return Ok(Flow::Break),
+            MolangExpr::Continue => return Ok(Flow::Continue),
+            MolangExpr::ForEach(_, _, _) | MolangExpr::Index(_, _) => return eval_error("Arrays can't be evaluated offline"),
+            MolangExpr::Arrow(_, _) => return eval_error("`->` can't be evaluated offline"),
+            MolangExpr::This => return eval_error("`this` can't be evaluated offline")
+        };
+
+        Ok(Flow::Value(value))
+    }
+
+    fn binary(&mut self, op: BinaryOp, l: &MolangExpr, r: &MolangExpr) -> Result<MolangValue, MolangEvalError> {
+        match op {
+            BinaryOp::And => Ok((self.value(l)?.as_bool()? && self.value(r)?.as_bool()?).into()),
+            BinaryOp::Or => Ok((self.value(l)?.as_bool()? || self.value(r)?.as_bool()?).into()),
+            BinaryOp::NullCoalesce => {
+                if let MolangExpr::Access(access) = l && let Some(storage) = self.storage(&access.namespace) {
+                    if let Some(value) = storage.get(&access.name()) {
+                        return Ok(value.clone());
+                    }
+                    return self.value(r);
+                }
+                self.value(l)
+            }
+            _ => match (self.value(l)?, self.value(r)?) {
+                (MolangValue::String(l), MolangValue::String(r)) => match op {
+                    BinaryOp::Equal => Ok((l == r).into()),
+                    BinaryOp::NotEqual => Ok((l != r).into()),
+                    _ => eval_error(format!("Operator `{}` can't be applied to strings", op.render()))
+                },
+                (l, r) => Ok(MolangValue::Number(op.apply(l.as_number()?, r.as_number()?)))
+            }
+        }
+    }
+
+    fn access(&mut self, access: &MolangAccess, args: &[MolangValue]) -> Result<MolangValue, MolangEvalError> {
+        let name = access.name();
+
+        match &access.namespace {
+            MolangNamespace::Query => self.query(&name, args),
+            MolangNamespace::Math => {
+                if name == "pi" {
+                    return Ok(MolangValue::Number(std::f64::consts::PI));
+                }
+                let numbers = args.iter().map(|a| a.as_number()).collect::<Result<Vec<f64>, MolangEvalError>>()?;
+                match fold_math(&name, &numbers) {
+                    Some(value) => Ok(MolangValue::Number(value)),
+                    None => eval_error(format!("`math.{}` can't be evaluated offline with {} argument(s)", name, args.len()))
+                }
+            }
+            namespace => match self.storage(namespace) {
+                Some(storage) => Ok(storage.get(&name).cloned().unwrap_or(MolangValue::Number(0.0))),
+                None => eval_error(format!("`{}` can't be evaluated offline", access))
+            }
+        }
+    }
+
+    fn query(&self, name: &str, args: &[MolangValue]) -> Result<MolangValue, MolangEvalError> {
+        if let Some(stub) = self.queries.get(name) {
+            return Ok(stub(args));
+        }
+
+        let lookup = |values: &HashMap<String, MolangValue>, has: bool| {
+            let Some(MolangValue::String(id)) = args.first() else {
+                return eval_error(format!("`q.{}` expects an identifier argument", name));
+            };
+            match values.get(id) {
+                Some(value) if !has => Ok(value.clone()),
+                value if has => Ok(value.is_some().into()),
+                _ => eval_error(format!("`q.{}('{}')` has no value in the context", name, id))
+            }
+        };
+
+        match name {
+            "block_state" => lookup(&self.block_states, false),
+            "has_block_state" => lookup(&self.block_states, true),
+            "property" | "actor_property" => lookup(&self.properties, false),
+            "has_property" => lookup(&self.properties, true),
+            _ => eval_error(format!("Query `q.{}` isn't stubbed in the context", name))
+        }
+    }
+}
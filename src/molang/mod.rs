@@ -1,54 +1,132 @@
+pub mod ast;
+pub mod parser;
+pub mod eval;
+
+mod functions {
+    include!(concat!(env!("OUT_DIR"), "/molang/functions.rs"));
+}
+
+use std::fmt::{Display, Formatter};
 use std::ops::{BitAnd, BitOr};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::molang::ast::{BinaryOp, MolangExpr, MolangNamespace};
+use crate::molang::eval::{MolangContext, MolangEvalError, MolangValue};
+use crate::molang::parser::MolangError;
 
-#[derive(Clone, Debug)]
-enum MolangConcat {
-    And,
-    Or,
-    None
-}
+pub use functions::{KNOWN_MATH_FUNCTIONS, KNOWN_QUERIES};
 
-/// A struct for describing Molang statements.<br>
+/// A struct for describing Molang statements, parsed into a [MolangExpr] tree.<br>
 /// Other statements can be programmatically appended with the operators & for `and` and | for `or`.
 /// ```rust
 /// use woah::molang::Molang;
 ///
 /// Molang::new("q.x") & (Molang::new("q.y") | Molang::new("q.z"));
 /// ```
+/// Syntax errors don't panic here, they are reported by [Molang::validate] when the pack is built.
 #[derive(Clone, Debug)]
 pub struct Molang {
-    pub query: String,
-    next: Vec<Molang>,
-    concat_mode: MolangConcat
+    expr: Result<MolangExpr, MolangError>
 }
 
 impl Molang {
     pub fn new(query: impl Into<String>) -> Self {
         Self {
-            query: query.into(),
-            next: Vec::new(),
-            concat_mode: MolangConcat::None
+            expr: parser::parse(&query.into())
         }
     }
 
-    pub fn and(&self, other: Molang) -> Self {
-        let mut sc = self.clone();
+    pub fn parse(query: impl Into<String>) -> Result<Self, MolangError> {
+        Ok(Self::from_expr(parser::parse(&query.into())?))
+    }
 
-        sc.concat_mode = MolangConcat::And;
-        
-        sc.next.push(other);
+    pub fn from_expr(expr: MolangExpr) -> Self {
+        Self { expr: Ok(expr) }
+    }
+
+    pub fn expr(&self) -> Result<&MolangExpr, &MolangError> {
+        self.expr.as_ref()
+    }
 
-        sc
+    pub fn and(&self, other: Molang) -> Self {
+        self.combine(BinaryOp::And, other)
     }
 
     pub fn or(&self, other: Molang) -> Self {
-        let mut sc = self.clone();
-        
-        sc.concat_mode = MolangConcat::Or;
+        self.combine(BinaryOp::Or, other)
+    }
+
+    /// `;`-separated statements and `return` aren't values, so combining them is reported as an error instead of rendering invalid Molang.
+    fn combine(&self, op: BinaryOp, other: Molang) -> Self {
+        let is_complex = |e: &MolangExpr| matches!(e, MolangExpr::Statements(_) | MolangExpr::Return(_));
+
+        Self {
+            expr: match (&self.expr, other.expr) {
+                (Ok(lhs), Ok(rhs)) if is_complex(lhs) || is_complex(&rhs) => Err(MolangError {
+                    source: format!("{} {} {}", lhs, op.render(), rhs),
+                    position: 0,
+                    message: format!("`;`-separated statements and `return` can't be combined with `{}`", op.render())
+                }),
+                (Ok(lhs), Ok(rhs)) => Ok(MolangExpr::binary(op, lhs.clone(), rhs)),
+                (Err(err), _) => Err(err.clone()),
+                (_, Err(err)) => Err(err)
+            }
+        }
+    }
+
+    /// Returns a copy of this statement with constant sub-expressions folded.
+    pub fn folded(&self) -> Self {
+        Self {
+            expr: self.expr.as_ref().map(|e| e.fold()).map_err(|e| e.clone())
+        }
+    }
+
+    /// Checks the statement for syntax errors.
+    pub fn validate(&self) -> Result<(), MolangError> {
+        self.expr.as_ref().map(|_| ()).map_err(|e| e.clone())
+    }
+
+    /// Lists `q.` and `math.` names which aren't known to the bundled Molang schemas.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if let Ok(expr) = &self.expr {
+            expr.walk(&mut |e| {
+                let access = match e {
+                    MolangExpr::Access(access) | MolangExpr::Call(access, _) => access,
+                    _ => return
+                };
+                let known = match access.namespace {
+                    MolangNamespace::Query => KNOWN_QUERIES,
+                    MolangNamespace::Math => KNOWN_MATH_FUNCTIONS,
+                    _ => return
+                };
+
+                if !known.iter().any(|k| *k == access.name()) {
+                    warnings.push(format!("Unknown Molang function `{}`", access));
+                }
+            });
+        }
 
-        sc.next.push(other);
+        warnings
+    }
 
-        sc
+    /// Evaluates the statement offline against a mock [MolangContext].
+    pub fn evaluate(&self, context: &mut MolangContext) -> Result<MolangValue, MolangEvalError> {
+        match &self.expr {
+            Ok(expr) => context.evaluate(expr),
+            Err(err) => Err(MolangEvalError { message: err.to_string() })
+        }
+    }
+
+    /// Renders the statement deterministically. Statements with syntax errors are rendered as they were written.
+    pub fn render(&self) -> String {
+        match &self.expr {
+            Ok(expr) => expr.to_string(),
+            Err(err) => err.source.clone()
+        }
     }
 }
 
@@ -80,18 +158,86 @@ impl From<&str> for Molang {
     }
 }
 
+impl From<MolangExpr> for Molang {
+    fn from(value: MolangExpr) -> Self {
+        Self::from_expr(value)
+    }
+}
+
+impl Display for Molang {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl Serialize for Molang {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        serializer.serialize_str(&self.render())
+    }
+}
+
 impl BedrockSerializable for Molang {
-    fn bedrock_serialize(&self) -> String {
-        let next = self.next.clone();
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(Value::String(self.render()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_and_fold() {
+        let condition = Molang::new("q.block_state('woah:val') > 2") & (Molang::new("Q.is_baby") | Molang::new("v.x == 1 + 2 * 3"));
+        assert_eq!(condition.render(), "q.block_state('woah:val') > 2 && (q.is_baby || v.x == 1 + 2 * 3)");
+        assert_eq!(condition.folded().render(), "q.block_state('woah:val') > 2 && (q.is_baby || v.x == 7)");
+        assert_eq!(Molang::new("v.a = (1 + 2) * 3; return v.a > 4 ? 1 : 0;").render(), "v.a = (1 + 2) * 3; return v.a > 4 ? 1 : 0;");
+
+        let combined = Molang::new("v.a = 1; return v.a;") & Molang::new("q.is_baby");
+        assert!(combined.validate().is_err());
+        assert_eq!(combined.render(), "v.a = 1; return v.a; && q.is_baby");
+    }
 
-        let serialized_next = next.into_iter().map(|x| x.bedrock_serialize()).collect::<Vec<String>>().join(" ");
+    #[test]
+    fn non_finite_folding() {
+        // Division by zero is `0` in game, so it folds like any other division.
+        assert_eq!(Molang::new("1 / 0").folded().render(), "0");
+        assert_eq!(Molang::new("math.ln(0) + 1 / 2").folded().render(), "math.ln(0) + 0.5");
+        assert_eq!(Molang::new("v.x + math.sqrt(-1)").folded().render(), "v.x + math.sqrt(-1)");
+        assert_eq!(Molang::new("math.pow(10, 400) * 10").folded().render(), "math.pow(10, 400) * 10");
+    }
 
-        let serialized_self = format!("({})", &self.query);
+    #[test]
+    fn validation() {
+        assert!(Molang::new("q.is_baby &&").validate().is_err());
+        assert_eq!(Molang::new("q.not_a_query").warnings().len(), 1);
+    }
 
-        match &self.concat_mode {
-            MolangConcat::And => format!("{} && ({})", serialized_self, serialized_next),
-            MolangConcat::Or => format!("{} || ({})", serialized_self, serialized_next),
-            MolangConcat::None => self.query.clone(),
+    #[test]
+    fn statements() {
+        for (source, rendered) in [
+            ("{ v.a = 1; v.b = 2; }", "{v.a = 1; v.b = 2;}"),
+            ("loop(10, { v.x = v.x + 1; (v.x > 5) ? break; });", "loop(10, {v.x = v.x + 1; v.x > 5 ? break;});"),
+            ("for_each(t.pig, q.get_nearby_entities(4, 'minecraft:pig'), { v.count = v.count + 1; });", "for_each(t.pig, q.get_nearby_entities(4, 'minecraft:pig'), {v.count = v.count + 1;});"),
+            ("loop(3, { q.is_baby ? continue; v.x = 1; });", "loop(3, {q.is_baby ? continue; v.x = 1;});"),
+            ("this * 2", "this * 2"),
+            ("array.skins[q.variant]", "array.skins[q.variant]"),
+            ("v.target->q.health > 4", "v.target->q.health > 4")
+        ] {
+            let molang = Molang::new(source);
+            assert_eq!(molang.validate(), Ok(()), "{}", source);
+            assert_eq!(molang.render(), rendered);
+            assert_eq!(Molang::new(rendered).expr(), molang.expr());
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn evaluation() {
+        let mut context = MolangContext::new();
+        assert_eq!(Molang::new("loop(3, {v.i = v.i + 1; v.i == 2 ? continue; v.s = v.s + v.i;}); return v.s;").evaluate(&mut context), Ok(MolangValue::Number(4.0)));
+        assert_eq!(Molang::new("loop(100, {v.n = v.n + 1; v.n >= 5 ? break;}); return v.n;").evaluate(&mut context), Ok(MolangValue::Number(5.0)));
+    }
+}
@@ -0,0 +1,428 @@
+use std::fmt::{Display, Formatter};
+use crate::molang::ast::{BinaryOp, MolangAccess, MolangExpr, MolangNamespace, UnaryOp};
+
+/// A Molang syntax error, with the character offset it was found at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MolangError {
+    pub source: String,
+    pub position: usize,
+    pub message: String
+}
+
+impl Display for MolangError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Molang syntax error at {} in `{}`: {}", self.position, self.source, self.message)
+    }
+}
+
+impl std::error::Error for MolangError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    String(String),
+    Ident(String),
+    Symbol(&'static str),
+    End
+}
+
+const SYMBOLS: &[&str] = &[
+    "??", "&&", "||", "==", "!=", "<=", ">=", "->",
+    "!", "<", ">", "+", "-", "*", "/", "?", ":", "=", ";", ",", ".", "(", ")", "[", "]", "{", "}"
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, MolangError> {
+    let chars = source.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let error = |position: usize, message: String| MolangError {
+        source: source.to_string(), position, message
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal = chars[start..i].iter().collect::<String>();
+            if i < chars.len() && chars[i].eq_ignore_ascii_case(&'f') {
+                i += 1;
+            }
+            let value = literal.parse::<f64>().map_err(|_| error(start, format!("Invalid number `{}`", literal)))?;
+            tokens.push((Token::Number(value), start));
+        } else if c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(error(start, "Unterminated string".to_string()));
+            }
+            tokens.push((Token::String(chars[start + 1..i].iter().collect()), start));
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()), start));
+        } else {
+            let rest = chars[i..].iter().take(2).collect::<String>();
+            match SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+                Some(symbol) => {
+                    tokens.push((Token::Symbol(symbol), i));
+                    i += symbol.len();
+                }
+                None => return Err(error(i, format!("Unexpected character `{}`", c)))
+            }
+        }
+    }
+
+    tokens.push((Token::End, chars.len()));
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(Token, usize)>,
+    position: usize
+}
+
+/// Parses a Molang expression or a `;`-separated list of statements.
+pub fn parse(source: &str) -> Result<MolangExpr, MolangError> {
+    let mut parser = Parser {
+        source,
+        tokens: tokenize(source)?,
+        position: 0
+    };
+
+    let expr = parser.parse_statements()?;
+    parser.expect_end()?;
+
+    Ok(expr)
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].0.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, MolangError> {
+        Err(MolangError {
+            source: self.source.to_string(),
+            position: self.offset(),
+            message: message.into()
+        })
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(s) if *s == symbol) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), MolangError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            self.error(format!("Expected `{}`, found {}", symbol, self.describe()))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), MolangError> {
+        if *self.peek() == Token::End {
+            Ok(())
+        } else {
+            self.error(format!("Unexpected {}", self.describe()))
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.peek() {
+            Token::Number(n) => format!("number `{}`", n),
+            Token::String(s) => format!("string `'{}'`", s),
+            Token::Ident(i) => format!("`{}`", i),
+            Token::Symbol(s) => format!("`{}`", s),
+            Token::End => "end of expression".to_string()
+        }
+    }
+
+    fn parse_statements(&mut self) -> Result<MolangExpr, MolangError> {
+        let mut statements = vec![self.parse_assignment()?];
+        let mut is_complex = false;
+
+        while self.eat(";") {
+            is_complex = true;
+            if *self.peek() == Token::End {
+                break;
+            }
+            statements.push(self.parse_assignment()?);
+        }
+
+        if is_complex {
+            Ok(MolangExpr::Statements(statements))
+        } else {
+            Ok(statements.remove(0))
+        }
+    }
+
+    fn parse_assignment(&mut self) -> Result<MolangExpr, MolangError> {
+        if *self.peek() == Token::Ident("return".to_string()) {
+            self.advance();
+            return Ok(MolangExpr::Return(Box::new(self.parse_ternary()?)));
+        }
+
+        let offset = self.offset();
+        let lhs = self.parse_ternary()?;
+
+        if self.eat("=") {
+            return match lhs {
+                MolangExpr::Access(access) if matches!(access.namespace, MolangNamespace::Variable | MolangNamespace::Temp | MolangNamespace::Context) => {
+                    Ok(MolangExpr::Assign(access, Box::new(self.parse_ternary()?)))
+                }
+                _ => Err(MolangError {
+                    source: self.source.to_string(),
+                    position: offset,
+                    message: "Only `v.`, `t.` and `c.` values can be assigned to".to_string()
+                })
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_ternary(&mut self) -> Result<MolangExpr, MolangError> {
+        let condition = self.parse_binary(BinaryOp::NullCoalesce.precedence())?;
+
+        if self.eat("?") {
+            let then = self.parse_ternary()?;
+
+            if self.eat(":") {
+                let otherwise = self.parse_ternary()?;
+                return Ok(MolangExpr::Ternary(Box::new(condition), Box::new(then), Box::new(otherwise)));
+            }
+
+            return Ok(MolangExpr::Conditional(Box::new(condition), Box::new(then)));
+        }
+
+        Ok(condition)
+    }
+
+    fn peek_binary(&self) -> Option<BinaryOp> {
+        Some(match self.peek() {
+            Token::Symbol("??") => BinaryOp::NullCoalesce,
+            Token::Symbol("||") => BinaryOp::Or,
+            Token::Symbol("&&") => BinaryOp::And,
+            Token::Symbol("==") => BinaryOp::Equal,
+            Token::Symbol("!=") => BinaryOp::NotEqual,
+            Token::Symbol("<") => BinaryOp::Less,
+            Token::Symbol("<=") => BinaryOp::LessEqual,
+            Token::Symbol(">") => BinaryOp::Greater,
+            Token::Symbol(">=") => BinaryOp::GreaterEqual,
+            Token::Symbol("+") => BinaryOp::Add,
+            Token::Symbol("-") => BinaryOp::Subtract,
+            Token::Symbol("*") => BinaryOp::Multiply,
+            Token::Symbol("/") => BinaryOp::Divide,
+            _ => return None
+        })
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<MolangExpr, MolangError> {
+        let mut lhs = self.parse_unary()?;
+
+        while let Some(op) = self.peek_binary() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+
+            self.advance();
+            let rhs = self.parse_binary(op.precedence() + 1)?;
+            lhs = MolangExpr::binary(op, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<MolangExpr, MolangError> {
+        if self.eat("!") {
+            return Ok(MolangExpr::Unary(UnaryOp::Not, Box::new(self.parse_unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(match self.parse_unary()? {
+                MolangExpr::Number(n) => MolangExpr::Number(-n),
+                v => MolangExpr::Unary(UnaryOp::Negate, Box::new(v))
+            });
+        }
+
+        self.parse_postfix()
+    }
+
+    /// Array indexing and arrow access, e.g. `array.skins[q.variant]` or `v.target->q.health`.
+    fn parse_postfix(&mut self) -> Result<MolangExpr, MolangError> {
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            if self.eat("[") {
+                let index = self.parse_ternary()?;
+                self.expect("]")?;
+                expr = MolangExpr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat("->") {
+                expr = MolangExpr::Arrow(Box::new(expr), Box::new(self.parse_primary()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// The statements of a `{ ... }` block, after the opening brace.
+    fn parse_block(&mut self) -> Result<MolangExpr, MolangError> {
+        let mut statements = Vec::new();
+
+        while !self.eat("}") {
+            statements.push(self.parse_assignment()?);
+
+            if !self.eat(";") {
+                self.expect("}")?;
+                break;
+            }
+        }
+
+        Ok(MolangExpr::Block(statements))
+    }
+
+    fn parse_loop(&mut self) -> Result<MolangExpr, MolangError> {
+        self.expect("(")?;
+        let count = self.parse_ternary()?;
+        self.expect(",")?;
+        let body = self.parse_ternary()?;
+        self.expect(")")?;
+
+        Ok(MolangExpr::Loop(Box::new(count), Box::new(body)))
+    }
+
+    fn parse_for_each(&mut self) -> Result<MolangExpr, MolangError> {
+        self.expect("(")?;
+        let offset = self.offset();
+        let variable = match self.parse_primary()? {
+            MolangExpr::Access(access) if matches!(access.namespace, MolangNamespace::Variable | MolangNamespace::Temp) => access,
+            _ => return Err(MolangError {
+                source: self.source.to_string(),
+                position: offset,
+                message: "`for_each` expects a `v.` or `t.` variable".to_string()
+            })
+        };
+        self.expect(",")?;
+        let array = self.parse_ternary()?;
+        self.expect(",")?;
+        let body = self.parse_ternary()?;
+        self.expect(")")?;
+
+        Ok(MolangExpr::ForEach(variable, Box::new(array), Box::new(body)))
+    }
+
+    fn parse_primary(&mut self) -> Result<MolangExpr, MolangError> {
+        match self.peek().clone() {
+            Token::Number(n) => {
+                self.advance();
+                Ok(MolangExpr::Number(n))
+            }
+            Token::String(s) => {
+                self.advance();
+                Ok(MolangExpr::String(s))
+            }
+            Token::Symbol("(") => {
+                self.advance();
+                let expr = self.parse_assignment()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Symbol("{") => {
+                self.advance();
+                self.parse_block()
+            }
+            Token::Ident(ident) if ident == "true" || ident == "false" => {
+                self.advance();
+                Ok(MolangExpr::Number(if ident == "true" { 1.0 } else { 0.0 }))
+            }
+            Token::Ident(ident) if matches!(ident.as_str(), "break" | "continue" | "this") => {
+                self.advance();
+                Ok(match ident.as_str() {
+                    "break" => MolangExpr::Break,
+                    "continue" => MolangExpr::Continue,
+                    _ => MolangExpr::This
+                })
+            }
+            Token::Ident(ident) if ident == "loop" => {
+                self.advance();
+                self.parse_loop()
+            }
+            Token::Ident(ident) if ident == "for_each" => {
+                self.advance();
+                self.parse_for_each()
+            }
+            Token::Ident(ident) => {
+                let Some(namespace) = MolangNamespace::parse(&ident) else {
+                    return self.error(format!("Unknown namespace `{}`", ident));
+                };
+                self.advance();
+
+                let mut path = Vec::new();
+                while self.eat(".") {
+                    match self.peek().clone() {
+                        Token::Ident(segment) => {
+                            self.advance();
+                            path.push(segment);
+                        }
+                        _ => return self.error(format!("Expected a name after `{}.`", ident))
+                    }
+                }
+
+                if path.is_empty() {
+                    return self.error(format!("Expected `.` after namespace `{}`", ident));
+                }
+
+                let access = MolangAccess { namespace, path };
+
+                if self.eat("(") {
+                    let mut args = Vec::new();
+                    if !self.eat(")") {
+                        loop {
+                            args.push(self.parse_ternary()?);
+                            if self.eat(")") {
+                                break;
+                            }
+                            self.expect(",")?;
+                        }
+                    }
+                    return Ok(MolangExpr::Call(access, args));
+                }
+
+                Ok(MolangExpr::Access(access))
+            }
+            _ => self.error(format!("Expected a value, found {}", self.describe()))
+        }
+    }
+}
@@ -0,0 +1,468 @@
+pub mod registry;
+
+use std::collections::{BTreeMap, BTreeSet};
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::core::validation::{Diagnostics, Registered};
+use crate::hold_builders;
+use crate::item::Item;
+
+hold_builders!(
+    Recipe, ShapedRecipe, ShapelessRecipe, FurnaceRecipe, BrewingRecipe,
+    SmithingTransformRecipe, SmithingTrimRecipe
+);
+
+/// A struct for describing Recipes, written to `BP/recipes/`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Recipe {
+    #[builder(default = "SemVer::new(1, 20, 80)")]
+    pub format_version: SemVer,
+    pub id: Identifier,
+    /// Where the recipe can be made. Defaults to the station of the recipe type, e.g. `crafting_table`.
+    #[builder(default = "Vec::new()")]
+    pub tags: Vec<String>,
+    pub recipe: RecipeType
+}
+
+/// An enum for all Recipe types.
+#[derive(Clone, Debug)]
+pub enum RecipeType {
+    Shaped(ShapedRecipe),
+    Shapeless(ShapelessRecipe),
+    Furnace(FurnaceRecipe),
+    BrewingMix(BrewingRecipe),
+    BrewingContainer(BrewingRecipe),
+    SmithingTransform(SmithingTransformRecipe),
+    SmithingTrim(SmithingTrimRecipe)
+}
+
+/// An item used by a recipe, either a single item (optionally with a data value and a count) or an item tag.<br>
+/// Registered [Item]s convert into it directly, so recipes can reference them without repeating their identifiers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecipeItem {
+    Item {
+        id: Identifier,
+        data: Option<i32>,
+        count: u32
+    },
+    Tag(String)
+}
+
+/// A crafting table recipe with a pattern of up to 3x3 symbols, each mapped to an item by `key`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ShapedRecipe {
+    #[builder(setter(into = false))]
+    pattern: Vec<String>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    key: BTreeMap<char, RecipeItem>,
+    result: RecipeItem,
+    #[builder(setter(into, strip_option), default = "None")]
+    group: Option<String>,
+    #[builder(setter(into, strip_option), default = "None")]
+    priority: Option<i32>,
+    #[builder(setter(into, strip_option), default = "None")]
+    assume_symmetry: Option<bool>,
+    /// The items which unlock the recipe in the recipe book.
+    #[builder(default = "Vec::new()")]
+    unlock: Vec<RecipeItem>
+}
+
+/// A crafting table recipe without a pattern.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ShapelessRecipe {
+    #[builder(default = "Vec::new()")]
+    ingredients: Vec<RecipeItem>,
+    result: RecipeItem,
+    #[builder(setter(into, strip_option), default = "None")]
+    group: Option<String>,
+    #[builder(setter(into, strip_option), default = "None")]
+    priority: Option<i32>,
+    /// The items which unlock the recipe in the recipe book.
+    #[builder(default = "Vec::new()")]
+    unlock: Vec<RecipeItem>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct FurnaceRecipe {
+    input: RecipeItem,
+    output: RecipeItem
+}
+
+/// Used by both brewing mix and brewing container recipes.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct BrewingRecipe {
+    input: RecipeItem,
+    reagent: RecipeItem,
+    output: RecipeItem
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SmithingTransformRecipe {
+    template: RecipeItem,
+    base: RecipeItem,
+    addition: RecipeItem,
+    result: RecipeItem
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SmithingTrimRecipe {
+    template: RecipeItem,
+    base: RecipeItem,
+    addition: RecipeItem
+}
+
+impl ShapedRecipeBuilder {
+    /// Maps a pattern symbol to an item. Call it once per symbol, e.g. `key = ('#', "minecraft:stick");`.
+    pub fn key<I: Into<RecipeItem>>(&mut self, (symbol, item): (char, I)) -> &mut Self {
+        self.key.get_or_insert_with(BTreeMap::new).insert(symbol, item.into());
+        self
+    }
+}
+
+impl ShapelessRecipeBuilder {
+    pub fn ingredient(&mut self, item: impl Into<RecipeItem>) -> &mut Self {
+        self.ingredients.get_or_insert_with(Vec::new).push(item.into());
+        self
+    }
+}
+
+impl ShapedRecipe {
+    pub fn pattern(&self) -> &Vec<String> {
+        &self.pattern
+    }
+
+    pub fn key(&self) -> &BTreeMap<char, RecipeItem> {
+        &self.key
+    }
+}
+
+impl RecipeItem {
+    pub fn item(id: impl Into<Identifier>) -> Self {
+        RecipeItem::Item { id: id.into(), data: None, count: 1 }
+    }
+
+    pub fn tag(tag: impl Into<String>) -> Self {
+        RecipeItem::Tag(tag.into())
+    }
+
+    /// Sets the count of an item, tags are left as they are.
+    pub fn with_count(mut self, count: u32) -> Self {
+        if let RecipeItem::Item { count: c, .. } = &mut self {
+            *c = count;
+        }
+        self
+    }
+
+    /// Sets the data value of an item, tags are left as they are.
+    pub fn with_data(mut self, data: i32) -> Self {
+        if let RecipeItem::Item { data: d, .. } = &mut self {
+            *d = Some(data);
+        }
+        self
+    }
+
+    pub fn id(&self) -> Option<&Identifier> {
+        match &self {
+            RecipeItem::Item { id, .. } => Some(id),
+            RecipeItem::Tag(_) => None
+        }
+    }
+
+    /// The identifier string used by recipe fields which don't accept tags, data values or counts.
+    fn render_id(&self) -> Value {
+        match &self {
+            RecipeItem::Item { id, .. } => Value::from(id.render()),
+            RecipeItem::Tag(tag) => Value::from(tag.clone())
+        }
+    }
+}
+
+impl From<&str> for RecipeItem {
+    fn from(value: &str) -> Self {
+        RecipeItem::item(value)
+    }
+}
+
+impl From<Identifier> for RecipeItem {
+    fn from(value: Identifier) -> Self {
+        RecipeItem::item(value)
+    }
+}
+
+impl From<&Item> for RecipeItem {
+    fn from(value: &Item) -> Self {
+        RecipeItem::item(value.id.clone())
+    }
+}
+
+impl BedrockSerializable for RecipeItem {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            RecipeItem::Item { id, data, count } => {
+                let mut item = Map::new();
+                item.insert("item".to_string(), Value::from(id.render()));
+                if let Some(data) = data {
+                    item.insert("data".to_string(), Value::from(*data));
+                }
+                if *count != 1 {
+                    item.insert("count".to_string(), Value::from(*count));
+                }
+                Value::Object(item)
+            }
+            RecipeItem::Tag(tag) => json!({ "tag": tag })
+        })
+    }
+}
+
+impl From<ShapedRecipe> for RecipeType {
+    fn from(value: ShapedRecipe) -> Self {
+        RecipeType::Shaped(value)
+    }
+}
+
+impl From<ShapelessRecipe> for RecipeType {
+    fn from(value: ShapelessRecipe) -> Self {
+        RecipeType::Shapeless(value)
+    }
+}
+
+impl From<FurnaceRecipe> for RecipeType {
+    fn from(value: FurnaceRecipe) -> Self {
+        RecipeType::Furnace(value)
+    }
+}
+
+impl From<SmithingTransformRecipe> for RecipeType {
+    fn from(value: SmithingTransformRecipe) -> Self {
+        RecipeType::SmithingTransform(value)
+    }
+}
+
+impl From<SmithingTrimRecipe> for RecipeType {
+    fn from(value: SmithingTrimRecipe) -> Self {
+        RecipeType::SmithingTrim(value)
+    }
+}
+
+impl RecipeType {
+    /// The key of the recipe object, e.g. `minecraft:recipe_shaped`.
+    pub fn render_type(&self) -> String {
+        match &self {
+            RecipeType::Shaped(_) => "minecraft:recipe_shaped",
+            RecipeType::Shapeless(_) => "minecraft:recipe_shapeless",
+            RecipeType::Furnace(_) => "minecraft:recipe_furnace",
+            RecipeType::BrewingMix(_) => "minecraft:recipe_brewing_mix",
+            RecipeType::BrewingContainer(_) => "minecraft:recipe_brewing_container",
+            RecipeType::SmithingTransform(_) => "minecraft:recipe_smithing_transform",
+            RecipeType::SmithingTrim(_) => "minecraft:recipe_smithing_trim"
+        }.to_string()
+    }
+
+    pub fn default_tags(&self) -> Vec<String> {
+        vec![match &self {
+            RecipeType::Shaped(_) | RecipeType::Shapeless(_) => "crafting_table",
+            RecipeType::Furnace(_) => "furnace",
+            RecipeType::BrewingMix(_) | RecipeType::BrewingContainer(_) => "brewing_stand",
+            RecipeType::SmithingTransform(_) | RecipeType::SmithingTrim(_) => "smithing_table"
+        }.to_string()]
+    }
+
+    /// Every item the recipe references, including its result.
+    pub fn items(&self) -> Vec<&RecipeItem> {
+        match &self {
+            RecipeType::Shaped(r) => r.key.values().chain(r.unlock.iter()).chain(std::iter::once(&r.result)).collect(),
+            RecipeType::Shapeless(r) => r.ingredients.iter().chain(r.unlock.iter()).chain(std::iter::once(&r.result)).collect(),
+            RecipeType::Furnace(r) => vec![&r.input, &r.output],
+            RecipeType::BrewingMix(r) | RecipeType::BrewingContainer(r) => vec![&r.input, &r.reagent, &r.output],
+            RecipeType::SmithingTransform(r) => vec![&r.template, &r.base, &r.addition, &r.result],
+            RecipeType::SmithingTrim(r) => vec![&r.template, &r.base, &r.addition]
+        }
+    }
+
+    /// The items written as plain identifiers, which can't be tags or carry a data value or a count.
+    pub fn identifier_only_items(&self) -> Vec<&RecipeItem> {
+        match &self {
+            RecipeType::Furnace(r) => vec![&r.input, &r.output],
+            RecipeType::BrewingMix(r) | RecipeType::BrewingContainer(r) => vec![&r.input, &r.reagent, &r.output],
+            RecipeType::SmithingTransform(r) => vec![&r.template, &r.base, &r.addition, &r.result],
+            _ => Vec::new()
+        }
+    }
+}
+
+fn serialize_items(items: &[RecipeItem]) -> WoahResult<Value> {
+    Ok(Value::Array(items.iter().map(|i| i.bedrock_json()).collect::<WoahResult<Vec<Value>>>()?))
+}
+
+impl BedrockSerializable for RecipeType {
+    /// Only the type-specific keys, [Recipe] adds `description` and `tags`.
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            RecipeType::Shaped(r) => {
+                let mut key = Map::new();
+                for (symbol, item) in &r.key {
+                    key.insert(symbol.to_string(), item.bedrock_json()?);
+                }
+
+                let mut recipe = json!({
+                    "pattern": r.pattern,
+                    "key": key,
+                    "result": r.result.bedrock_json()?
+                });
+                if let Some(assume_symmetry) = r.assume_symmetry {
+                    recipe["assume_symmetry"] = Value::from(assume_symmetry);
+                }
+                crafting_fields(&mut recipe, &r.group, &r.priority, &r.unlock)?;
+                recipe
+            }
+            RecipeType::Shapeless(r) => {
+                let mut recipe = json!({
+                    "ingredients": serialize_items(&r.ingredients)?,
+                    "result": r.result.bedrock_json()?
+                });
+                crafting_fields(&mut recipe, &r.group, &r.priority, &r.unlock)?;
+                recipe
+            }
+            RecipeType::Furnace(r) => json!({
+                "input": r.input.render_id(),
+                "output": r.output.render_id()
+            }),
+            RecipeType::BrewingMix(r) | RecipeType::BrewingContainer(r) => json!({
+                "input": r.input.render_id(),
+                "reagent": r.reagent.render_id(),
+                "output": r.output.render_id()
+            }),
+            RecipeType::SmithingTransform(r) => json!({
+                "template": r.template.render_id(),
+                "base": r.base.render_id(),
+                "addition": r.addition.render_id(),
+                "result": r.result.render_id()
+            }),
+            RecipeType::SmithingTrim(r) => json!({
+                "template": r.template.bedrock_json()?,
+                "base": r.base.bedrock_json()?,
+                "addition": r.addition.bedrock_json()?
+            })
+        })
+    }
+}
+
+fn crafting_fields(recipe: &mut Value, group: &Option<String>, priority: &Option<i32>, unlock: &[RecipeItem]) -> WoahResult<()> {
+    if let Some(group) = group {
+        recipe["group"] = Value::from(group.clone());
+    }
+    if let Some(priority) = priority {
+        recipe["priority"] = Value::from(*priority);
+    }
+    if !unlock.is_empty() {
+        recipe["unlock"] = serialize_items(unlock)?;
+    }
+
+    Ok(())
+}
+
+impl Recipe {
+    /// The tags of the recipe, falling back to the station of its type.
+    pub fn tags(&self) -> Vec<String> {
+        if self.tags.is_empty() {
+            self.recipe.default_tags()
+        } else {
+            self.tags.clone()
+        }
+    }
+
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        for id in self.recipe.items().into_iter().filter_map(|i| i.id()) {
+            if !registered.is_known_item(id) {
+                diagnostics.error(&self.id, format!("The recipe references an unknown item `{}`", id));
+            }
+        }
+
+        for item in self.recipe.identifier_only_items() {
+            if !matches!(item, RecipeItem::Item { data: None, count: 1, .. }) {
+                diagnostics.error(&self.id, format!("`{}` only accepts plain item identifiers, found {:?}", self.recipe.render_type(), item));
+            }
+        }
+
+        if let RecipeType::Shaped(shaped) = &self.recipe {
+            check_pattern(diagnostics, &self.id, shaped.pattern(), shaped.key().keys().copied().collect());
+        }
+    }
+}
+
+/// A shaped pattern is at most 3x3, and every symbol other than a space has to be mapped by the key.
+fn check_pattern(diagnostics: &mut Diagnostics, subject: &Identifier, pattern: &[String], key: BTreeSet<char>) {
+    if pattern.is_empty() || pattern.len() > 3 || pattern.iter().any(|row| row.chars().count() > 3) {
+        diagnostics.error(subject, format!("The pattern {:?} has to be between 1x1 and 3x3", pattern));
+    }
+
+    let symbols = pattern.iter().flat_map(|row| row.chars()).filter(|c| *c != ' ').collect::<BTreeSet<char>>();
+
+    for symbol in &symbols {
+        if !key.contains(symbol) {
+            diagnostics.error(subject, format!("The pattern symbol `{}` isn't mapped by the key", symbol));
+        }
+    }
+    for symbol in &key {
+        if !symbols.contains(symbol) {
+            diagnostics.warning(subject, format!("The key symbol `{}` isn't used by the pattern", symbol));
+        }
+    }
+}
+
+impl BedrockSerializable for Recipe {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut body = Map::new();
+        body.insert("description".to_string(), json!({ "identifier": self.id.render() }));
+        body.insert("tags".to_string(), Value::from(self.tags()));
+
+        if let Value::Object(recipe) = self.recipe.bedrock_json()? {
+            body.extend(recipe);
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            self.recipe.render_type(): body
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn shaped_recipe() {
+        let memory = testing::build(|events| {
+            events.recipe_registration.subscribe(|reg| {
+                reg.register_recipe(woah! {
+                    @Recipe {
+                        id = "woah:ladder_from_sticks";
+                        recipe = @ShapedRecipe {
+                            pattern = map vec!["##", "##"];
+                            key = ('#', "minecraft:stick");
+                            result = RecipeItem::item("minecraft:ladder").with_count(2);
+                        };
+                    }
+                });
+            });
+        });
+
+        let recipe = memory.read_json("WoahTest_BP/recipes/woah_ladder_from_sticks.json").unwrap();
+        assert_eq!(recipe["minecraft:recipe_shaped"]["tags"], json!(["crafting_table"]));
+        assert_eq!(recipe["minecraft:recipe_shaped"]["key"]["#"], json!({ "item": "minecraft:stick" }));
+        assert_eq!(recipe["minecraft:recipe_shaped"]["result"], json!({ "item": "minecraft:ladder", "count": 2 }));
+    }
+}
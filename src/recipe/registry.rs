@@ -0,0 +1,17 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::recipe::Recipe;
+
+/// The recipe registry. Used for registering Recipes.
+pub struct RecipeRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl RecipeRegistry {
+    pub fn register_recipe(&self, recipe: Recipe) {
+        info!("@recipe Registering recipe {}", &recipe.id);
+
+        self.registry.register_recipe(recipe);
+    }
+}
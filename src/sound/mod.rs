@@ -0,0 +1,520 @@
+pub mod registry;
+
+use std::collections::{BTreeMap, HashSet};
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::audio::Audio;
+use crate::core::error::WoahResult;
+use crate::core::utilities::Identifier;
+use crate::core::validation::{check_molang, Diagnostics, Registered};
+use crate::core::vanilla;
+use crate::hold_builders;
+use crate::molang::Molang;
+
+hold_builders!(SoundDefinition, Sound, SoundEvents, BlockSounds, EntitySounds);
+
+/// A struct for describing sound definitions, written to `RP/sounds/sound_definitions.json`.<br>
+/// Every [Sound] is a variant, the game picks one of them at random each time the definition is played.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SoundDefinition {
+    /// The name events play the sound by, e.g. `mob.woah.cow.moo`.
+    pub id: String,
+    #[builder(default = "SoundCategory::Neutral")]
+    pub category: SoundCategory,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub min_distance: Option<f64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub max_distance: Option<f64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub subtitle: Option<String>,
+    #[builder(default = "Vec::new()")]
+    pub sounds: Vec<Sound>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Sound {
+    pub source: SoundSource,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub volume: Option<f64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub pitch: Option<f64>,
+    /// How likely this variant is picked, relative to the others.
+    #[builder(setter(into = false, strip_option), default = "None")]
+    pub weight: Option<u32>,
+    /// Long sounds like music should be streamed instead of being loaded at once.
+    #[builder(default = "false")]
+    pub stream: bool,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub load_on_low_memory: Option<bool>
+}
+
+/// Either a file shipped with the pack, or a path to a sound which is already in the game, like `sounds/mob/cow/say1`.
+#[derive(Clone, Debug)]
+pub enum SoundSource {
+    Audio(Audio),
+    Path(String)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundCategory {
+    Ambient,
+    Weather,
+    Block,
+    Hostile,
+    Player,
+    Neutral,
+    Bucket,
+    Record,
+    Ui,
+    Music
+}
+
+/// A volume or pitch, either fixed or picked at random between the bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SoundRange {
+    Constant(f64),
+    Range(f64, f64)
+}
+
+/// A sound played by an event, e.g. `ambient` or `step`. An empty sound silences the event.
+#[derive(Clone, Debug)]
+pub struct SoundEvent {
+    pub sound: String,
+    pub volume: Option<SoundRange>,
+    pub pitch: Option<SoundRange>
+}
+
+/// Events mapped to the sounds they play, with the volume and pitch they default to.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SoundEvents {
+    #[builder(setter(into, strip_option), default = "None")]
+    pub volume: Option<SoundRange>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub pitch: Option<SoundRange>,
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub events: BTreeMap<String, SoundEvent>
+}
+
+/// A set of block sounds, written to `block_sounds` of `RP/sounds.json`. Blocks use it through
+/// [SoundRegistry::bind_block_sound](registry::SoundRegistry::bind_block_sound).
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct BlockSounds {
+    /// The name blocks refer to, like the vanilla `stone` or `wood`.
+    pub name: String,
+    pub sounds: SoundEvents
+}
+
+/// The sounds of an entity, written to `entity_sounds` of `RP/sounds.json`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct EntitySounds {
+    pub id: Identifier,
+    pub sounds: SoundEvents,
+    /// Molang returning the name of the variant to use, e.g. `q.property('woah:color')`.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub variant_key: Option<Molang>,
+    /// Variants overriding some of the events, selected by [EntitySounds::variant_key].
+    #[builder(setter(custom), default = "BTreeMap::new()")]
+    pub variants: BTreeMap<String, SoundEvents>
+}
+
+/// Everything the sound registry collected for a pack.
+#[derive(Clone, Debug, Default)]
+pub struct Sounds {
+    pub definitions: Vec<SoundDefinition>,
+    pub block_sounds: Vec<BlockSounds>,
+    pub entity_sounds: Vec<EntitySounds>,
+    /// Block identifiers mapped to the block sounds they use, written to `RP/blocks.json`.
+    pub block_bindings: BTreeMap<String, String>
+}
+
+impl SoundEventsBuilder {
+    /// Binds a sound to an event, e.g. `event = ("ambient", SoundEvent::new("mob.woah.cow.moo"));`.
+    pub fn event<N: Into<String>, E: Into<SoundEvent>>(&mut self, (name, event): (N, E)) -> &mut Self {
+        self.events.get_or_insert_with(BTreeMap::new).insert(name.into(), event.into());
+        self
+    }
+}
+
+impl EntitySoundsBuilder {
+    /// Adds a variant, e.g. `variant = ("red", @SoundEvents { ... });`.
+    pub fn variant<N: Into<String>>(&mut self, (name, sounds): (N, SoundEvents)) -> &mut Self {
+        self.variants.get_or_insert_with(BTreeMap::new).insert(name.into(), sounds);
+        self
+    }
+}
+
+impl SoundDefinition {
+    /// The path of a shipped variant relative to the RP, without the extension as the definition references it.
+    pub fn sound_path(&self, pack_name: &str, index: usize) -> String {
+        format!("sounds/{}/{}_{}", pack_name, self.id.replace('.', "_"), index + 1)
+    }
+
+    /// The shipped files of the definition, with the index they're referenced by.
+    pub fn audio(&self) -> Vec<(usize, &Audio)> {
+        self.sounds.iter().enumerate().filter_map(|(i, sound)| match &sound.source {
+            SoundSource::Audio(audio) => Some((i, audio)),
+            SoundSource::Path(_) => None
+        }).collect()
+    }
+
+    /// The `sound_definitions` entry of the pack named `pack_name`. Shipped files live in a folder named after the pack, so this isn't [BedrockSerializable].
+    pub fn bedrock_json(&self, pack_name: &str) -> WoahResult<Value> {
+        let mut definition = Map::new();
+        definition.insert("category".to_string(), Value::from(self.category.render()));
+
+        if let Some(min_distance) = self.min_distance {
+            definition.insert("min_distance".to_string(), json!(min_distance));
+        }
+        if let Some(max_distance) = self.max_distance {
+            definition.insert("max_distance".to_string(), json!(max_distance));
+        }
+        if let Some(subtitle) = &self.subtitle {
+            definition.insert("subtitle".to_string(), Value::from(subtitle.clone()));
+        }
+
+        let sounds = self.sounds.iter().enumerate().map(|(i, sound)| {
+            let name = match &sound.source {
+                SoundSource::Audio(_) => self.sound_path(pack_name, i),
+                SoundSource::Path(path) => path.clone()
+            };
+
+            let mut entry = Map::new();
+            entry.insert("name".to_string(), Value::from(name));
+            for (key, value) in [("volume", sound.volume), ("pitch", sound.pitch)] {
+                if let Some(value) = value {
+                    entry.insert(key.to_string(), json!(value));
+                }
+            }
+            if let Some(weight) = sound.weight {
+                entry.insert("weight".to_string(), json!(weight));
+            }
+            if sound.stream {
+                entry.insert("stream".to_string(), Value::Bool(true));
+            }
+            if let Some(load) = sound.load_on_low_memory {
+                entry.insert("load_on_low_memory".to_string(), Value::Bool(load));
+            }
+
+            Value::Object(entry)
+        }).collect::<Vec<Value>>();
+        definition.insert("sounds".to_string(), Value::from(sounds));
+
+        Ok(json!({ &self.id: definition }))
+    }
+}
+
+impl SoundCategory {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            SoundCategory::Ambient => "ambient",
+            SoundCategory::Weather => "weather",
+            SoundCategory::Block => "block",
+            SoundCategory::Hostile => "hostile",
+            SoundCategory::Player => "player",
+            SoundCategory::Neutral => "neutral",
+            SoundCategory::Bucket => "bucket",
+            SoundCategory::Record => "record",
+            SoundCategory::Ui => "ui",
+            SoundCategory::Music => "music"
+        }
+    }
+}
+
+impl SoundEvent {
+    pub fn new(sound: impl Into<String>) -> Self {
+        Self { sound: sound.into(), volume: None, pitch: None }
+    }
+
+    pub fn with_volume(mut self, volume: impl Into<SoundRange>) -> Self {
+        self.volume = Some(volume.into());
+        self
+    }
+
+    pub fn with_pitch(mut self, pitch: impl Into<SoundRange>) -> Self {
+        self.pitch = Some(pitch.into());
+        self
+    }
+}
+
+impl From<&str> for SoundEvent {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for SoundEvent {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Audio> for SoundSource {
+    fn from(value: Audio) -> Self {
+        SoundSource::Audio(value)
+    }
+}
+
+impl From<&str> for SoundSource {
+    fn from(value: &str) -> Self {
+        SoundSource::Path(value.to_string())
+    }
+}
+
+impl From<f64> for SoundRange {
+    fn from(value: f64) -> Self {
+        SoundRange::Constant(value)
+    }
+}
+
+impl From<std::ops::RangeInclusive<f64>> for SoundRange {
+    fn from(value: std::ops::RangeInclusive<f64>) -> Self {
+        SoundRange::Range(*value.start(), *value.end())
+    }
+}
+
+impl SoundEvents {
+    /// Every sound definition the events play, including silenced events.
+    pub fn sounds(&self) -> Vec<&String> {
+        self.events.values().map(|e| &e.sound).collect()
+    }
+}
+
+impl EntitySounds {
+    pub fn sounds(&self) -> Vec<&String> {
+        std::iter::once(&self.sounds).chain(self.variants.values()).flat_map(|s| s.sounds()).collect()
+    }
+}
+
+impl Sounds {
+    /// Events may only play registered or vanilla sound definitions, and blocks may only use registered or vanilla block sounds.
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, registered: &Registered) {
+        let mut definitions = HashSet::new();
+
+        for definition in &self.definitions {
+            if !definitions.insert(&definition.id) {
+                diagnostics.error(&definition.id, "The sound definition is registered more than once");
+            }
+            if definition.sounds.is_empty() {
+                diagnostics.warning(&definition.id, "The sound definition has no sounds");
+            }
+        }
+
+        let is_known_sound = |sound: &String| sound.is_empty() || definitions.contains(sound) || vanilla::SOUND_DEFINITIONS.contains(&sound.as_str());
+
+        let mut block_sounds = HashSet::new();
+
+        for set in &self.block_sounds {
+            if !block_sounds.insert(&set.name) {
+                diagnostics.error(&set.name, "The block sounds are registered more than once");
+            }
+
+            for sound in set.sounds.sounds() {
+                if !is_known_sound(sound) {
+                    diagnostics.error(&set.name, format!("The block sounds play an unregistered sound `{}`", sound));
+                }
+            }
+        }
+
+        for entity in &self.entity_sounds {
+            if !registered.is_known_entity(&entity.id) {
+                diagnostics.error(&entity.id, "The entity sounds have no matching registered entity");
+            }
+
+            for sound in entity.sounds() {
+                if !is_known_sound(sound) {
+                    diagnostics.error(&entity.id, format!("The entity sounds play an unregistered sound `{}`", sound));
+                }
+            }
+
+            match &entity.variant_key {
+                Some(key) => check_molang(diagnostics, &entity.id, key),
+                None if !entity.variants.is_empty() => diagnostics.warning(&entity.id, "The sound variants are ignored without a variant key"),
+                None => {}
+            }
+        }
+
+        for (block, set) in &self.block_bindings {
+            if !registered.blocks.contains(block) {
+                diagnostics.error(block, "Block sounds are bound to an unregistered block");
+            }
+            if !block_sounds.contains(set) && !vanilla::BLOCK_SOUNDS.contains(&set.as_str()) {
+                diagnostics.error(block, format!("The block uses unregistered block sounds `{}`", set));
+            }
+        }
+    }
+}
+
+impl BedrockSerializable for SoundRange {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            SoundRange::Constant(value) => json!(value),
+            SoundRange::Range(min, max) => json!([min, max])
+        })
+    }
+}
+
+impl BedrockSerializable for SoundEvent {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        if self.volume.is_none() && self.pitch.is_none() {
+            return Ok(Value::from(self.sound.clone()));
+        }
+
+        let mut event = Map::new();
+        event.insert("sound".to_string(), Value::from(self.sound.clone()));
+        if let Some(volume) = &self.volume {
+            event.insert("volume".to_string(), volume.bedrock_json()?);
+        }
+        if let Some(pitch) = &self.pitch {
+            event.insert("pitch".to_string(), pitch.bedrock_json()?);
+        }
+
+        Ok(Value::Object(event))
+    }
+}
+
+impl BedrockSerializable for SoundEvents {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut sounds = Map::new();
+        if let Some(volume) = &self.volume {
+            sounds.insert("volume".to_string(), volume.bedrock_json()?);
+        }
+        if let Some(pitch) = &self.pitch {
+            sounds.insert("pitch".to_string(), pitch.bedrock_json()?);
+        }
+
+        let mut events = Map::new();
+        for (name, event) in &self.events {
+            events.insert(name.clone(), event.bedrock_json()?);
+        }
+        sounds.insert("events".to_string(), Value::Object(events));
+
+        Ok(Value::Object(sounds))
+    }
+}
+
+impl BedrockSerializable for BlockSounds {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(json!({ &self.name: self.sounds.bedrock_json()? }))
+    }
+}
+
+impl BedrockSerializable for EntitySounds {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut sounds = self.sounds.bedrock_json()?;
+
+        if let (Some(key), Value::Object(entry)) = (&self.variant_key, &mut sounds) {
+            let mut variants = Map::new();
+            for (name, variant) in &self.variants {
+                variants.insert(name.clone(), variant.bedrock_json()?);
+            }
+
+            entry.insert("variants".to_string(), json!({ "key": key, "map": variants }));
+        }
+
+        Ok(json!({ self.id.render(): sounds }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use crate::block::Block;
+    use crate::core::testing;
+    use crate::entity::Entity;
+    use crate::woah;
+
+    #[test]
+    fn sounds() {
+        let memory = testing::build(|events| {
+            events.block_registration.subscribe(|reg| {
+                reg.register_block(woah! {
+                    @Block {
+                        id = "woah:block";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.entity_registration.subscribe(|reg| {
+                reg.register_entity(woah! {
+                    @Entity {
+                        id = "cool:entity";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.sound_registration.subscribe(|reg| {
+                reg.register_sound(woah! {
+                    @SoundDefinition {
+                        id = "mob.cool.entity.ambient";
+                        category = SoundCategory::Neutral;
+                        sounds = vec![
+                            @Sound {
+                                source = Audio::from_bytes(b"OggS\0\x02".to_vec()).unwrap();
+                                pitch = 1.2;
+                            },
+                            @Sound {
+                                source = "sounds/mob/cow/say1";
+                                weight = 2;
+                            }
+                        ];
+                    }
+                });
+                reg.register_entity_sounds(woah! {
+                    @EntitySounds {
+                        id = "cool:entity";
+                        sounds = @SoundEvents {
+                            volume = 0.8;
+                            event = ("ambient", SoundEvent::new("mob.cool.entity.ambient").with_pitch(0.8..=1.2));
+                            event = ("hurt", "mob.cow.hurt");
+                        };
+                    }
+                });
+                reg.register_block_sounds(woah! {
+                    @BlockSounds {
+                        name = "cool_block";
+                        sounds = @SoundEvents {
+                            event = ("break", "mob.cool.entity.ambient");
+                        };
+                    }
+                });
+                reg.bind_block_sound("woah:block", "cool_block");
+            });
+        });
+
+        assert_eq!(memory.read("WoahTest_RP/sounds/WoahTest/mob_cool_entity_ambient_1.ogg").unwrap(), b"OggS\0\x02".to_vec());
+        assert_eq!(memory.read_json("WoahTest_RP/sounds/sound_definitions.json").unwrap()["sound_definitions"], json!({
+            "mob.cool.entity.ambient": {
+                "category": "neutral",
+                "sounds": [
+                    { "name": "sounds/WoahTest/mob_cool_entity_ambient_1", "pitch": 1.2 },
+                    { "name": "sounds/mob/cow/say1", "weight": 2 }
+                ]
+            }
+        }));
+        assert_eq!(memory.read_json("WoahTest_RP/sounds.json").unwrap(), json!({
+            "block_sounds": {
+                "cool_block": { "events": { "break": "mob.cool.entity.ambient" } }
+            },
+            "entity_sounds": {
+                "entities": {
+                    "cool:entity": {
+                        "volume": 0.8,
+                        "events": {
+                            "ambient": { "sound": "mob.cool.entity.ambient", "pitch": [0.8, 1.2] },
+                            "hurt": "mob.cow.hurt"
+                        }
+                    }
+                }
+            }
+        }));
+        assert_eq!(memory.read_json("WoahTest_RP/blocks.json").unwrap()["woah:block"], json!({ "sound": "cool_block" }));
+    }
+}
@@ -0,0 +1,39 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::core::utilities::Identifier;
+use crate::sound::{BlockSounds, EntitySounds, SoundDefinition};
+
+/// The sound registry. Used for registering Sound definitions and binding them to blocks and entities, all of which end up in the RP.
+pub struct SoundRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl SoundRegistry {
+    /// Registers a sound definition. Its shipped files are written to `RP/sounds/<pack>/`.
+    pub fn register_sound(&self, definition: SoundDefinition) {
+        info!("@sound Registering sound {}", &definition.id);
+
+        self.registry.register_sound(definition);
+    }
+
+    pub fn register_block_sounds(&self, sounds: BlockSounds) {
+        info!("@sound Registering block sounds {}", &sounds.name);
+
+        self.registry.register_block_sounds(sounds);
+    }
+
+    pub fn register_entity_sounds(&self, sounds: EntitySounds) {
+        info!("@sound Registering entity sounds {}", &sounds.id);
+
+        self.registry.register_entity_sounds(sounds);
+    }
+
+    /// Makes a block use a set of block sounds, either registered or vanilla like `stone`.
+    pub fn bind_block_sound(&self, block: impl Into<Identifier>, sounds: impl Into<String>) {
+        let (block, sounds) = (block.into(), sounds.into());
+        info!("@sound Binding block sounds {} to {}", &sounds, &block);
+
+        self.registry.bind_block_sound(block, sounds);
+    }
+}
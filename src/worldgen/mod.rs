@@ -0,0 +1,430 @@
+pub mod rule;
+pub mod ore;
+pub mod registry;
+
+use std::collections::{BTreeMap, HashSet};
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::block::Block;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::core::validation::{check_biome_filter, Diagnostics, Registered};
+use crate::core::vanilla;
+use crate::hold_builders;
+use crate::worldgen::rule::{Distribution, FeatureRule};
+
+hold_builders!(
+    Feature, OreFeature, ReplaceRule, ScatterFeature, SingleBlockFeature,
+    AggregateFeature, SequenceFeature, WeightedRandomFeature
+);
+
+/// A worldgen feature, written to `BP/features/`. Features only describe what is placed,
+/// a [FeatureRule](rule::FeatureRule) decides where and how often.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Feature {
+    #[builder(default = "SemVer::new(1, 21, 40)")]
+    pub format_version: SemVer,
+    /// The file is named after the path of the identifier, so it has to be unique across namespaces.
+    pub id: Identifier,
+    pub feature: FeatureType
+}
+
+/// An enum for the supported Feature types.
+#[derive(Clone, Debug)]
+pub enum FeatureType {
+    Ore(OreFeature),
+    Scatter(Box<ScatterFeature>),
+    SingleBlock(SingleBlockFeature),
+    Aggregate(AggregateFeature),
+    Sequence(SequenceFeature),
+    WeightedRandom(WeightedRandomFeature)
+}
+
+/// A block placed or matched by a feature, optionally with block states, e.g. `minecraft:stone` or
+/// `{ "name": "minecraft:deepslate", "states": { "pillar_axis": "y" } }`.<br>
+/// Registered [Block]s convert into it directly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockReference {
+    pub name: Identifier,
+    pub states: BTreeMap<String, Value>
+}
+
+/// Places veins of blocks, replacing the blocks matched by each rule.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct OreFeature {
+    /// The number of blocks in a vein.
+    #[builder(setter(into = false))]
+    pub count: u32,
+    #[builder(default = "Vec::new()")]
+    pub replace_rules: Vec<ReplaceRule>,
+    /// The chance, from 0 to 1, of skipping a block touching air.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub discard_chance_on_air_exposure: Option<f64>
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ReplaceRule {
+    pub places_block: BlockReference,
+    /// Any block is replaced when empty.
+    #[builder(default = "Vec::new()")]
+    pub may_replace: Vec<BlockReference>
+}
+
+/// Places another feature several times around the input position.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct ScatterFeature {
+    pub places_feature: Identifier,
+    pub distribution: Distribution,
+    /// Snaps the scattered positions to the terrain height.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub project_input_to_floor: Option<bool>
+}
+
+/// Places a single block.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SingleBlockFeature {
+    pub places_block: BlockReference,
+    /// Any block is replaced when empty.
+    #[builder(default = "Vec::new()")]
+    pub may_replace: Vec<BlockReference>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub randomize_rotation: Option<bool>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub enforce_survivability_rules: Option<bool>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub enforce_placement_rules: Option<bool>
+}
+
+/// Places every feature at the same input position, in any order.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct AggregateFeature {
+    #[builder(setter(into = false))]
+    pub features: Vec<Identifier>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub early_out: Option<EarlyOut>
+}
+
+/// Places the features in order, each one at the position the previous one ended at.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct SequenceFeature {
+    #[builder(setter(into = false))]
+    pub features: Vec<Identifier>
+}
+
+/// Places one of the features, picked by their relative weights.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct WeightedRandomFeature {
+    #[builder(setter(custom), default = "Vec::new()")]
+    pub features: Vec<(Identifier, u32)>
+}
+
+/// When an [AggregateFeature] stops placing its features.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EarlyOut {
+    None,
+    FirstFailure,
+    FirstSuccess
+}
+
+impl WeightedRandomFeatureBuilder {
+    /// Adds a feature with its weight, e.g. `feature = ("woah:big_tree_feature", 3);`.
+    pub fn feature<F: Into<Identifier>>(&mut self, (feature, weight): (F, u32)) -> &mut Self {
+        self.features.get_or_insert_with(Vec::new).push((feature.into(), weight));
+        self
+    }
+}
+
+impl Feature {
+    /// The name of the feature file, `woah:ruby_ore_feature` is written to `features/ruby_ore_feature.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.json", self.id.path())
+    }
+}
+
+/// Features and feature rules may only place registered or vanilla features and blocks.
+pub(crate) fn validate_features(diagnostics: &mut Diagnostics, features: &[Feature], rules: &[FeatureRule], registered: &Registered) {
+    let feature_ids = features.iter().map(|f| f.id.render()).collect::<HashSet<String>>();
+    let is_known_feature = |id: &Identifier| feature_ids.contains(&id.render()) || vanilla::FEATURES.contains(&id.render().as_str());
+
+    for feature in features {
+        for placed in feature.feature.features() {
+            if !is_known_feature(placed) {
+                diagnostics.error(&feature.id, format!("The feature places an unknown feature `{}`", placed));
+            }
+        }
+
+        for block in feature.feature.blocks() {
+            if !registered.is_known_block(&block.name) {
+                diagnostics.error(&feature.id, format!("The feature references an unknown block `{}`", block.name));
+            }
+        }
+
+        if let FeatureType::Scatter(scatter) = &feature.feature {
+            scatter.distribution.validate(diagnostics, &feature.id);
+        }
+    }
+
+    for rule in rules {
+        if !is_known_feature(&rule.places_feature) {
+            diagnostics.error(&rule.id, format!("The feature rule places an unknown feature `{}`", rule.places_feature));
+        }
+
+        rule.distribution.validate(diagnostics, &rule.id);
+
+        if let Some(filter) = &rule.biome_filter {
+            check_biome_filter(diagnostics, &rule.id, filter, registered);
+        }
+    }
+}
+
+impl FeatureType {
+    /// The key of the feature object, e.g. `minecraft:ore_feature`.
+    pub fn render_type(&self) -> String {
+        match &self {
+            FeatureType::Ore(_) => "minecraft:ore_feature",
+            FeatureType::Scatter(_) => "minecraft:scatter_feature",
+            FeatureType::SingleBlock(_) => "minecraft:single_block_feature",
+            FeatureType::Aggregate(_) => "minecraft:aggregate_feature",
+            FeatureType::Sequence(_) => "minecraft:sequence_feature",
+            FeatureType::WeightedRandom(_) => "minecraft:weighted_random_feature"
+        }.to_string()
+    }
+
+    /// Every block the feature places or matches.
+    pub fn blocks(&self) -> Vec<&BlockReference> {
+        match &self {
+            FeatureType::Ore(f) => f.replace_rules.iter().flat_map(|r| std::iter::once(&r.places_block).chain(r.may_replace.iter())).collect(),
+            FeatureType::SingleBlock(f) => std::iter::once(&f.places_block).chain(f.may_replace.iter()).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Every feature placed by this one.
+    pub fn features(&self) -> Vec<&Identifier> {
+        match &self {
+            FeatureType::Scatter(f) => vec![&f.places_feature],
+            FeatureType::Aggregate(f) => f.features.iter().collect(),
+            FeatureType::Sequence(f) => f.features.iter().collect(),
+            FeatureType::WeightedRandom(f) => f.features.iter().map(|(id, _)| id).collect(),
+            _ => Vec::new()
+        }
+    }
+}
+
+impl BlockReference {
+    pub fn new(name: impl Into<Identifier>) -> Self {
+        Self { name: name.into(), states: BTreeMap::new() }
+    }
+
+    pub fn with_state(mut self, state: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.states.insert(state.into(), value.into());
+        self
+    }
+}
+
+impl EarlyOut {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            EarlyOut::None => "none",
+            EarlyOut::FirstFailure => "first_failure",
+            EarlyOut::FirstSuccess => "first_success"
+        }
+    }
+}
+
+impl From<&str> for BlockReference {
+    fn from(value: &str) -> Self {
+        BlockReference::new(value)
+    }
+}
+
+impl From<Identifier> for BlockReference {
+    fn from(value: Identifier) -> Self {
+        BlockReference::new(value)
+    }
+}
+
+impl From<&Block> for BlockReference {
+    fn from(value: &Block) -> Self {
+        BlockReference::new(value.id.clone())
+    }
+}
+
+impl From<OreFeature> for FeatureType {
+    fn from(value: OreFeature) -> Self {
+        FeatureType::Ore(value)
+    }
+}
+
+impl From<ScatterFeature> for FeatureType {
+    fn from(value: ScatterFeature) -> Self {
+        FeatureType::Scatter(Box::new(value))
+    }
+}
+
+impl From<SingleBlockFeature> for FeatureType {
+    fn from(value: SingleBlockFeature) -> Self {
+        FeatureType::SingleBlock(value)
+    }
+}
+
+impl From<AggregateFeature> for FeatureType {
+    fn from(value: AggregateFeature) -> Self {
+        FeatureType::Aggregate(value)
+    }
+}
+
+impl From<SequenceFeature> for FeatureType {
+    fn from(value: SequenceFeature) -> Self {
+        FeatureType::Sequence(value)
+    }
+}
+
+impl From<WeightedRandomFeature> for FeatureType {
+    fn from(value: WeightedRandomFeature) -> Self {
+        FeatureType::WeightedRandom(value)
+    }
+}
+
+fn serialize_blocks(blocks: &[BlockReference]) -> WoahResult<Value> {
+    Ok(Value::Array(blocks.iter().map(|b| b.bedrock_json()).collect::<WoahResult<Vec<Value>>>()?))
+}
+
+fn render_features(features: &[Identifier]) -> Value {
+    Value::from(features.iter().map(|f| f.render()).collect::<Vec<String>>())
+}
+
+impl BedrockSerializable for BlockReference {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(if self.states.is_empty() {
+            Value::from(self.name.render())
+        } else {
+            json!({ "name": self.name.render(), "states": self.states })
+        })
+    }
+}
+
+impl BedrockSerializable for ReplaceRule {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut rule = Map::new();
+        rule.insert("places_block".to_string(), self.places_block.bedrock_json()?);
+
+        if !self.may_replace.is_empty() {
+            rule.insert("may_replace".to_string(), serialize_blocks(&self.may_replace)?);
+        }
+
+        Ok(Value::Object(rule))
+    }
+}
+
+impl BedrockSerializable for FeatureType {
+    /// Only the type-specific keys, [Feature] adds `description`.
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut feature = Map::new();
+
+        match &self {
+            FeatureType::Ore(f) => {
+                feature.insert("count".to_string(), json!(f.count));
+                feature.insert("replace_rules".to_string(), Value::Array(
+                    f.replace_rules.iter().map(|r| r.bedrock_json()).collect::<WoahResult<Vec<Value>>>()?
+                ));
+                if let Some(chance) = f.discard_chance_on_air_exposure {
+                    feature.insert("discard_chance_on_air_exposure".to_string(), json!(chance));
+                }
+            }
+            FeatureType::Scatter(f) => {
+                feature.insert("places_feature".to_string(), Value::from(f.places_feature.render()));
+                feature.insert("distribution".to_string(), f.distribution.bedrock_json()?);
+                if let Some(project) = f.project_input_to_floor {
+                    feature.insert("project_input_to_floor".to_string(), Value::from(project));
+                }
+            }
+            FeatureType::SingleBlock(f) => {
+                feature.insert("places_block".to_string(), f.places_block.bedrock_json()?);
+                if !f.may_replace.is_empty() {
+                    feature.insert("may_replace".to_string(), serialize_blocks(&f.may_replace)?);
+                }
+                for (key, flag) in [
+                    ("randomize_rotation", f.randomize_rotation),
+                    ("enforce_survivability_rules", f.enforce_survivability_rules),
+                    ("enforce_placement_rules", f.enforce_placement_rules)
+                ] {
+                    if let Some(flag) = flag {
+                        feature.insert(key.to_string(), Value::from(flag));
+                    }
+                }
+            }
+            FeatureType::Aggregate(f) => {
+                feature.insert("features".to_string(), render_features(&f.features));
+                if let Some(early_out) = f.early_out {
+                    feature.insert("early_out".to_string(), Value::from(early_out.render()));
+                }
+            }
+            FeatureType::Sequence(f) => {
+                feature.insert("features".to_string(), render_features(&f.features));
+            }
+            FeatureType::WeightedRandom(f) => {
+                feature.insert("features".to_string(), Value::Array(
+                    f.features.iter().map(|(id, weight)| json!([id.render(), weight])).collect()
+                ));
+            }
+        }
+
+        Ok(Value::Object(feature))
+    }
+}
+
+impl BedrockSerializable for Feature {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut feature = Map::new();
+        feature.insert("description".to_string(), json!({ "identifier": self.id.render() }));
+
+        if let Value::Object(fields) = self.feature.bedrock_json()? {
+            feature.extend(fields);
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            self.feature.render_type(): feature
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn aggregate_feature() {
+        let memory = testing::build(|events| {
+            events.worldgen_registration.subscribe(|reg| {
+                reg.register_feature(woah! {
+                    @Feature {
+                        id = "woah:ores_feature";
+                        feature = @AggregateFeature {
+                            features = map vec!["minecraft:coal_ore_feature", "minecraft:copper_ore_feature"];
+                            early_out = EarlyOut::FirstSuccess;
+                        };
+                    }
+                });
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_BP/features/ores_feature.json").unwrap()["minecraft:aggregate_feature"], json!({
+            "description": { "identifier": "woah:ores_feature" },
+            "features": ["minecraft:coal_ore_feature", "minecraft:copper_ore_feature"],
+            "early_out": "first_success"
+        }));
+    }
+}
@@ -0,0 +1,146 @@
+use std::ops::RangeInclusive;
+use derive_builder::Builder;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::entity::filter::EntityFilter;
+use crate::hold_builders;
+use crate::molang::ast::MolangExpr;
+use crate::molang::Molang;
+use crate::worldgen::{BlockReference, Feature, FeatureType, OreFeature, ReplaceRule};
+use crate::worldgen::rule::{Coordinate, Distribution, FeatureRule, PlacementPass};
+
+hold_builders!(Ore);
+
+/// A high-level ore, generating veins of a block underground. Register it with
+/// [register_ore](crate::worldgen::registry::WorldgenRegistry::register_ore), which writes both the [Feature] and the [FeatureRule].<br>
+/// For `woah:ruby_ore`, they're named `woah:ruby_ore_feature` and `woah:ruby_ore_feature_rule`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Ore {
+    /// The ore block, usually a registered [Block](crate::block::Block).
+    pub block: BlockReference,
+    #[builder(setter(into = false), default = "vec![BlockReference::new(\"minecraft:stone\")]")]
+    pub replaces: Vec<BlockReference>,
+    /// The number of blocks in a vein.
+    #[builder(setter(into = false))]
+    pub vein_size: u32,
+    /// The heights veins generate at, e.g. `-16..=48`.
+    pub height: RangeInclusive<i32>,
+    #[builder(setter(into = false), default = "8")]
+    pub veins_per_chunk: u32,
+    /// Generates in every biome when not set.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub biome_filter: Option<EntityFilter>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub discard_chance_on_air_exposure: Option<f64>
+}
+
+impl Ore {
+    pub fn feature_id(&self) -> Identifier {
+        Identifier::new(self.block.name.namespace(), format!("{}_feature", self.block.name.path()))
+    }
+
+    pub fn feature_rule_id(&self) -> Identifier {
+        Identifier::new(self.block.name.namespace(), format!("{}_feature_rule", self.block.name.path()))
+    }
+
+    pub fn feature(&self) -> Feature {
+        Feature {
+            format_version: SemVer::new(1, 21, 40),
+            id: self.feature_id(),
+            feature: FeatureType::Ore(OreFeature {
+                count: self.vein_size,
+                replace_rules: vec![ReplaceRule { places_block: self.block.clone(), may_replace: self.replaces.clone() }],
+                discard_chance_on_air_exposure: self.discard_chance_on_air_exposure
+            })
+        }
+    }
+
+    /// Places the veins uniformly within the chunk and the height range, in the underground pass.
+    pub fn feature_rule(&self) -> FeatureRule {
+        FeatureRule {
+            format_version: SemVer::new(1, 13, 0),
+            id: self.feature_rule_id(),
+            places_feature: self.feature_id(),
+            placement_pass: PlacementPass::Underground,
+            biome_filter: self.biome_filter.clone(),
+            distribution: Distribution {
+                iterations: Molang::from_expr(MolangExpr::Number(self.veins_per_chunk as f64)),
+                scatter_chance: None,
+                coordinate_eval_order: None,
+                x: Some(Coordinate::uniform(0..=16)),
+                y: Some(Coordinate::uniform(self.height.clone())),
+                z: Some(Coordinate::uniform(0..=16))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eo::sjson;
+    use eo::sjson::{SJsonElement, TransformHashMap};
+    use serde_json::json;
+    use crate::biome::{Biome, BiomeTag};
+    use crate::block::Block;
+    use crate::core::testing;
+    use crate::woah;
+
+    #[test]
+    fn ore_feature_and_rule() {
+        let memory = testing::build(|events| {
+            events.block_registration.subscribe(|reg| {
+                reg.register_block(woah! {
+                    @Block {
+                        id = "woah:block";
+                        components = sjson! {};
+                    }
+                })
+            });
+            events.biome_registration.subscribe(|reg| {
+                reg.register_biome(woah! {
+                    @Biome {
+                        id = "woah:crystal_caves";
+                        tags = map vec!["overworld", "woah_crystal"];
+                    }
+                });
+            });
+            events.worldgen_registration.subscribe(|reg| {
+                reg.register_ore(woah! {
+                    @Ore {
+                        block = "woah:block";
+                        replaces = map vec!["minecraft:stone", "minecraft:deepslate"];
+                        vein_size = 6;
+                        height = -16..=48;
+                        biome_filter = BiomeTag::new("woah_crystal");
+                    }
+                });
+            });
+        });
+
+        assert_eq!(memory.read_json("WoahTest_BP/features/block_feature.json").unwrap(), json!({
+            "format_version": "1.21.40",
+            "minecraft:ore_feature": {
+                "description": { "identifier": "woah:block_feature" },
+                "count": 6,
+                "replace_rules": [{ "places_block": "woah:block", "may_replace": ["minecraft:stone", "minecraft:deepslate"] }]
+            }
+        }));
+        assert_eq!(memory.read_json("WoahTest_BP/feature_rules/block_feature_rule.json").unwrap(), json!({
+            "format_version": "1.13.0",
+            "minecraft:feature_rules": {
+                "description": { "identifier": "woah:block_feature_rule", "places_feature": "woah:block_feature" },
+                "conditions": {
+                    "placement_pass": "underground_pass",
+                    "minecraft:biome_filter": { "test": "has_biome_tag", "value": "woah_crystal" }
+                },
+                "distribution": {
+                    "iterations": 8,
+                    "x": { "distribution": "uniform", "extent": [0, 16] },
+                    "y": { "distribution": "uniform", "extent": [-16, 48] },
+                    "z": { "distribution": "uniform", "extent": [0, 16] }
+                }
+            }
+        }));
+    }
+}
@@ -0,0 +1,31 @@
+use log::info;
+use std::sync::Arc;
+use crate::core::core_registry::CoreRegistry;
+use crate::worldgen::Feature;
+use crate::worldgen::ore::Ore;
+use crate::worldgen::rule::FeatureRule;
+
+/// The worldgen registry. Used for registering Features and the Feature rules placing them.
+pub struct WorldgenRegistry {
+    pub(crate) registry: Arc<CoreRegistry>
+}
+
+impl WorldgenRegistry {
+    pub fn register_feature(&self, feature: Feature) {
+        info!("@worldgen Registering feature {}", &feature.id);
+
+        self.registry.register_feature(feature);
+    }
+
+    pub fn register_feature_rule(&self, rule: FeatureRule) {
+        info!("@worldgen Registering feature rule {}", &rule.id);
+
+        self.registry.register_feature_rule(rule);
+    }
+
+    /// Registers both the feature and the feature rule of an ore.
+    pub fn register_ore(&self, ore: Ore) {
+        self.register_feature(ore.feature());
+        self.register_feature_rule(ore.feature_rule());
+    }
+}
@@ -0,0 +1,334 @@
+use std::ops::RangeInclusive;
+use derive_builder::Builder;
+use serde_json::{json, Map, Value};
+use crate::bedrock::BedrockSerializable;
+use crate::core::error::WoahResult;
+use crate::core::utilities::{Identifier, SemVer};
+use crate::core::validation::{check_molang, Diagnostics};
+use crate::entity::filter::EntityFilter;
+use crate::hold_builders;
+use crate::molang::ast::MolangExpr;
+use crate::molang::Molang;
+
+hold_builders!(FeatureRule, Distribution);
+
+/// Decides where a [Feature](crate::worldgen::Feature) generates, written to `BP/feature_rules/`.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct FeatureRule {
+    #[builder(default = "SemVer::new(1, 13, 0)")]
+    pub format_version: SemVer,
+    /// The file is named after the path of the identifier, so it has to be unique across namespaces.
+    pub id: Identifier,
+    pub places_feature: Identifier,
+    #[builder(default = "PlacementPass::Surface")]
+    pub placement_pass: PlacementPass,
+    /// The biomes the feature generates in, e.g. `has_biome_tag` tests. Generates in every biome when not set.
+    #[builder(setter(into, strip_option), default = "None")]
+    pub biome_filter: Option<EntityFilter>,
+    pub distribution: Distribution
+}
+
+/// How many times and at which offsets a feature is placed in a chunk. Shared by feature rules and scatter features.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into))]
+pub struct Distribution {
+    /// A number or a Molang expression, e.g. `"8"`.
+    pub iterations: Molang,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub scatter_chance: Option<ScatterChance>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub coordinate_eval_order: Option<CoordinateEvalOrder>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub x: Option<Coordinate>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub y: Option<Coordinate>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub z: Option<Coordinate>
+}
+
+/// The world generation pass a feature rule runs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlacementPass {
+    First,
+    BeforeUnderground,
+    Underground,
+    AfterUnderground,
+    BeforeSurface,
+    Surface,
+    AfterSurface,
+    BeforeLiquids,
+    Liquids,
+    AfterLiquids,
+    BeforeSky,
+    Sky,
+    AfterSky,
+    Final,
+    Pregeneration
+}
+
+/// A coordinate offset from the chunk origin, either a Molang expression or a random value within an extent.
+#[derive(Clone, Debug)]
+pub enum Coordinate {
+    Molang(Molang),
+    Distributed {
+        extent: RangeInclusive<i32>,
+        distribution: DistributionType,
+        /// Only used by the grid distributions.
+        step_size: Option<u32>,
+        /// Only used by the grid distributions.
+        grid_offset: Option<u32>
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistributionType {
+    Uniform,
+    Gaussian,
+    InverseGaussian,
+    Triangle,
+    FixedGrid,
+    JitteredGrid
+}
+
+/// The chance of the whole distribution running, either a Molang percentage or a fraction.
+#[derive(Clone, Debug)]
+pub enum ScatterChance {
+    Percent(Molang),
+    Fraction {
+        numerator: u32,
+        denominator: u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoordinateEvalOrder {
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx
+}
+
+impl FeatureRule {
+    /// The name of the feature rule file, `woah:ruby_ore_rule` is written to `feature_rules/ruby_ore_rule.json`.
+    pub fn file_name(&self) -> String {
+        format!("{}.json", self.id.path())
+    }
+}
+
+impl Distribution {
+    /// Every Molang statement of the distribution.
+    pub fn statements(&self) -> Vec<&Molang> {
+        let chance = match &self.scatter_chance {
+            Some(ScatterChance::Percent(molang)) => Some(molang),
+            _ => None
+        };
+        let coordinates = [&self.x, &self.y, &self.z].into_iter().flatten().filter_map(|c| match c {
+            Coordinate::Molang(molang) => Some(molang),
+            Coordinate::Distributed { .. } => None
+        });
+
+        std::iter::once(&self.iterations).chain(chance).chain(coordinates).collect()
+    }
+
+    pub(crate) fn validate(&self, diagnostics: &mut Diagnostics, subject: &Identifier) {
+        for statement in self.statements() {
+            check_molang(diagnostics, subject, statement);
+        }
+
+        for coordinate in [&self.x, &self.y, &self.z].into_iter().flatten() {
+            if let Coordinate::Distributed { extent, .. } = coordinate && extent.start() > extent.end() {
+                diagnostics.error(subject, format!("The distribution extent {:?} is empty", extent));
+            }
+        }
+    }
+}
+
+impl Coordinate {
+    pub fn distributed(distribution: DistributionType, extent: RangeInclusive<i32>) -> Self {
+        Coordinate::Distributed { extent, distribution, step_size: None, grid_offset: None }
+    }
+
+    pub fn uniform(extent: RangeInclusive<i32>) -> Self {
+        Self::distributed(DistributionType::Uniform, extent)
+    }
+
+    pub fn gaussian(extent: RangeInclusive<i32>) -> Self {
+        Self::distributed(DistributionType::Gaussian, extent)
+    }
+
+    pub fn triangle(extent: RangeInclusive<i32>) -> Self {
+        Self::distributed(DistributionType::Triangle, extent)
+    }
+}
+
+impl PlacementPass {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            PlacementPass::First => "first_pass",
+            PlacementPass::BeforeUnderground => "before_underground_pass",
+            PlacementPass::Underground => "underground_pass",
+            PlacementPass::AfterUnderground => "after_underground_pass",
+            PlacementPass::BeforeSurface => "before_surface_pass",
+            PlacementPass::Surface => "surface_pass",
+            PlacementPass::AfterSurface => "after_surface_pass",
+            PlacementPass::BeforeLiquids => "before_liquids_pass",
+            PlacementPass::Liquids => "liquids_pass",
+            PlacementPass::AfterLiquids => "after_liquids_pass",
+            PlacementPass::BeforeSky => "before_sky_pass",
+            PlacementPass::Sky => "sky_pass",
+            PlacementPass::AfterSky => "after_sky_pass",
+            PlacementPass::Final => "final_pass",
+            PlacementPass::Pregeneration => "pregeneration_pass"
+        }
+    }
+}
+
+impl DistributionType {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            DistributionType::Uniform => "uniform",
+            DistributionType::Gaussian => "gaussian",
+            DistributionType::InverseGaussian => "inverse_gaussian",
+            DistributionType::Triangle => "triangle",
+            DistributionType::FixedGrid => "fixed_grid",
+            DistributionType::JitteredGrid => "jittered_grid"
+        }
+    }
+}
+
+impl CoordinateEvalOrder {
+    pub fn render(&self) -> &'static str {
+        match &self {
+            CoordinateEvalOrder::Xyz => "xyz",
+            CoordinateEvalOrder::Xzy => "xzy",
+            CoordinateEvalOrder::Yxz => "yxz",
+            CoordinateEvalOrder::Yzx => "yzx",
+            CoordinateEvalOrder::Zxy => "zxy",
+            CoordinateEvalOrder::Zyx => "zyx"
+        }
+    }
+}
+
+impl From<i32> for Coordinate {
+    fn from(value: i32) -> Self {
+        Coordinate::Molang(Molang::from_expr(MolangExpr::Number(value as f64)))
+    }
+}
+
+impl From<&str> for Coordinate {
+    fn from(value: &str) -> Self {
+        Coordinate::Molang(value.into())
+    }
+}
+
+impl From<Molang> for Coordinate {
+    fn from(value: Molang) -> Self {
+        Coordinate::Molang(value)
+    }
+}
+
+impl From<RangeInclusive<i32>> for Coordinate {
+    fn from(value: RangeInclusive<i32>) -> Self {
+        Coordinate::uniform(value)
+    }
+}
+
+impl From<&str> for ScatterChance {
+    fn from(value: &str) -> Self {
+        ScatterChance::Percent(value.into())
+    }
+}
+
+impl From<Molang> for ScatterChance {
+    fn from(value: Molang) -> Self {
+        ScatterChance::Percent(value)
+    }
+}
+
+/// Constant values are written as numbers, everything else as Molang strings.
+fn numeric_value(molang: &Molang) -> Value {
+    match molang.expr() {
+        Ok(MolangExpr::Number(number)) if number.fract() == 0.0 => json!(*number as i64),
+        Ok(MolangExpr::Number(number)) => json!(number),
+        _ => Value::String(molang.render())
+    }
+}
+
+impl BedrockSerializable for Coordinate {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            Coordinate::Molang(molang) => numeric_value(molang),
+            Coordinate::Distributed { extent, distribution, step_size, grid_offset } => {
+                let mut coordinate = Map::new();
+                coordinate.insert("distribution".to_string(), Value::from(distribution.render()));
+                coordinate.insert("extent".to_string(), json!([extent.start(), extent.end()]));
+
+                if let Some(step_size) = step_size {
+                    coordinate.insert("step_size".to_string(), json!(step_size));
+                }
+                if let Some(grid_offset) = grid_offset {
+                    coordinate.insert("grid_offset".to_string(), json!(grid_offset));
+                }
+
+                Value::Object(coordinate)
+            }
+        })
+    }
+}
+
+impl BedrockSerializable for ScatterChance {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        Ok(match &self {
+            ScatterChance::Percent(molang) => Value::String(molang.render()),
+            ScatterChance::Fraction { numerator, denominator } => json!({ "numerator": numerator, "denominator": denominator })
+        })
+    }
+}
+
+impl BedrockSerializable for Distribution {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut distribution = Map::new();
+        distribution.insert("iterations".to_string(), numeric_value(&self.iterations));
+
+        if let Some(chance) = &self.scatter_chance {
+            distribution.insert("scatter_chance".to_string(), chance.bedrock_json()?);
+        }
+        if let Some(order) = self.coordinate_eval_order {
+            distribution.insert("coordinate_eval_order".to_string(), Value::from(order.render()));
+        }
+
+        for (key, coordinate) in [("x", &self.x), ("y", &self.y), ("z", &self.z)] {
+            if let Some(coordinate) = coordinate {
+                distribution.insert(key.to_string(), coordinate.bedrock_json()?);
+            }
+        }
+
+        Ok(Value::Object(distribution))
+    }
+}
+
+impl BedrockSerializable for FeatureRule {
+    fn bedrock_json(&self) -> WoahResult<Value> {
+        let mut conditions = Map::new();
+        conditions.insert("placement_pass".to_string(), Value::from(self.placement_pass.render()));
+
+        if let Some(filter) = &self.biome_filter {
+            conditions.insert("minecraft:biome_filter".to_string(), filter.bedrock_json()?);
+        }
+
+        Ok(json!({
+            "format_version": self.format_version.render_dotted(),
+            "minecraft:feature_rules": {
+                "description": {
+                    "identifier": self.id.render(),
+                    "places_feature": self.places_feature.render()
+                },
+                "conditions": conditions,
+                "distribution": self.distribution.bedrock_json()?
+            }
+        }))
+    }
+}